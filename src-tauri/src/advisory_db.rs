@@ -0,0 +1,394 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::types::{AdvisoryDbStatus, AdvisorySourceStatus};
+
+pub const ADVISORY_DB_ENV: &str = "RZONEHUB_ADVISORY_DB";
+
+// Layout do banco local:
+//   rustsec/            clone do github.com/rustsec/advisory-db
+//   osv/<Ecossistema>/  exports JSON do OSV (npm, PyPI, Go) + index.json por pacote
+//   metadata.json       data da última atualização de cada fonte
+pub const RUSTSEC_SOURCE: &str = "rustsec";
+pub const RUSTSEC_DIR: &str = "rustsec";
+pub const OSV_DIR: &str = "osv";
+pub const OSV_INDEX_FILE: &str = "index.json";
+pub const OSV_ECOSYSTEMS: &[&str] = &["npm", "PyPI", "Go"];
+
+const METADATA_FILE: &str = "metadata.json";
+const RUSTSEC_REPO: &str = "https://github.com/rustsec/advisory-db";
+const OSV_EXPORT_URL: &str = "https://osv-vulnerabilities.storage.googleapis.com";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+// Advisories do OSV têm poucos KB; entradas maiores que isso no zip são ignoradas
+const MAX_ADVISORY_SIZE: u64 = 5 * 1024 * 1024;
+
+pub fn default_dir() -> Option<PathBuf> {
+    std::env::var_os(ADVISORY_DB_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("RZOneHub").join("advisory-db")))
+}
+
+pub fn resolve_dir(path: Option<String>) -> Result<PathBuf> {
+    path.filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .or_else(default_dir)
+        .context("Não foi possível determinar o diretório do banco de vulnerabilidades")
+}
+
+// Normaliza o nome do pacote para busca no índice (PyPI ignora caixa e separadores)
+pub fn index_key(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "PyPI" {
+        name.to_lowercase().replace(['_', '.'], "-")
+    } else {
+        name.to_string()
+    }
+}
+
+fn read_metadata(dir: &Path) -> HashMap<String, String> {
+    fs::read_to_string(dir.join(METADATA_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_metadata(dir: &Path, metadata: &HashMap<String, String>) -> Result<()> {
+    let content = serde_json::to_string_pretty(metadata)?;
+    fs::write(dir.join(METADATA_FILE), content).context("Falha ao salvar metadata.json")
+}
+
+pub fn status(dir: &Path) -> AdvisoryDbStatus {
+    let metadata = read_metadata(dir);
+    let mut sources = vec![source_status(
+        RUSTSEC_SOURCE,
+        count_rustsec(&dir.join(RUSTSEC_DIR)),
+        &metadata,
+    )];
+    for ecosystem in OSV_ECOSYSTEMS {
+        let osv_dir = dir.join(OSV_DIR).join(ecosystem);
+        sources.push(source_status(ecosystem, count_osv(&osv_dir), &metadata));
+    }
+
+    AdvisoryDbStatus {
+        path: dir.display().to_string(),
+        sources,
+    }
+}
+
+fn source_status(
+    source: &str,
+    advisories: Option<usize>,
+    metadata: &HashMap<String, String>,
+) -> AdvisorySourceStatus {
+    AdvisorySourceStatus {
+        source: source.to_string(),
+        available: advisories.is_some(),
+        advisories: advisories.unwrap_or(0),
+        updated_at: metadata.get(source).cloned(),
+        error: None,
+    }
+}
+
+fn count_rustsec(dir: &Path) -> Option<usize> {
+    let crates = fs::read_dir(dir.join("crates")).ok()?;
+    Some(
+        crates
+            .filter_map(|entry| fs::read_dir(entry.ok()?.path()).ok())
+            .flat_map(|files| files.filter_map(|f| f.ok()))
+            .filter(|file| file.path().extension().is_some_and(|ext| ext == "md"))
+            .count(),
+    )
+}
+
+fn count_osv(dir: &Path) -> Option<usize> {
+    let files = fs::read_dir(dir).ok()?;
+    Some(
+        files
+            .filter_map(|f| f.ok())
+            .filter(|file| {
+                file.path().extension().is_some_and(|ext| ext == "json")
+                    && file.file_name() != OSV_INDEX_FILE
+            })
+            .count(),
+    )
+}
+
+// Atualiza as fontes pedidas (todas quando `sources` é None). Uma fonte com erro não
+// impede as demais; o erro vai no status dela.
+pub async fn refresh(dir: &Path, sources: Option<Vec<String>>) -> Result<AdvisoryDbStatus> {
+    fs::create_dir_all(dir).with_context(|| format!("Falha ao criar {}", dir.display()))?;
+
+    let wanted = |source: &str| {
+        sources
+            .as_ref()
+            .map(|list| list.iter().any(|s| s.eq_ignore_ascii_case(source)))
+            .unwrap_or(true)
+    };
+
+    let mut errors: HashMap<String, String> = HashMap::new();
+    let mut metadata = read_metadata(dir);
+
+    if wanted(RUSTSEC_SOURCE) {
+        let target = dir.join(RUSTSEC_DIR);
+        let result = tokio::task::spawn_blocking(move || refresh_rustsec(&target))
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+            .and_then(|result| result);
+        record_refresh(RUSTSEC_SOURCE, result, &mut metadata, &mut errors);
+    }
+
+    let client = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    for ecosystem in OSV_ECOSYSTEMS.iter().filter(|e| wanted(e)) {
+        let result = refresh_osv(&client, dir, ecosystem).await;
+        record_refresh(ecosystem, result, &mut metadata, &mut errors);
+    }
+
+    write_metadata(dir, &metadata)?;
+
+    let mut status = status(dir);
+    for source in &mut status.sources {
+        source.error = errors.remove(&source.source);
+    }
+    Ok(status)
+}
+
+fn record_refresh(
+    source: &str,
+    result: Result<()>,
+    metadata: &mut HashMap<String, String>,
+    errors: &mut HashMap<String, String>,
+) {
+    match result {
+        Ok(()) => {
+            log::info!("Banco de vulnerabilidades '{}' atualizado", source);
+            metadata.insert(source.to_string(), Utc::now().to_rfc3339());
+        }
+        Err(e) => {
+            log::warn!("Falha ao atualizar '{}': {:#}", source, e);
+            errors.insert(source.to_string(), format!("{:#}", e));
+        }
+    }
+}
+
+fn refresh_rustsec(target: &Path) -> Result<()> {
+    let target_str = target.to_string_lossy().to_string();
+    let output = if target.join(".git").exists() {
+        Command::new("git")
+            .args(["-C", &target_str, "pull", "--ff-only"])
+            .output()
+    } else {
+        Command::new("git")
+            .args(["clone", "--depth", "1", RUSTSEC_REPO, &target_str])
+            .output()
+    }
+    .context("Erro ao executar git. Certifique-se de que o Git está instalado.")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git falhou: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+// Baixa o all.zip do ecossistema e troca o diretório só depois de extrair tudo,
+// para que uma falha no meio não deixe o banco pela metade
+async fn refresh_osv(client: &Client, dir: &Path, ecosystem: &str) -> Result<()> {
+    let osv_dir = dir.join(OSV_DIR);
+    fs::create_dir_all(&osv_dir)?;
+    let archive = osv_dir.join(format!("{}.zip.part", ecosystem));
+
+    let mut response = client
+        .get(format!("{}/{}/all.zip", OSV_EXPORT_URL, ecosystem))
+        .send()
+        .await
+        .context("Falha ao conectar ao OSV")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Falha ao baixar export do OSV ({})", response.status());
+    }
+
+    let mut file = File::create(&archive)?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Falha ao baixar export do OSV")?
+    {
+        file.write_all(&chunk)?;
+    }
+    drop(file);
+
+    let target = osv_dir.join(ecosystem);
+    let ecosystem = ecosystem.to_string();
+    let extract_archive = archive.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        extract_osv_archive(&extract_archive, &target, &ecosystem)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!(e.to_string()))
+    .and_then(|result| result);
+
+    let _ = fs::remove_file(&archive);
+    result
+}
+
+fn extract_osv_archive(archive: &Path, target: &Path, ecosystem: &str) -> Result<()> {
+    let staging = target.with_extension("tmp");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let mut zip = zip::ZipArchive::new(File::open(archive)?).context("Arquivo zip inválido")?;
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for position in 0..zip.len() {
+        let entry = zip.by_index(position).context("Arquivo zip corrompido")?;
+        // Só o nome do arquivo é usado, então caminhos do zip nunca saem do staging
+        let Some(file_name) = entry
+            .enclosed_name()
+            .and_then(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        else {
+            continue;
+        };
+        if !entry.is_file() || !file_name.ends_with(".json") || file_name == OSV_INDEX_FILE {
+            continue;
+        }
+        if entry.size() > MAX_ADVISORY_SIZE {
+            log::warn!(
+                "Ignorando {} no export do OSV: {} bytes",
+                entry.name(),
+                entry.size()
+            );
+            continue;
+        }
+
+        let name = entry.name().to_string();
+        let mut content = Vec::new();
+        entry
+            .take(MAX_ADVISORY_SIZE)
+            .read_to_end(&mut content)
+            .with_context(|| format!("Falha ao extrair {}", name))?;
+        let Ok(advisory) = serde_json::from_slice::<Value>(&content) else {
+            continue;
+        };
+
+        let packages = advisory["affected"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|affected| affected["package"]["ecosystem"].as_str() == Some(ecosystem))
+            .filter_map(|affected| affected["package"]["name"].as_str());
+        for package in packages {
+            let files = index.entry(index_key(ecosystem, package)).or_default();
+            if !files.contains(&file_name) {
+                files.push(file_name.clone());
+            }
+        }
+
+        fs::write(staging.join(&file_name), content)?;
+    }
+
+    fs::write(staging.join(OSV_INDEX_FILE), serde_json::to_string(&index)?)?;
+
+    if target.exists() {
+        fs::remove_dir_all(target)?;
+    }
+    fs::rename(&staging, target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rzonehub-{}-{}-{}",
+            name,
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extract_osv_archive_indexes_advisories_and_stays_in_target() {
+        let dir = temp_dir("osv-extract");
+        let archive = dir.join("PyPI.zip");
+        let advisory = r#"{
+            "id": "PYSEC-2023-1",
+            "affected": [
+                { "package": { "ecosystem": "PyPI", "name": "Flask_Cors" } },
+                { "package": { "ecosystem": "npm", "name": "outro" } }
+            ]
+        }"#;
+
+        let mut writer = ZipWriter::new(File::create(&archive).unwrap());
+        for (name, method, content) in [
+            ("PYSEC-2023-1.json", CompressionMethod::Deflated, advisory),
+            ("nested/PYSEC-2023-2.json", CompressionMethod::Stored, "{}"),
+            ("../escape.json", CompressionMethod::Stored, "{}"),
+            ("README.txt", CompressionMethod::Stored, "texto"),
+            ("invalid.json", CompressionMethod::Stored, "{"),
+        ] {
+            let options = SimpleFileOptions::default().compression_method(method);
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let target = dir.join("osv").join("PyPI");
+        extract_osv_archive(&archive, &target, "PyPI").unwrap();
+
+        let index: BTreeMap<String, Vec<String>> =
+            serde_json::from_str(&fs::read_to_string(target.join(OSV_INDEX_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(
+            index,
+            BTreeMap::from([(
+                "flask-cors".to_string(),
+                vec!["PYSEC-2023-1.json".to_string()]
+            )])
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("PYSEC-2023-1.json")).unwrap(),
+            advisory
+        );
+        assert!(target.join("PYSEC-2023-2.json").is_file());
+        assert!(!target.join("README.txt").exists());
+        assert!(!target.join("invalid.json").exists());
+        assert!(!dir.join("escape.json").exists());
+        assert!(!dir.join("osv").join("escape.json").exists());
+        assert!(!target.with_extension("tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn extract_osv_archive_rejects_invalid_zip() {
+        let dir = temp_dir("osv-invalid");
+        let archive = dir.join("npm.zip");
+        fs::write(&archive, b"PK\x05\x06 truncado").unwrap();
+
+        let target = dir.join("npm");
+        assert!(extract_osv_archive(&archive, &target, "npm").is_err());
+        assert!(!target.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
 
-use crate::types::{ChatRequest, ChatResponse, CodeRequest, CodeResponse};
+use crate::mock_provider;
+use crate::prompt_templates;
+use crate::types::{ChatRequest, ChatResponse, CodeRequest, CodeResponse, TokenUsage};
 
 const OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
 const ANTHROPIC_CHAT_URL: &str = "https://api.anthropic.com/v1/messages";
@@ -13,7 +17,31 @@ const GROQ_CHAT_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
 const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
 const OLLAMA_CHAT_URL: &str = "http://localhost:11434/api/chat";
 
+const OPENAI_DEFAULT_MODEL: &str = "gpt-4o-mini";
+const ANTHROPIC_DEFAULT_MODEL: &str = "claude-3-5-sonnet-20241022";
+const GEMINI_DEFAULT_MODEL: &str = "gemini-1.5-flash";
+const COHERE_DEFAULT_MODEL: &str = "command-r-plus";
+const MISTRAL_DEFAULT_MODEL: &str = "mistral-large-latest";
+const GROQ_DEFAULT_MODEL: &str = "llama-3.3-70b-versatile";
+const DEEPSEEK_DEFAULT_MODEL: &str = "deepseek-chat";
+const OLLAMA_DEFAULT_MODEL: &str = "llama3.1";
+
 pub async fn generate_chat_response(request: ChatRequest) -> Result<ChatResponse> {
+    if request.provider == mock_provider::MOCK_PROVIDER {
+        return mock_provider::chat(&request).await;
+    }
+
+    let Some(record_dir) = mock_provider::recording_dir() else {
+        return dispatch_chat(request).await;
+    };
+
+    let recorded_request = request.clone();
+    let result = dispatch_chat(request).await;
+    record_exchange(&record_dir, &recorded_request, &result, &[]);
+    result
+}
+
+async fn dispatch_chat(request: ChatRequest) -> Result<ChatResponse> {
     let client = Client::new();
     
     match request.provider.as_str() {
@@ -27,15 +55,17 @@ pub async fn generate_chat_response(request: ChatRequest) -> Result<ChatResponse
         "ollama" => ollama_chat(&client, request).await,
         _ => Ok(ChatResponse {
             content: "Provedor de IA não suportado.".to_string(),
+            usage: None,
         }),
     }
 }
 
 pub async fn generate_code_snippet(request: CodeRequest) -> Result<CodeResponse> {
-    let prompt = format!(
-        "Gere um snippet de código em {} para: {}\n\nRetorne apenas o código, sem explicações.",
-        request.language, request.description
-    );
+    let variables = HashMap::from([
+        ("language".to_string(), request.language.clone()),
+        ("description".to_string(), request.description.clone()),
+    ]);
+    let prompt = prompt_templates::render_by_id("code_snippet", &variables)?;
 
     let chat_request = ChatRequest {
         provider: request.provider,
@@ -53,9 +83,337 @@ pub async fn generate_code_snippet(request: CodeRequest) -> Result<CodeResponse>
     })
 }
 
+pub fn default_model(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some(OPENAI_DEFAULT_MODEL),
+        "anthropic" => Some(ANTHROPIC_DEFAULT_MODEL),
+        "gemini" => Some(GEMINI_DEFAULT_MODEL),
+        "cohere" => Some(COHERE_DEFAULT_MODEL),
+        "mistral" => Some(MISTRAL_DEFAULT_MODEL),
+        "groq" => Some(GROQ_DEFAULT_MODEL),
+        "deepseek" => Some(DEEPSEEK_DEFAULT_MODEL),
+        "ollama" => Some(OLLAMA_DEFAULT_MODEL),
+        _ => None,
+    }
+}
+
+// Aceita os formatos de uso de tokens dos diferentes provedores
+fn parse_usage(value: &Value) -> Option<TokenUsage> {
+    let read = |keys: &[&str]| keys.iter().find_map(|key| value[*key].as_u64());
+
+    let prompt_tokens = read(&[
+        "prompt_tokens",
+        "input_tokens",
+        "promptTokenCount",
+        "prompt_eval_count",
+    ]);
+    let completion_tokens = read(&[
+        "completion_tokens",
+        "output_tokens",
+        "candidatesTokenCount",
+        "eval_count",
+    ]);
+
+    if prompt_tokens.is_none() && completion_tokens.is_none() {
+        return None;
+    }
+
+    let prompt_tokens = prompt_tokens.unwrap_or(0);
+    let completion_tokens = completion_tokens.unwrap_or(0);
+    let total_tokens =
+        read(&["total_tokens", "totalTokenCount"]).unwrap_or(prompt_tokens + completion_tokens);
+
+    Some(TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum StreamFormat {
+    OpenAi,
+    Anthropic,
+    Ollama,
+}
+
+// Envia a requisição em modo streaming, chamando `on_delta` a cada trecho recebido.
+// Gemini e Cohere ainda não têm streaming e entregam a resposta completa de uma vez.
+pub async fn stream_chat_response<F>(request: ChatRequest, mut on_delta: F) -> Result<ChatResponse>
+where
+    F: FnMut(&str) + Send,
+{
+    if request.provider == mock_provider::MOCK_PROVIDER {
+        return mock_provider::stream(&request, on_delta).await;
+    }
+
+    let Some(record_dir) = mock_provider::recording_dir() else {
+        return stream_provider_response(request, on_delta).await;
+    };
+
+    // Os trechos vão para a fixture, para a gravação ser reproduzida como stream
+    let recorded_request = request.clone();
+    let mut chunks = Vec::new();
+    let result = stream_provider_response(request, |delta| {
+        chunks.push(delta.to_string());
+        on_delta(delta)
+    })
+    .await;
+    record_exchange(&record_dir, &recorded_request, &result, &chunks);
+    result
+}
+
+fn record_exchange(
+    dir: &Path,
+    request: &ChatRequest,
+    result: &Result<ChatResponse>,
+    chunks: &[String],
+) {
+    match mock_provider::record_exchange(dir, request, result, chunks) {
+        Ok(path) => log::info!("Troca gravada como fixture: {}", path.display()),
+        Err(e) => log::warn!("Falha ao gravar fixture: {}", e),
+    }
+}
+
+async fn stream_provider_response<F>(request: ChatRequest, mut on_delta: F) -> Result<ChatResponse>
+where
+    F: FnMut(&str) + Send,
+{
+    let client = Client::new();
+    let provider = request.provider.clone();
+
+    let (builder, format) = match provider.as_str() {
+        "openai" | "mistral" | "groq" | "deepseek" => (
+            openai_compatible_stream_request(&client, request)?,
+            StreamFormat::OpenAi,
+        ),
+        "anthropic" => (
+            anthropic_stream_request(&client, request)?,
+            StreamFormat::Anthropic,
+        ),
+        "ollama" => (
+            ollama_stream_request(&client, request),
+            StreamFormat::Ollama,
+        ),
+        _ => {
+            let response = dispatch_chat(request).await?;
+            on_delta(&response.content);
+            return Ok(response);
+        }
+    };
+
+    let mut response = builder
+        .send()
+        .await
+        .with_context(|| format!("Falha ao enviar requisição para {}", provider))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(anyhow::anyhow!(
+            "{} API error ({}): {}",
+            provider,
+            status,
+            error_text
+        ));
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut content = String::new();
+    let mut usage: Option<TokenUsage> = None;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Falha ao ler stream da resposta")?
+    {
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(delta) = parse_stream_line(format, line.trim(), &mut usage)? {
+                content.push_str(&delta);
+                on_delta(&delta);
+            }
+        }
+    }
+
+    let rest = String::from_utf8_lossy(&buffer).to_string();
+    if let Some(delta) = parse_stream_line(format, rest.trim(), &mut usage)? {
+        content.push_str(&delta);
+        on_delta(&delta);
+    }
+
+    Ok(ChatResponse { content, usage })
+}
+
+fn parse_stream_line(
+    format: StreamFormat,
+    line: &str,
+    usage: &mut Option<TokenUsage>,
+) -> Result<Option<String>> {
+    let payload = match format {
+        StreamFormat::Ollama => line,
+        StreamFormat::OpenAi | StreamFormat::Anthropic => match line.strip_prefix("data:") {
+            Some(data) => data.trim(),
+            None => return Ok(None),
+        },
+    };
+
+    if payload.is_empty() || payload == "[DONE]" {
+        return Ok(None);
+    }
+
+    let data: Value = serde_json::from_str(payload).context("Falha ao parsear trecho do stream")?;
+
+    if let Some(error) = data.get("error") {
+        return Err(anyhow::anyhow!("Erro no stream: {}", error));
+    }
+
+    let delta = match format {
+        StreamFormat::OpenAi => {
+            if let Some(parsed) =
+                parse_usage(&data["usage"]).or_else(|| parse_usage(&data["x_groq"]["usage"]))
+            {
+                *usage = Some(parsed);
+            }
+            data["choices"][0]["delta"]["content"].as_str()
+        }
+        StreamFormat::Anthropic => match data["type"].as_str() {
+            Some("message_start") => {
+                *usage = parse_usage(&data["message"]["usage"]);
+                None
+            }
+            Some("message_delta") => {
+                let current = usage.get_or_insert_with(TokenUsage::default);
+                if let Some(output_tokens) = data["usage"]["output_tokens"].as_u64() {
+                    current.completion_tokens = output_tokens;
+                    current.total_tokens = current.prompt_tokens + output_tokens;
+                }
+                None
+            }
+            Some("content_block_delta") => data["delta"]["text"].as_str(),
+            _ => None,
+        },
+        StreamFormat::Ollama => {
+            if data["done"].as_bool() == Some(true) {
+                *usage = parse_usage(&data);
+            }
+            data["message"]["content"].as_str()
+        }
+    };
+
+    Ok(delta.filter(|d| !d.is_empty()).map(|d| d.to_string()))
+}
+
+fn chat_messages(request: &ChatRequest) -> Vec<Value> {
+    let mut messages: Vec<Value> = request
+        .history
+        .iter()
+        .map(|msg| {
+            json!({
+                "role": msg.role,
+                "content": msg.content
+            })
+        })
+        .collect();
+
+    messages.push(json!({
+        "role": "user",
+        "content": request.prompt
+    }));
+
+    messages
+}
+
+fn openai_compatible_stream_request(
+    client: &Client,
+    request: ChatRequest,
+) -> Result<RequestBuilder> {
+    let (url, name) = match request.provider.as_str() {
+        "openai" => (OPENAI_CHAT_URL, "OpenAI"),
+        "mistral" => (MISTRAL_CHAT_URL, "Mistral"),
+        "groq" => (GROQ_CHAT_URL, "Groq"),
+        _ => (DEEPSEEK_CHAT_URL, "DeepSeek"),
+    };
+
+    let api_key = request
+        .api_key
+        .clone()
+        .with_context(|| format!("{} API key não fornecida", name))?;
+    let model = request
+        .model
+        .clone()
+        .or_else(|| default_model(&request.provider).map(|m| m.to_string()));
+
+    let mut body = json!({
+        "model": model,
+        "messages": chat_messages(&request),
+        "stream": true
+    });
+
+    // Somente OpenAI e DeepSeek aceitam pedir o uso de tokens no último trecho
+    if matches!(request.provider.as_str(), "openai" | "deepseek") {
+        body["stream_options"] = json!({ "include_usage": true });
+    }
+
+    Ok(client
+        .post(url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&body))
+}
+
+fn anthropic_stream_request(client: &Client, request: ChatRequest) -> Result<RequestBuilder> {
+    let api_key = request
+        .api_key
+        .clone()
+        .context("Anthropic API key não fornecida")?;
+    let model = request
+        .model
+        .clone()
+        .unwrap_or_else(|| ANTHROPIC_DEFAULT_MODEL.to_string());
+
+    let body = json!({
+        "model": model,
+        "messages": chat_messages(&request),
+        "max_tokens": 4096,
+        "stream": true
+    });
+
+    Ok(client
+        .post(ANTHROPIC_CHAT_URL)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("Content-Type", "application/json")
+        .json(&body))
+}
+
+fn ollama_stream_request(client: &Client, request: ChatRequest) -> RequestBuilder {
+    let model = request
+        .model
+        .clone()
+        .unwrap_or_else(|| OLLAMA_DEFAULT_MODEL.to_string());
+
+    let body = json!({
+        "model": model,
+        "messages": chat_messages(&request),
+        "stream": true
+    });
+
+    client
+        .post(OLLAMA_CHAT_URL)
+        .header("Content-Type", "application/json")
+        .json(&body)
+}
+
 async fn openai_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("OpenAI API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "gpt-4o-mini".to_string());
+    let model = request.model.unwrap_or_else(|| OPENAI_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -95,12 +453,15 @@ async fn openai_chat(client: &Client, request: ChatRequest) -> Result<ChatRespon
         .context("Resposta inválida da OpenAI")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
 }
 
 async fn anthropic_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("Anthropic API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "claude-3-5-sonnet-20241022".to_string());
+    let model = request.model.unwrap_or_else(|| ANTHROPIC_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -141,12 +502,15 @@ async fn anthropic_chat(client: &Client, request: ChatRequest) -> Result<ChatRes
         .context("Resposta inválida da Anthropic")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
 }
 
 async fn gemini_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("Gemini API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "gemini-1.5-flash".to_string());
+    let model = request.model.unwrap_or_else(|| GEMINI_DEFAULT_MODEL.to_string());
 
     let url = format!("{}{}:generateContent?key={}", GEMINI_CHAT_URL, model, api_key);
 
@@ -177,12 +541,15 @@ async fn gemini_chat(client: &Client, request: ChatRequest) -> Result<ChatRespon
         .context("Resposta inválida do Gemini")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usageMetadata"]),
+    })
 }
 
 async fn cohere_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("Cohere API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "command-r-plus".to_string());
+    let model = request.model.unwrap_or_else(|| COHERE_DEFAULT_MODEL.to_string());
 
     let chat_history: Vec<Value> = request
         .history
@@ -217,12 +584,15 @@ async fn cohere_chat(client: &Client, request: ChatRequest) -> Result<ChatRespon
         .context("Resposta inválida da Cohere")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["meta"]["billed_units"]),
+    })
 }
 
 async fn mistral_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("Mistral API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "mistral-large-latest".to_string());
+    let model = request.model.unwrap_or_else(|| MISTRAL_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -261,12 +631,15 @@ async fn mistral_chat(client: &Client, request: ChatRequest) -> Result<ChatRespo
         .context("Resposta inválida da Mistral")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
 }
 
 async fn groq_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("Groq API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "llama-3.3-70b-versatile".to_string());
+    let model = request.model.unwrap_or_else(|| GROQ_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -313,12 +686,15 @@ async fn groq_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse
         .context("Resposta inválida da Groq")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
 }
 
 async fn deepseek_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
     let api_key = request.api_key.context("DeepSeek API key não fornecida")?;
-    let model = request.model.unwrap_or_else(|| "deepseek-chat".to_string());
+    let model = request.model.unwrap_or_else(|| DEEPSEEK_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -381,11 +757,14 @@ async fn deepseek_chat(client: &Client, request: ChatRequest) -> Result<ChatResp
         ));
     };
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
 }
 
 async fn ollama_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
-    let model = request.model.unwrap_or_else(|| "llama3.1".to_string());
+    let model = request.model.unwrap_or_else(|| OLLAMA_DEFAULT_MODEL.to_string());
 
     let mut messages: Vec<Value> = request
         .history
@@ -419,10 +798,21 @@ async fn ollama_chat(client: &Client, request: ChatRequest) -> Result<ChatRespon
 
     let data: Value = response.json().await.context("Falha ao parsear resposta")?;
 
+    if let Some(error) = data["error"].as_str() {
+        return Err(anyhow::anyhow!(
+            "Ollama API error: {}. Verifique se o modelo '{}' foi baixado.",
+            error,
+            model
+        ));
+    }
+
     let content = data["message"]["content"]
         .as_str()
         .context("Resposta inválida do Ollama")?
         .to_string();
 
-    Ok(ChatResponse { content })
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data),
+    })
 }
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Registro das análises em segundo plano, com a flag de cancelamento de cada job
+#[derive(Default)]
+pub struct AnalysisJobs {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    counter: AtomicU64,
+}
+
+impl AnalysisJobs {
+    pub fn start(&self) -> (String, Arc<AtomicBool>) {
+        let sequence = self.counter.fetch_add(1, Ordering::Relaxed);
+        let job_id = format!(
+            "analysis-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            sequence
+        );
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job_id.clone(), Arc::clone(&cancel));
+
+        (job_id, cancel)
+    }
+
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.jobs.lock().unwrap().get(job_id) {
+            Some(cancel) => {
+                cancel.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self, job_id: &str) {
+        self.jobs.lock().unwrap().remove(job_id);
+    }
+}
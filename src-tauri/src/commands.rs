@@ -1,8 +1,34 @@
+use crate::advisory_db;
 use crate::ai_client;
-use crate::project_analyzer::analyze_project;
-use crate::types::{ChatRequest, ChatResponse, CodeRequest, CodeResponse, ProjectAnalysis};
+use crate::analysis_jobs::AnalysisJobs;
+use crate::connection_diagnostics;
+use crate::ignore_rules::IgnoreRules;
+use crate::ollama;
+use crate::project_analyzer::{
+    analysis_snapshots, analyze_project, analyze_project_with,
+    audit_dependencies as run_dependency_audit, build_module_graph,
+    code_metrics as run_code_metrics, debt_markers, diff_analyses as run_analysis_diff,
+    export_analysis as run_analysis_export, find_duplicates, git_analytics,
+    invalidate_analysis_cache as run_cache_invalidation, license_report,
+    render_license_report, render_module_graph, save_analysis_snapshot,
+    scan_secrets as run_secret_scan,
+    suggestion_rules, AnalysisControl, Cancelled,
+};
+use crate::prompt_templates;
+use crate::provider_comparison;
+use crate::symbol_index::SymbolIndex;
+use crate::types::{
+    AdvisoryDbStatus, AnalysisDiff, AnalysisJobResult, AnalysisProgress, ChatMessage, ChatRequest, ChatResponse, CodeMetrics, CodeRequest, Dependency,
+    CodeResponse, ComparisonDelta, ComparisonTarget, ComparisonUpdate, ConnectionDiagnostics,
+    DuplicateReport, GitAnalytics, LicensePolicy, LicenseReport, MarkerFilter, ModuleGraph,
+    OllamaModel, OllamaModelInfo, OllamaStatus, ProjectAnalysis, PromptTemplate,
+    ProviderComparison, SecretFinding, SnapshotInfo, SuggestionRuleInfo, SymbolIndexStatus,
+    SymbolInfo, TechDebtReport, TemplateRunRequest, VulnerabilityAudit,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 #[tauri::command]
 pub async fn chat_with_ai(request: ChatRequest) -> Result<ChatResponse, String> {
@@ -24,28 +50,533 @@ pub async fn generate_code(request: CodeRequest) -> Result<CodeResponse, String>
 }
 
 #[tauri::command]
-pub fn analyze_project_structure(path: Option<String>) -> Result<ProjectAnalysis, String> {
+pub async fn compare_providers(
+    app: AppHandle,
+    prompt: String,
+    targets: Vec<ComparisonTarget>,
+    history: Option<Vec<ChatMessage>>,
+) -> Result<ProviderComparison, String> {
+    if targets.is_empty() {
+        return Err("Informe ao menos um provedor para comparar".to_string());
+    }
+
+    let comparison_id = format!("cmp-{}", chrono::Utc::now().timestamp_millis());
+    log::info!("Comparando {} provedores ({})", targets.len(), comparison_id);
+
+    let delta_app = app.clone();
+    let delta_id = comparison_id.clone();
+    let result_app = app;
+    let result_id = comparison_id.clone();
+
+    let comparison = provider_comparison::compare_providers(
+        comparison_id,
+        prompt,
+        history.unwrap_or_default(),
+        targets,
+        move |target_index, delta| {
+            let _ = delta_app.emit(
+                "compare-providers-delta",
+                ComparisonDelta {
+                    comparison_id: delta_id.clone(),
+                    target_index,
+                    delta: delta.to_string(),
+                },
+            );
+        },
+        move |result| {
+            let _ = result_app.emit(
+                "compare-providers-result",
+                ComparisonUpdate {
+                    comparison_id: result_id.clone(),
+                    result: result.clone(),
+                },
+            );
+        },
+    )
+    .await;
+
+    Ok(comparison)
+}
+
+#[tauri::command]
+pub fn list_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    Ok(prompt_templates::list_templates())
+}
+
+#[tauri::command]
+pub fn render_prompt_template(
+    template_id: String,
+    variables: HashMap<String, String>,
+) -> Result<String, String> {
+    prompt_templates::render_by_id(&template_id, &variables).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_prompt_template(request: TemplateRunRequest) -> Result<ChatResponse, String> {
+    log::info!("Executando template de prompt: {}", request.template_id);
+
+    let template = prompt_templates::find_template(&request.template_id).map_err(|e| e.to_string())?;
+    let mut variables = request.variables;
+
+    // Preenche o resumo do projeto automaticamente quando o template precisar dele
+    if template.variables.iter().any(|v| v == "project_summary")
+        && !variables.contains_key("project_summary")
+    {
+        let project_path = request.project_path.clone();
+        let analysis = tokio::task::spawn_blocking(move || analyze_project(project_path))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        variables.insert("project_summary".to_string(), analysis.summary);
+    }
+
+    let prompt = prompt_templates::render(&template, &variables).map_err(|e| e.to_string())?;
+
+    ai_client::generate_chat_response(ChatRequest {
+        provider: request.provider,
+        api_key: request.api_key,
+        prompt,
+        history: vec![],
+        model: request.model,
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn analyze_project_structure(path: Option<String>) -> Result<ProjectAnalysis, String> {
     log::info!("Analyzing project at path: {:?}", path);
-    analyze_project(path).map_err(|e| {
+
+    // Roda fora da thread principal para não travar a interface
+    tokio::task::spawn_blocking(move || {
+        let analysis = analyze_project(path.clone())?;
+        record_snapshot(path, &analysis);
+        anyhow::Ok(analysis)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
         log::error!("Project analysis failed: {}", e);
         e.to_string()
     })
 }
 
 #[tauri::command]
-pub async fn test_api_connection(provider: String, api_key: String) -> Result<bool, String> {
-    let request = ChatRequest {
-        provider,
-        api_key: Some(api_key),
-        prompt: "Hello".to_string(),
-        history: vec![],
-        model: None,
-    };
+pub fn start_project_analysis(
+    app: AppHandle,
+    jobs: State<'_, AnalysisJobs>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let (job_id, cancel) = jobs.start();
+    log::info!(
+        "Iniciando análise em segundo plano {} em {:?}",
+        job_id,
+        path
+    );
+
+    let task_job_id = job_id.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let job_id = task_job_id;
+        let progress_app = app.clone();
+        let progress_job_id = job_id.clone();
+        let on_progress = move |progress: &AnalysisProgress| {
+            let mut progress = progress.clone();
+            progress.job_id = Some(progress_job_id.clone());
+            let _ = progress_app.emit("project-analysis-progress", progress);
+        };
+
+        let control = AnalysisControl::new(&cancel, &on_progress);
+        let result = analyze_project_with(path.clone(), &control);
+        if let Ok(analysis) = &result {
+            record_snapshot(path, analysis);
+        }
+        app.state::<AnalysisJobs>().finish(&job_id);
+
+        let event = match result {
+            Ok(analysis) => AnalysisJobResult {
+                job_id,
+                analysis: Some(analysis),
+                error: None,
+                cancelled: false,
+            },
+            Err(e) => {
+                let cancelled = e.downcast_ref::<Cancelled>().is_some();
+                if !cancelled {
+                    log::error!("Project analysis failed: {}", e);
+                }
+                AnalysisJobResult {
+                    job_id,
+                    analysis: None,
+                    error: Some(e.to_string()),
+                    cancelled,
+                }
+            }
+        };
+
+        let _ = app.emit("project-analysis-finished", event);
+    });
+
+    Ok(job_id)
+}
+
+// Sem o snapshot a análise continua valendo; só fica fora do histórico
+fn record_snapshot(path: Option<String>, analysis: &ProjectAnalysis) {
+    if let Err(e) = save_analysis_snapshot(path, analysis) {
+        log::warn!("Não foi possível gravar o snapshot da análise: {:#}", e);
+    }
+}
+
+#[tauri::command]
+pub fn cancel_project_analysis(
+    jobs: State<'_, AnalysisJobs>,
+    job_id: String,
+) -> Result<bool, String> {
+    log::info!("Cancelando análise {}", job_id);
+    Ok(jobs.cancel(&job_id))
+}
+
+#[tauri::command]
+pub async fn audit_dependencies(
+    path: String,
+    database_path: Option<String>,
+) -> Result<VulnerabilityAudit, String> {
+    let db_dir = advisory_db::resolve_dir(database_path).map_err(|e| e.to_string())?;
+    log::info!("Auditando dependências de {} com o banco {:?}", path, db_dir);
+
+    tokio::task::spawn_blocking(move || run_dependency_audit(Path::new(&path), &db_dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn refresh_advisory_db(
+    database_path: Option<String>,
+    sources: Option<Vec<String>>,
+) -> Result<AdvisoryDbStatus, String> {
+    let db_dir = advisory_db::resolve_dir(database_path).map_err(|e| e.to_string())?;
+    log::info!("Atualizando banco de vulnerabilidades em {:?}", db_dir);
+
+    advisory_db::refresh(&db_dir, sources)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn advisory_db_status(database_path: Option<String>) -> Result<AdvisoryDbStatus, String> {
+    let db_dir = advisory_db::resolve_dir(database_path).map_err(|e| e.to_string())?;
+    Ok(advisory_db::status(&db_dir))
+}
+
+// Sem `path` apaga o cache de todos os workspaces; devolve quantos caches foram removidos
+#[tauri::command]
+pub fn invalidate_analysis_cache(path: Option<String>) -> Result<usize, String> {
+    log::info!(
+        "Invalidando cache de análise de {}",
+        path.as_deref().unwrap_or("todos os workspaces")
+    );
+
+    run_cache_invalidation(path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+}
+
+// Regras de sugestão (embutidas e do usuário) com o estado delas no projeto
+#[tauri::command]
+pub fn list_suggestion_rules(path: Option<String>) -> Vec<SuggestionRuleInfo> {
+    suggestion_rules(path.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+pub async fn scan_secrets(path: String) -> Result<Vec<SecretFinding>, String> {
+    log::info!("Procurando segredos em {}", path);
+
+    tokio::task::spawn_blocking(move || run_secret_scan(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn analyze_code_metrics(
+    path: String,
+    limit: Option<usize>,
+) -> Result<CodeMetrics, String> {
+    log::info!("Calculando métricas de complexidade de {}", path);
+
+    tokio::task::spawn_blocking(move || run_code_metrics(Path::new(&path), limit.unwrap_or(50)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_duplicate_code(
+    path: String,
+    min_tokens: Option<usize>,
+    normalize_identifiers: Option<bool>,
+    limit: Option<usize>,
+) -> Result<DuplicateReport, String> {
+    log::info!("Procurando código duplicado em {}", path);
+
+    tokio::task::spawn_blocking(move || {
+        find_duplicates(
+            Path::new(&path),
+            min_tokens.unwrap_or(50),
+            normalize_identifiers.unwrap_or(false),
+            limit.unwrap_or(100),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_debt_markers(
+    path: String,
+    filter: Option<MarkerFilter>,
+) -> Result<TechDebtReport, String> {
+    log::info!("Procurando marcadores de dívida técnica em {}", path);
+
+    tokio::task::spawn_blocking(move || {
+        debt_markers(Path::new(&path), &filter.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_analysis_snapshots(path: String) -> Result<Vec<SnapshotInfo>, String> {
+    tokio::task::spawn_blocking(move || analysis_snapshots(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Compara dois snapshots pelo id; `a` é o ponto de partida e `b` o estado mais novo
+#[tauri::command]
+pub async fn diff_analyses(a: String, b: String) -> Result<AnalysisDiff, String> {
+    log::info!("Comparando análises {} e {}", a, b);
+
+    tokio::task::spawn_blocking(move || run_analysis_diff(&a, &b))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
 
-    match ai_client::generate_chat_response(request).await {
-        Ok(_) => Ok(true),
-        Err(e) => Err(e.to_string()),
+// Grava o relatório da análise em `output` como "json", "markdown", "html" ou "sarif".
+// Sem `analysis` usa o snapshot mais recente do workspace; `metrics` é o resultado de
+// `code_metrics`, quando a tela de complexidade já o carregou
+#[tauri::command]
+pub async fn export_analysis(
+    path: String,
+    format: String,
+    output: String,
+    analysis: Option<ProjectAnalysis>,
+    metrics: Option<CodeMetrics>,
+) -> Result<(), String> {
+    log::info!("Exportando análise de {} como {} em {}", path, format, output);
+
+    tokio::task::spawn_blocking(move || {
+        run_analysis_export(
+            Path::new(&path),
+            analysis,
+            metrics.as_ref(),
+            &format,
+            Path::new(&output),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn analyze_licenses(
+    path: String,
+    policy: Option<LicensePolicy>,
+    dependencies: Option<Vec<Dependency>>,
+) -> Result<LicenseReport, String> {
+    log::info!("Analisando licenças de {}", path);
+
+    tokio::task::spawn_blocking(move || license_report(Path::new(&path), dependencies, policy))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Devolve o relatório de licenças como texto em "json" ou "markdown". Nos dois comandos,
+// `dependencies` (as da análise já feita) evita ler os manifestos de novo
+#[tauri::command]
+pub async fn export_license_report(
+    path: String,
+    format: String,
+    policy: Option<LicensePolicy>,
+    dependencies: Option<Vec<Dependency>>,
+) -> Result<String, String> {
+    log::info!("Exportando relatório de licenças de {} como {}", path, format);
+
+    tokio::task::spawn_blocking(move || {
+        let report = license_report(Path::new(&path), dependencies, policy)?;
+        render_license_report(&report, &format)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn analyze_git_history(
+    path: String,
+    days: Option<u32>,
+    limit: Option<usize>,
+) -> Result<GitAnalytics, String> {
+    log::info!("Analisando histórico do git de {}", path);
+
+    tokio::task::spawn_blocking(move || {
+        git_analytics(Path::new(&path), days.unwrap_or(90), limit.unwrap_or(50))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn analyze_module_graph(path: String) -> Result<ModuleGraph, String> {
+    log::info!("Montando grafo de módulos de {}", path);
+
+    tokio::task::spawn_blocking(move || build_module_graph(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Devolve o grafo como texto em "json", "dot" (Graphviz) ou "mermaid"
+#[tauri::command]
+pub async fn export_module_graph(path: String, format: String) -> Result<String, String> {
+    log::info!("Exportando grafo de módulos de {} como {}", path, format);
+
+    tokio::task::spawn_blocking(move || {
+        let graph = build_module_graph(Path::new(&path))?;
+        render_module_graph(&graph, &format)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+// Indexa os símbolos do workspace e passa a acompanhar mudanças nos arquivos
+#[tauri::command]
+pub async fn index_symbols(app: AppHandle, path: String) -> Result<SymbolIndexStatus, String> {
+    log::info!("Indexando símbolos de {}", path);
+
+    tokio::task::spawn_blocking(move || app.state::<SymbolIndex>().index(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Busca fuzzy por nome; sem `path`, procura em todos os workspaces indexados
+#[tauri::command]
+pub async fn find_symbol(
+    app: AppHandle,
+    query: String,
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SymbolInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        app.state::<SymbolIndex>().find_symbol(
+            &query,
+            path.as_deref().map(Path::new),
+            limit.unwrap_or(50),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_definition(
+    app: AppHandle,
+    name: String,
+    file: String,
+) -> Result<Vec<SymbolInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        app.state::<SymbolIndex>().find_definition(&name, Path::new(&file))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn test_api_connection(
+    provider: String,
+    api_key: String,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Result<ConnectionDiagnostics, String> {
+    log::info!("Diagnosticando conexão - Provider: {}, base URL: {:?}", provider, base_url);
+
+    let diagnostics =
+        connection_diagnostics::diagnose(&provider, Some(api_key), base_url, model).await;
+
+    if !diagnostics.success {
+        log::warn!("Diagnóstico de conexão falhou: {}", diagnostics.message);
     }
+
+    Ok(diagnostics)
+}
+
+// ===== Ollama =====
+
+#[tauri::command]
+pub async fn ollama_status(base_url: Option<String>) -> Result<OllamaStatus, String> {
+    Ok(ollama::status(base_url.as_deref()).await)
+}
+
+#[tauri::command]
+pub async fn ollama_list_models(base_url: Option<String>) -> Result<Vec<OllamaModel>, String> {
+    ollama::list_models(base_url.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn ollama_pull_model(
+    app: AppHandle,
+    model: String,
+    base_url: Option<String>,
+) -> Result<(), String> {
+    log::info!("Baixando modelo do Ollama: {}", model);
+
+    ollama::pull_model(base_url.as_deref(), &model, |progress| {
+        let _ = app.emit("ollama-pull-progress", progress);
+    })
+    .await
+    .map_err(|e| {
+        log::error!("Falha ao baixar modelo {}: {}", model, e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn ollama_delete_model(model: String, base_url: Option<String>) -> Result<(), String> {
+    log::info!("Removendo modelo do Ollama: {}", model);
+
+    ollama::delete_model(base_url.as_deref(), &model)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn ollama_show_model(
+    model: String,
+    base_url: Option<String>,
+) -> Result<OllamaModelInfo, String> {
+    ollama::show_model(base_url.as_deref(), &model)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // GitHub integration placeholder
@@ -150,30 +681,18 @@ pub fn list_directory(path: Option<String>) -> Result<DirectoryListing, String>
 }
 
 // Carrega apenas um nível de profundidade
-fn read_directory_shallow(path: &PathBuf, files: &mut Vec<FileNode>) -> Result<(), String> {
-    use std::fs;
+fn read_directory_shallow(path: &Path, files: &mut Vec<FileNode>) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Erro ao ler diretório: {} não é um diretório", path.display()));
+    }
 
-    let entries = fs::read_dir(path)
-        .map_err(|e| format!("Erro ao ler diretório: {}", e))?;
+    // Usa as mesmas regras de ignore da análise de projeto
+    let ignore_rules = IgnoreRules::for_path(path);
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Erro ao ler entrada: {}", e))?;
-        let file_path = entry.path();
+    for entry in ignore_rules.list_dir(path) {
+        let file_path = entry.path().to_path_buf();
         let file_name = entry.file_name().to_string_lossy().to_string();
 
-        // Ignora arquivos/pastas ocultos e pastas pesadas
-        if file_name.starts_with('.') || 
-           file_name == "node_modules" || 
-           file_name == "target" ||
-           file_name == "dist" ||
-           file_name == "build" ||
-           file_name == ".git" ||
-           file_name == "vendor" ||
-           file_name == "__pycache__" ||
-           file_name == "RZ-One" {
-            continue;
-        }
-
         let is_directory = file_path.is_dir();
         let node = FileNode {
             name: file_name,
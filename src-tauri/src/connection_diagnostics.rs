@@ -0,0 +1,350 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Url};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ai_client;
+use crate::mock_provider;
+use crate::ollama;
+use crate::types::{ChatRequest, CheckStatus, ConnectionDiagnostics, DiagnosticCheck};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const RATE_LIMIT_HEADER_PREFIXES: &[&str] = &[
+    "x-ratelimit-",
+    "ratelimit-",
+    "anthropic-ratelimit-",
+    "retry-after",
+];
+
+pub fn default_base_url(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some("https://api.openai.com/v1"),
+        "anthropic" => Some("https://api.anthropic.com/v1"),
+        "gemini" => Some("https://generativelanguage.googleapis.com/v1beta"),
+        "cohere" => Some("https://api.cohere.ai/v1"),
+        "mistral" => Some("https://api.mistral.ai/v1"),
+        "groq" => Some("https://api.groq.com/openai/v1"),
+        "deepseek" => Some("https://api.deepseek.com"),
+        "ollama" => Some(ollama::OLLAMA_BASE_URL),
+        _ => None,
+    }
+}
+
+fn check(
+    status: CheckStatus,
+    detail: impl Into<String>,
+    started: Option<Instant>,
+) -> DiagnosticCheck {
+    DiagnosticCheck {
+        status,
+        detail: detail.into(),
+        duration_ms: started.map(|s| s.elapsed().as_millis() as u64),
+    }
+}
+
+fn skipped(detail: &str) -> DiagnosticCheck {
+    check(CheckStatus::Skipped, detail, None)
+}
+
+// Diagnostica a conexão com um provedor sem gastar tokens: resolve DNS, abre a conexão
+// (TLS quando https) e consulta o endpoint gratuito de listagem de modelos.
+pub async fn diagnose(
+    provider: &str,
+    api_key: Option<String>,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> ConnectionDiagnostics {
+    let model = model.or_else(|| ai_client::default_model(provider).map(|m| m.to_string()));
+
+    if provider == mock_provider::MOCK_PROVIDER {
+        return diagnose_mock(model);
+    }
+
+    let base_url = base_url
+        .filter(|url| !url.trim().is_empty())
+        .or_else(|| default_base_url(provider).map(|url| url.to_string()))
+        .unwrap_or_default();
+
+    let mut diagnostics = ConnectionDiagnostics {
+        success: false,
+        message: String::new(),
+        provider: provider.to_string(),
+        base_url: base_url.clone(),
+        model: model.clone(),
+        dns: skipped("Não verificado"),
+        tls: skipped("Não verificado"),
+        auth: skipped("Não verificado"),
+        model_available: skipped("Não verificado"),
+        latency_ms: None,
+        rate_limits: HashMap::new(),
+        available_models: Vec::new(),
+    };
+
+    let url = match Url::parse(base_url.trim_end_matches('/')) {
+        Ok(url) if url.host_str().is_some() => url,
+        _ => {
+            diagnostics.message = format!(
+                "URL base inválida para o provedor '{}': '{}'",
+                provider, base_url
+            );
+            return diagnostics;
+        }
+    };
+
+    // DNS
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let started = Instant::now();
+    match tokio::net::lookup_host((host.as_str(), port)).await {
+        Ok(addresses) => {
+            let addresses: Vec<String> = addresses.map(|a| a.ip().to_string()).collect();
+            diagnostics.dns = check(CheckStatus::Ok, addresses.join(", "), Some(started));
+        }
+        Err(e) => {
+            diagnostics.dns = check(CheckStatus::Failed, e.to_string(), Some(started));
+            diagnostics.message = format!("Não foi possível resolver {}: {}", host, e);
+            return diagnostics;
+        }
+    }
+
+    // Conexão/TLS + autenticação via listagem de modelos
+    let client = match Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            diagnostics.message = format!("Falha ao criar cliente HTTP: {}", e);
+            return diagnostics;
+        }
+    };
+
+    let api_key = api_key.filter(|key| !key.trim().is_empty());
+
+    let started = Instant::now();
+    let response = models_request(&client, provider, &url, api_key.as_deref())
+        .send()
+        .await;
+    let latency = started.elapsed().as_millis() as u64;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            // Sem a URL para não vazar a chave do Gemini, que vai na query string
+            let timed_out = e.is_timeout();
+            let detail = error_chain(&e.without_url());
+            diagnostics.tls = check(CheckStatus::Failed, detail.clone(), Some(started));
+            diagnostics.message = if timed_out {
+                format!("Tempo esgotado ao conectar em {}", host)
+            } else if provider == "ollama" {
+                format!("Não foi possível conectar ao Ollama em {}. Verifique se o servidor está rodando.", base_url)
+            } else {
+                format!("Falha na conexão com {}: {}", host, detail)
+            };
+            return diagnostics;
+        }
+    };
+
+    diagnostics.latency_ms = Some(latency);
+    diagnostics.tls = if url.scheme() == "https" {
+        check(CheckStatus::Ok, "Conexão TLS estabelecida", Some(started))
+    } else {
+        check(CheckStatus::Skipped, "Conexão HTTP sem TLS", Some(started))
+    };
+    diagnostics.rate_limits = rate_limit_headers(response.headers());
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if status.is_success() || status.as_u16() == 429 {
+        let detail = if status.as_u16() == 429 {
+            "Credenciais válidas, mas o limite de requisições foi atingido".to_string()
+        } else if provider == "ollama" {
+            "Servidor Ollama não requer autenticação".to_string()
+        } else {
+            "Credenciais aceitas".to_string()
+        };
+        diagnostics.auth = check(CheckStatus::Ok, detail, None);
+    } else {
+        let detail = match status.as_u16() {
+            401 | 403 if api_key.is_none() => format!("API key não fornecida ({})", status),
+            401 | 403 => format!("API key rejeitada ({})", status),
+            404 => format!(
+                "Endpoint de modelos não encontrado ({}). Verifique a URL base.",
+                status
+            ),
+            _ => format!("Resposta inesperada ({}): {}", status, truncate(&body, 300)),
+        };
+        diagnostics.auth = check(CheckStatus::Failed, detail.clone(), None);
+        diagnostics.message = detail;
+        return diagnostics;
+    }
+
+    let data: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+    diagnostics.available_models = parse_model_ids(&data);
+    diagnostics.model_available = match &model {
+        _ if diagnostics.available_models.is_empty() => {
+            skipped("O provedor não retornou a lista de modelos")
+        }
+        Some(model) if model_listed(&diagnostics.available_models, model) => check(
+            CheckStatus::Ok,
+            format!("Modelo '{}' disponível", model),
+            None,
+        ),
+        Some(model) => check(
+            CheckStatus::Failed,
+            format!(
+                "Modelo '{}' não encontrado entre os {} disponíveis",
+                model,
+                diagnostics.available_models.len()
+            ),
+            None,
+        ),
+        None => skipped("Nenhum modelo selecionado"),
+    };
+
+    diagnostics.success = diagnostics.model_available.status != CheckStatus::Failed;
+    diagnostics.message = if diagnostics.success {
+        format!("Conexão com {} funcionando ({} ms)", provider, latency)
+    } else {
+        diagnostics.model_available.detail.clone()
+    };
+
+    diagnostics
+}
+
+fn diagnose_mock(model: Option<String>) -> ConnectionDiagnostics {
+    let fixture = mock_provider::fixtures_dir().map(|dir| {
+        let request = ChatRequest {
+            provider: mock_provider::MOCK_PROVIDER.to_string(),
+            api_key: None,
+            prompt: String::new(),
+            history: vec![],
+            model: model.clone(),
+        };
+        mock_provider::find_fixture(&dir, &request)
+    });
+
+    let model_available = match fixture {
+        Some(Ok(_)) => check(CheckStatus::Ok, "Fixture encontrada", None),
+        Some(Err(e)) => check(CheckStatus::Failed, e.to_string(), None),
+        None => check(
+            CheckStatus::Failed,
+            "Diretório de fixtures mock não configurado",
+            None,
+        ),
+    };
+    let success = model_available.status == CheckStatus::Ok;
+
+    ConnectionDiagnostics {
+        success,
+        message: if success {
+            "Provedor mock pronto (offline)".to_string()
+        } else {
+            model_available.detail.clone()
+        },
+        provider: mock_provider::MOCK_PROVIDER.to_string(),
+        base_url: mock_provider::fixtures_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default(),
+        model,
+        dns: skipped("Provedor offline"),
+        tls: skipped("Provedor offline"),
+        auth: skipped("Provedor mock não requer autenticação"),
+        model_available,
+        latency_ms: Some(0),
+        rate_limits: HashMap::new(),
+        available_models: Vec::new(),
+    }
+}
+
+fn models_request(
+    client: &Client,
+    provider: &str,
+    base: &Url,
+    api_key: Option<&str>,
+) -> RequestBuilder {
+    let base = base.as_str().trim_end_matches('/');
+
+    match provider {
+        "anthropic" => client
+            .get(format!("{}/models", base))
+            .header("x-api-key", api_key.unwrap_or_default())
+            .header("anthropic-version", "2023-06-01"),
+        "gemini" => client
+            .get(format!("{}/models", base))
+            .query(&[("key", api_key.unwrap_or_default())]),
+        "ollama" => client.get(format!("{}/api/tags", base)),
+        // OpenAI, Mistral, Groq, DeepSeek, Cohere e URLs compatíveis com OpenAI
+        _ => {
+            let request = client.get(format!("{}/models", base));
+            match api_key {
+                Some(key) => request.bearer_auth(key),
+                None => request,
+            }
+        }
+    }
+}
+
+// Formatos: OpenAI/Anthropic `data[].id`, Gemini/Cohere/Ollama `models[].name`
+fn parse_model_ids(data: &Value) -> Vec<String> {
+    let from_list = |list: &Value, key: &str| -> Vec<String> {
+        list.as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item[key].as_str())
+                    .map(|id| id.trim_start_matches("models/").to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut ids = from_list(&data["data"], "id");
+    if ids.is_empty() {
+        ids = from_list(&data["models"], "name");
+    }
+    ids
+}
+
+fn model_listed(models: &[String], model: &str) -> bool {
+    models.iter().any(|listed| {
+        listed == model
+            || listed.strip_suffix(":latest") == Some(model)
+            || model.strip_suffix(":latest") == Some(listed.as_str())
+    })
+}
+
+fn rate_limit_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            RATE_LIMIT_HEADER_PREFIXES
+                .iter()
+                .any(|prefix| name.as_str().starts_with(prefix))
+        })
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}...", &text[..index]),
+        None => text.to_string(),
+    }
+}
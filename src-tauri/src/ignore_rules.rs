@@ -0,0 +1,256 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{DirEntry, Match, WalkBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const PROJECT_IGNORE_FILE: &str = ".rzonehubignore";
+
+// Arquivos de ignore lidos em cada pasta, do menos para o mais prioritário
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore", PROJECT_IGNORE_FILE];
+
+// Pastas de dependências e artefatos ignoradas mesmo sem .gitignore.
+// Podem ser reativadas com uma regra de negação no .rzonehubignore (ex.: `!vendor/`).
+const DEFAULT_IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    ".nuxt",
+    "__pycache__",
+    "venv",
+    ".venv",
+    "vendor",
+];
+
+// Regras de ignore compartilhadas pela árvore de arquivos e pela análise de projeto:
+// .gitignore, .ignore, excludes globais do git, .rzonehubignore e os padrões acima.
+#[derive(Clone)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    project_rules: Arc<Gitignore>,
+}
+
+impl IgnoreRules {
+    pub fn for_root(root: &Path) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let ignore_file = root.join(PROJECT_IGNORE_FILE);
+        if ignore_file.is_file() {
+            if let Some(e) = builder.add(&ignore_file) {
+                log::warn!("Erro ao ler {:?}: {}", ignore_file, e);
+            }
+        }
+
+        let project_rules = builder.build().unwrap_or_else(|e| {
+            log::warn!("Regras inválidas em {:?}: {}", ignore_file, e);
+            Gitignore::empty()
+        });
+
+        IgnoreRules {
+            root: root.to_path_buf(),
+            project_rules: Arc::new(project_rules),
+        }
+    }
+
+    // Usa como raiz o primeiro diretório acima de `path` que tenha .git ou .rzonehubignore
+    pub fn for_path(path: &Path) -> Self {
+        let root = path
+            .ancestors()
+            .find(|dir| dir.join(".git").exists() || dir.join(PROJECT_IGNORE_FILE).is_file())
+            .unwrap_or(path);
+        Self::for_root(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn walker(&self, path: &Path) -> WalkBuilder {
+        let mut builder = WalkBuilder::new(path);
+        builder
+            .hidden(false)
+            .parents(true)
+            .ignore(true)
+            .git_ignore(true)
+            .git_global(true)
+            .git_exclude(true)
+            .require_git(false)
+            .follow_links(false)
+            .add_custom_ignore_filename(PROJECT_IGNORE_FILE);
+
+        let rules = self.clone();
+        builder.filter_entry(move |entry| !rules.is_default_ignored(entry));
+        builder
+    }
+
+    fn is_default_ignored(&self, entry: &DirEntry) -> bool {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        let name = entry.file_name().to_string_lossy();
+
+        if name == ".git" {
+            return true;
+        }
+
+        if !is_dir || !DEFAULT_IGNORED_DIRS.contains(&name.as_ref()) {
+            return false;
+        }
+
+        !self
+            .project_rules
+            .matched(entry.path(), is_dir)
+            .is_whitelist()
+    }
+
+    // Entradas diretas de `dir` que não são ignoradas
+    pub fn list_dir(&self, dir: &Path) -> Vec<DirEntry> {
+        self.walker(dir)
+            .max_depth(Some(1))
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.depth() == 1)
+            .collect()
+    }
+
+    pub fn matcher(&self) -> PathMatcher {
+        let (global, error) = GitignoreBuilder::new(&self.root).build_global();
+        if let Some(e) = error {
+            log::warn!("Erro ao ler o excludesFile global do git: {}", e);
+        }
+        PathMatcher {
+            rules: self.clone(),
+            global,
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+// Confere um caminho avulso (ex.: evento do watcher) contra as mesmas regras do walker,
+// sem listar diretórios. As regras de cada pasta são lidas uma vez e ficam guardadas.
+// Arquivos de ignore acima da raiz não são considerados
+pub struct PathMatcher {
+    rules: IgnoreRules,
+    global: Gitignore,
+    dirs: Mutex<HashMap<PathBuf, Arc<Gitignore>>>,
+}
+
+impl PathMatcher {
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let root = &self.rules.root;
+        let Ok(relative) = path.strip_prefix(root) else {
+            return true;
+        };
+
+        // `.git` e as pastas de dependências em qualquer nível do caminho
+        let components: Vec<_> = relative.components().collect();
+        let mut dir = root.clone();
+        for (index, component) in components.iter().enumerate() {
+            dir.push(component);
+            let name = component.as_os_str().to_string_lossy();
+            let component_is_dir = is_dir || index + 1 < components.len();
+            if name == ".git" {
+                return true;
+            }
+            if component_is_dir
+                && DEFAULT_IGNORED_DIRS.contains(&name.as_ref())
+                && !self.rules.project_rules.matched(&dir, true).is_whitelist()
+            {
+                return true;
+            }
+        }
+
+        // Como no walker, as regras da pasta mais funda têm prioridade
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(root) {
+                break;
+            }
+            match self
+                .dir_rules(dir)
+                .matched_path_or_any_parents(path, is_dir)
+            {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        self.global
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+
+    // Um arquivo de ignore alterado descarta as regras guardadas da pasta dele
+    pub fn refresh(&self, changed: &Path) {
+        let is_ignore_file = changed
+            .file_name()
+            .is_some_and(|name| IGNORE_FILES.iter().any(|file| name == *file));
+        if let (true, Some(dir)) = (is_ignore_file, changed.parent()) {
+            self.dirs.lock().unwrap().remove(dir);
+        }
+    }
+
+    fn dir_rules(&self, dir: &Path) -> Arc<Gitignore> {
+        if let Some(rules) = self.dirs.lock().unwrap().get(dir) {
+            return rules.clone();
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut files: Vec<PathBuf> = IGNORE_FILES.iter().map(|file| dir.join(file)).collect();
+        if dir == self.rules.root {
+            files.insert(0, dir.join(".git").join("info").join("exclude"));
+        }
+        for file in files.iter().filter(|file| file.is_file()) {
+            if let Some(e) = builder.add(file) {
+                log::warn!("Erro ao ler {:?}: {}", file, e);
+            }
+        }
+        let rules = Arc::new(builder.build().unwrap_or_else(|e| {
+            log::warn!("Regras inválidas em {:?}: {}", dir, e);
+            Gitignore::empty()
+        }));
+        self.dirs
+            .lock()
+            .unwrap()
+            .insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn matcher_follows_walker_rules_without_listing() {
+        let root = std::env::temp_dir().join(format!(
+            "rzonehub-ignore-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n!keep.log\n").unwrap();
+        fs::write(root.join("sub").join(".gitignore"), "local.txt\n").unwrap();
+        fs::write(root.join(PROJECT_IGNORE_FILE), "!vendor/\n").unwrap();
+
+        let matcher = IgnoreRules::for_root(&root).matcher();
+        let ignored = |path: &str| matcher.is_ignored(&root.join(path), false);
+        assert!(ignored("erro.log"));
+        assert!(!ignored("keep.log"));
+        assert!(ignored("sub/local.txt"));
+        assert!(!ignored("local.txt"));
+        assert!(ignored("node_modules/pacote/index.js"));
+        assert!(matcher.is_ignored(&root.join("target"), true));
+        assert!(!ignored("vendor/lib/mod.go"));
+        assert!(ignored(".git/config"));
+        assert!(!ignored("src/main.rs"));
+        assert!(matcher.is_ignored(Path::new("/fora/da/raiz.rs"), false));
+
+        // As regras da pasta ficam guardadas até o arquivo de ignore mudar
+        fs::write(root.join("sub").join(".gitignore"), "local.txt\n*.rs\n").unwrap();
+        assert!(!ignored("sub/novo.rs"));
+        matcher.refresh(&root.join("sub").join(".gitignore"));
+        assert!(ignored("sub/novo.rs"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,13 +1,42 @@
-mod ai_client;
-mod commands;
+mod advisory_db;
+pub mod ai_client;
+mod analysis_jobs;
+pub mod commands;
+mod connection_diagnostics;
+mod ignore_rules;
+pub mod mock_provider;
+mod ollama;
 mod project_analyzer;
-mod types;
+mod prompt_templates;
+mod provider_comparison;
+mod symbol_index;
+pub mod types;
 
 use commands::{
     analyze_project_structure, chat_with_ai, connect_github, generate_code, get_system_info,
     test_api_connection, open_repository, list_directory, read_file_content, 
-    write_file_content, run_terminal_command, expand_directory,
+    write_file_content, run_terminal_command, expand_directory, list_prompt_templates,
+    render_prompt_template, run_prompt_template, compare_providers, ollama_status,
+    ollama_list_models, ollama_pull_model, ollama_delete_model, ollama_show_model,
+    start_project_analysis, cancel_project_analysis,
+    audit_dependencies, refresh_advisory_db, advisory_db_status,
+    scan_secrets,
+    analyze_code_metrics,
+    analyze_module_graph, export_module_graph,
+    index_symbols, find_symbol, find_definition,
+    invalidate_analysis_cache,
+    list_suggestion_rules,
+    analyze_git_history,
+    find_duplicate_code,
+    find_debt_markers,
+    analyze_licenses,
+    export_license_report,
+    export_analysis,
+    list_analysis_snapshots,
+    diff_analyses,
 };
+use analysis_jobs::AnalysisJobs;
+use symbol_index::SymbolIndex;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -16,6 +45,8 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(AnalysisJobs::default())
+        .manage(SymbolIndex::default())
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -39,6 +70,37 @@ pub fn run() {
             read_file_content,
             write_file_content,
             run_terminal_command,
+            list_prompt_templates,
+            render_prompt_template,
+            run_prompt_template,
+            compare_providers,
+            ollama_status,
+            ollama_list_models,
+            ollama_pull_model,
+            ollama_delete_model,
+            ollama_show_model,
+            start_project_analysis,
+            cancel_project_analysis,
+            audit_dependencies,
+            refresh_advisory_db,
+            advisory_db_status,
+            scan_secrets,
+            analyze_code_metrics,
+            analyze_module_graph,
+            export_module_graph,
+            index_symbols,
+            find_symbol,
+            find_definition,
+            invalidate_analysis_cache,
+            list_suggestion_rules,
+            analyze_git_history,
+            find_duplicate_code,
+            find_debt_markers,
+            analyze_licenses,
+            export_license_report,
+            export_analysis,
+            list_analysis_snapshots,
+            diff_analyses,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
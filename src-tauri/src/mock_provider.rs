@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::project_analyzer;
+use crate::types::{ChatMessage, ChatRequest, ChatResponse, TokenUsage};
+
+pub const MOCK_PROVIDER: &str = "mock";
+
+const FIXTURES_ENV: &str = "RZONEHUB_MOCK_FIXTURES";
+const RECORD_ENV: &str = "RZONEHUB_RECORD_FIXTURES";
+const DEFAULT_FIXTURE: &str = "default";
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<FixtureRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<FixtureResponse>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<String>,
+    #[serde(default)]
+    pub chunk_delay_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureRequest {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub history: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureResponse {
+    pub content: String,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+// Diretório das fixtures: variável RZONEHUB_MOCK_FIXTURES ou <config>/RZOneHub/mock
+pub fn fixtures_dir() -> Option<PathBuf> {
+    std::env::var_os(FIXTURES_ENV)
+        .map(PathBuf::from)
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("RZOneHub").join("mock")))
+}
+
+// Quando RZONEHUB_RECORD_FIXTURES aponta para um diretório, as trocas reais são gravadas nele
+pub fn recording_dir() -> Option<PathBuf> {
+    std::env::var_os(RECORD_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+pub async fn chat(request: &ChatRequest) -> Result<ChatResponse> {
+    stream(request, |_| {}).await
+}
+
+pub async fn stream<F>(request: &ChatRequest, on_delta: F) -> Result<ChatResponse>
+where
+    F: FnMut(&str) + Send,
+{
+    let dir = fixtures_dir().context("Diretório de fixtures mock não configurado")?;
+    stream_from(&dir, request, on_delta).await
+}
+
+// Reproduz a fixture de um diretório explícito, sem depender da variável de ambiente
+pub async fn stream_from<F>(
+    dir: &Path,
+    request: &ChatRequest,
+    mut on_delta: F,
+) -> Result<ChatResponse>
+where
+    F: FnMut(&str) + Send,
+{
+    let fixture = find_fixture(dir, request)?;
+
+    let mut content = String::new();
+    for chunk in &fixture.chunks {
+        if fixture.chunk_delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(fixture.chunk_delay_ms)).await;
+        }
+        content.push_str(chunk);
+        on_delta(chunk);
+    }
+
+    // Um erro depois dos trechos simula uma falha no meio do stream
+    if let Some(error) = fixture.error {
+        return Err(anyhow::anyhow!("Mock API error: {}", error));
+    }
+
+    let usage = fixture.response.as_ref().and_then(|r| r.usage.clone());
+    if fixture.chunks.is_empty() {
+        content = fixture.response.map(|r| r.content).unwrap_or_default();
+        on_delta(&content);
+    }
+
+    Ok(ChatResponse { content, usage })
+}
+
+// Ordem de busca: fixture com o nome do modelo, fixture cujo prompt gravado é igual, default.json
+pub fn find_fixture(dir: &Path, request: &ChatRequest) -> Result<Fixture> {
+    if let Some(model) = request
+        .model
+        .as_deref()
+        .filter(|model| is_fixture_name(model))
+    {
+        let path = dir.join(format!("{}.json", model));
+        if path.is_file() {
+            return load_fixture(&path);
+        }
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Falha ao ler diretório de fixtures: {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+
+    for path in &paths {
+        let fixture = load_fixture(path)?;
+        let matches = fixture
+            .request
+            .as_ref()
+            .and_then(|r| r.prompt.as_deref())
+            .is_some_and(|prompt| prompt == request.prompt);
+        if matches {
+            return Ok(fixture);
+        }
+    }
+
+    let default_path = dir.join(format!("{}.json", DEFAULT_FIXTURE));
+    if default_path.is_file() {
+        return load_fixture(&default_path);
+    }
+
+    Err(anyhow::anyhow!(
+        "Nenhuma fixture mock encontrada para o prompt em {}",
+        dir.display()
+    ))
+}
+
+// O modelo vira nome de arquivo: nada de separadores, `..` ou caminho absoluto, para a
+// busca não sair do diretório de fixtures
+fn is_fixture_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+        && !Path::new(name).is_absolute()
+}
+
+// Cada trecho é mascarado sozinho. Um segredo partido entre dois trechos só é reconhecido
+// no texto inteiro; nesse caso o stream é gravado como um único trecho já mascarado
+fn scrub_chunks(chunks: &[String], scrub: &impl Fn(&str) -> String) -> Vec<String> {
+    let scrubbed: Vec<String> = chunks.iter().map(|chunk| scrub(chunk)).collect();
+    let whole = scrub(&chunks.concat());
+    if scrubbed.concat() == whole {
+        scrubbed
+    } else {
+        vec![whole]
+    }
+}
+
+fn load_fixture(path: &Path) -> Result<Fixture> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Falha ao ler fixture {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Fixture inválida: {}", path.display()))
+}
+
+// Grava a troca como fixture, sem a API key: ela e qualquer outro segredo reconhecido pela
+// varredura (tokens, chaves privadas, senhas) são mascarados em prompt, histórico, resposta
+// e nos trechos do stream (`chunks` vazio para respostas sem streaming)
+pub fn record_exchange(
+    dir: &Path,
+    request: &ChatRequest,
+    result: &Result<ChatResponse>,
+    chunks: &[String],
+) -> Result<PathBuf> {
+    let secret = request.api_key.as_deref().filter(|key| !key.is_empty());
+    let scrub = |text: &str| {
+        let text = match secret {
+            Some(key) => text.replace(key, REDACTED),
+            None => text.to_string(),
+        };
+        project_analyzer::redact_secrets(&text)
+    };
+
+    let fixture = Fixture {
+        request: Some(FixtureRequest {
+            provider: Some(request.provider.clone()),
+            model: request.model.clone(),
+            prompt: Some(scrub(&request.prompt)),
+            history: request
+                .history
+                .iter()
+                .map(|msg| ChatMessage {
+                    role: msg.role.clone(),
+                    content: scrub(&msg.content),
+                })
+                .collect(),
+        }),
+        response: result.as_ref().ok().map(|response| FixtureResponse {
+            content: scrub(&response.content),
+            usage: response.usage.clone(),
+        }),
+        chunks: scrub_chunks(chunks, &scrub),
+        chunk_delay_ms: 0,
+        error: result.as_ref().err().map(|e| scrub(&e.to_string())),
+    };
+
+    fs::create_dir_all(dir)?;
+    let provider = if is_fixture_name(&request.provider) {
+        request.provider.as_str()
+    } else {
+        "provider"
+    };
+    let path = dir.join(format!(
+        "{}-{}.json",
+        provider,
+        chrono::Utc::now().format("%Y%m%d%H%M%S%3f")
+    ));
+    fs::write(&path, serde_json::to_string_pretty(&fixture)?)?;
+
+    Ok(path)
+}
@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::types::{OllamaModel, OllamaModelInfo, OllamaPullProgress, OllamaStatus};
+
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn base(base_url: Option<&str>) -> String {
+    base_url
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or(OLLAMA_BASE_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+async fn error_from_response(response: reqwest::Response, action: &str) -> anyhow::Error {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|data| data["error"].as_str().map(|e| e.to_string()))
+        .unwrap_or(body);
+    anyhow::anyhow!("Falha ao {} ({}): {}", action, status, message)
+}
+
+pub async fn status(base_url: Option<&str>) -> OllamaStatus {
+    let base_url = base(base_url);
+    let client = Client::builder()
+        .timeout(STATUS_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    match client.get(format!("{}/api/version", base_url)).send().await {
+        Ok(response) if response.status().is_success() => {
+            let data: Value = response.json().await.unwrap_or(Value::Null);
+            OllamaStatus {
+                running: true,
+                version: data["version"].as_str().map(|v| v.to_string()),
+                base_url,
+                error: None,
+            }
+        }
+        Ok(response) => OllamaStatus {
+            running: false,
+            version: None,
+            error: Some(format!(
+                "Resposta inesperada do servidor: {}",
+                response.status()
+            )),
+            base_url,
+        },
+        Err(e) => OllamaStatus {
+            running: false,
+            version: None,
+            error: Some(format!(
+                "Servidor Ollama não encontrado em {}. Verifique se o Ollama está rodando. ({})",
+                base_url, e
+            )),
+            base_url,
+        },
+    }
+}
+
+pub async fn list_models(base_url: Option<&str>) -> Result<Vec<OllamaModel>> {
+    let response = Client::new()
+        .get(format!("{}/api/tags", base(base_url)))
+        .send()
+        .await
+        .context("Falha ao conectar ao Ollama. Verifique se o Ollama está rodando.")?;
+
+    if !response.status().is_success() {
+        return Err(error_from_response(response, "listar modelos").await);
+    }
+
+    let data: Value = response.json().await.context("Falha ao parsear resposta")?;
+
+    let mut models: Vec<OllamaModel> = data["models"]
+        .as_array()
+        .map(|models| models.iter().map(parse_model).collect())
+        .unwrap_or_default();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(models)
+}
+
+fn parse_model(model: &Value) -> OllamaModel {
+    let text = |value: &Value| value.as_str().map(|s| s.to_string());
+
+    OllamaModel {
+        name: text(&model["name"]).unwrap_or_default(),
+        size: model["size"].as_u64().unwrap_or(0),
+        digest: text(&model["digest"]),
+        modified_at: text(&model["modified_at"]),
+        family: text(&model["details"]["family"]),
+        parameter_size: text(&model["details"]["parameter_size"]),
+        quantization_level: text(&model["details"]["quantization_level"]),
+    }
+}
+
+// O endpoint /api/pull responde em NDJSON: uma linha de status por evento até "success"
+pub async fn pull_model<F>(base_url: Option<&str>, model: &str, mut on_progress: F) -> Result<()>
+where
+    F: FnMut(OllamaPullProgress),
+{
+    let mut response = Client::new()
+        .post(format!("{}/api/pull", base(base_url)))
+        .json(&json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .context("Falha ao conectar ao Ollama. Verifique se o Ollama está rodando.")?;
+
+    if !response.status().is_success() {
+        return Err(error_from_response(response, "baixar modelo").await);
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut finished = false;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Falha ao ler progresso do download")?
+    {
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            finished |= handle_pull_line(model, &line, &mut on_progress)?;
+        }
+    }
+    finished |= handle_pull_line(model, &buffer, &mut on_progress)?;
+
+    if !finished {
+        anyhow::bail!(
+            "Download do modelo '{}' terminou sem confirmação do Ollama",
+            model
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_pull_line<F>(model: &str, line: &[u8], on_progress: &mut F) -> Result<bool>
+where
+    F: FnMut(OllamaPullProgress),
+{
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(false);
+    }
+
+    let data: Value =
+        serde_json::from_str(line).context("Falha ao parsear progresso do download")?;
+
+    if let Some(error) = data["error"].as_str() {
+        anyhow::bail!("Falha ao baixar modelo '{}': {}", model, error);
+    }
+
+    let status = data["status"].as_str().unwrap_or_default().to_string();
+    let total = data["total"].as_u64();
+    let completed = data["completed"].as_u64();
+    let percent = match (total, completed) {
+        (Some(total), Some(completed)) if total > 0 => {
+            Some((completed as f64 / total as f64 * 100.0).min(100.0))
+        }
+        _ => None,
+    };
+    let finished = status == "success";
+
+    on_progress(OllamaPullProgress {
+        model: model.to_string(),
+        status,
+        digest: data["digest"].as_str().map(|d| d.to_string()),
+        total,
+        completed,
+        percent,
+        done: finished,
+    });
+
+    Ok(finished)
+}
+
+pub async fn delete_model(base_url: Option<&str>, model: &str) -> Result<()> {
+    let response = Client::new()
+        .delete(format!("{}/api/delete", base(base_url)))
+        .json(&json!({ "model": model }))
+        .send()
+        .await
+        .context("Falha ao conectar ao Ollama. Verifique se o Ollama está rodando.")?;
+
+    if !response.status().is_success() {
+        return Err(error_from_response(response, "remover modelo").await);
+    }
+
+    Ok(())
+}
+
+pub async fn show_model(base_url: Option<&str>, model: &str) -> Result<OllamaModelInfo> {
+    let response = Client::new()
+        .post(format!("{}/api/show", base(base_url)))
+        .json(&json!({ "model": model }))
+        .send()
+        .await
+        .context("Falha ao conectar ao Ollama. Verifique se o Ollama está rodando.")?;
+
+    if !response.status().is_success() {
+        return Err(error_from_response(response, "obter informações do modelo").await);
+    }
+
+    let data: Value = response.json().await.context("Falha ao parsear resposta")?;
+    let text = |value: &Value| value.as_str().map(|s| s.to_string());
+
+    Ok(OllamaModelInfo {
+        name: model.to_string(),
+        family: text(&data["details"]["family"]),
+        parameter_size: text(&data["details"]["parameter_size"]),
+        quantization_level: text(&data["details"]["quantization_level"]),
+        format: text(&data["details"]["format"]),
+        license: text(&data["license"]),
+        parameters: text(&data["parameters"]),
+        template: text(&data["template"]),
+        modelfile: text(&data["modelfile"]),
+        model_info: data["model_info"].clone(),
+    })
+}
@@ -1,27 +1,55 @@
+mod audit;
+mod cache;
+mod complexity;
+mod context;
+mod dependencies;
+mod duplicates;
+mod git_history;
+mod languages;
+mod licenses;
+mod loc;
+mod markers;
+mod module_graph;
+mod packages;
+mod progress;
+mod report;
+mod rules;
+mod secrets;
+mod snapshots;
+mod stack;
+mod symbols;
+mod syntax;
+
 use anyhow::Result;
-use std::collections::HashMap;
+use ignore::WalkState;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+
+use crate::advisory_db;
+use crate::ignore_rules::IgnoreRules;
+use crate::types::{
+    AnalysisDiff, CodeMetrics, Dependency, DetectedTechnology, DuplicateReport, FileInfo,
+    FileMetrics, FileTypeStat, FunctionMetrics, GitAnalytics, LanguageStats, LicensePolicy,
+    LicenseReport, MarkerFilter, ModuleGraph, OmissionReason, OmittedFile, ProjectAnalysis,
+    SecretFinding, SnapshotInfo, SuggestionRuleInfo, SymbolInfo, TechDebtReport,
+    TechnologyCategory, VulnerabilityAudit,
+};
+use cache::{AnalysisCache, CachedFile, CachedLines};
+use context::ContextSource;
+use loc::FileLines;
+use syntax::Grammar;
 
-use crate::types::{FileInfo, FileTypeStat, ProjectAnalysis};
+pub use progress::{AnalysisControl, Cancelled};
 
 const MAX_FILE_SIZE: u64 = 500_000; // 500KB (aumentado de 100KB)
 const MAX_FILES_WITH_CONTENT: usize = 100; // 100 arquivos (aumentado de 20)
-
-const IGNORED_DIRS: &[&str] = &[
-    "node_modules",
-    "target",
-    "dist",
-    "build",
-    ".git",
-    ".next",
-    ".nuxt",
-    "__pycache__",
-    "venv",
-    ".venv",
-    "vendor",
-];
+const MAX_LOC_FILE_SIZE: u64 = 2_000_000; // arquivos maiores ficam fora da contagem de linhas
+const BINARY_CHECK_BYTES: usize = 8_192;
+const MAX_GIT_ENTRIES: usize = 20; // arquivos com mais churn e hotspots na análise completa
 
 const IGNORED_EXTENSIONS: &[&str] = &[
     "lock",
@@ -35,49 +63,28 @@ const IGNORED_EXTENSIONS: &[&str] = &[
 ];
 
 pub fn analyze_project(base_path: Option<String>) -> Result<ProjectAnalysis> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    analyze_project_with(base_path, &control)
+}
+
+pub fn analyze_project_with(
+    base_path: Option<String>,
+    control: &AnalysisControl,
+) -> Result<ProjectAnalysis> {
     let root = if let Some(path) = base_path {
         PathBuf::from(path)
     } else {
         std::env::current_dir()?
     };
 
-    let mut total_files = 0;
-    let mut total_directories = 0;
-    let mut file_types: HashMap<String, usize> = HashMap::new();
-    let mut files_with_size: Vec<(PathBuf, u64)> = Vec::new();
-
-    for entry in WalkDir::new(&root)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-    {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
-
-        let path = entry.path();
-
-        if path.is_dir() {
-            total_directories += 1;
-        } else if path.is_file() {
-            total_files += 1;
-
-            // Count file types
-            if let Some(ext) = path.extension() {
-                if let Some(ext_str) = ext.to_str() {
-                    if !IGNORED_EXTENSIONS.contains(&ext_str) {
-                        *file_types.entry(ext_str.to_string()).or_insert(0) += 1;
-                    }
-                }
-            }
+    control.set_phase("scanning");
+    let scan = scan_files(&root, control)?;
 
-            // Collect file sizes
-            if let Ok(metadata) = fs::metadata(path) {
-                files_with_size.push((path.to_path_buf(), metadata.len()));
-            }
-        }
-    }
+    let total_files = scan.files.len();
+    let total_directories = scan.total_directories;
+    let file_types = scan.file_types;
+    let mut files_with_size = scan.files;
 
     // Sort and get top file types
     let mut file_type_stats: Vec<FileTypeStat> = file_types
@@ -91,20 +98,114 @@ pub fn analyze_project(base_path: Option<String>) -> Result<ProjectAnalysis> {
     file_type_stats.truncate(10);
 
     // Sort and get largest files
-    files_with_size.sort_by(|a, b| b.1.cmp(&a.1));
-    
+    files_with_size.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    // Lê em paralelo os arquivos para contar linhas; o conteúdo só é guardado
+    // para os arquivos de código que podem entrar no resumo, em ordem de prioridade
+    control.set_phase("reading");
+    let code_files: Vec<&(PathBuf, u64)> = files_with_size
+        .iter()
+        .filter(|(path, _)| {
+            path.strip_prefix(&root)
+                .is_ok_and(|relative| should_include_content(&relative.to_string_lossy()))
+        })
+        .collect();
+    let prioritized = context::prioritize(
+        &root,
+        code_files
+            .iter()
+            .copied()
+            .filter(|(_, size)| *size < MAX_FILE_SIZE),
+    );
+    let candidates: HashSet<&PathBuf> = prioritized
+        .iter()
+        .copied()
+        .take(MAX_FILES_WITH_CONTENT)
+        .collect();
+    let ReadFiles {
+        mut contents,
+        line_counts,
+        secrets,
+    } = read_files(
+        &root,
+        &files_with_size,
+        &candidates,
+        &mut AnalysisCache::load(&root),
+        control,
+    )?;
+    let languages = loc::language_stats(&line_counts);
+    let directories = loc::directory_stats(&line_counts);
+
+    control.check_cancelled()?;
+    control.set_phase("dependencies");
+    let (dependencies, manifests) = dependencies::collect_with_manifests(&root, &files_with_size);
+    let packages = packages::build(
+        &root,
+        &files_with_size,
+        &line_counts,
+        manifests,
+        &dependencies,
+    );
+    let stack = stack::detect(&root, &files_with_size, &dependencies, &languages);
+
+    // A auditoria só roda quando o banco local de advisories já foi baixado
+    let vulnerability_audit = advisory_db::default_dir()
+        .filter(|dir| dir.is_dir())
+        .map(|dir| {
+            control.set_phase("audit");
+            audit::audit(&root, &files_with_size, &dir)
+        });
+
+    control.check_cancelled()?;
+    control.set_phase("git");
+    let git = match git_history::analyze(
+        &root,
+        &files_with_size,
+        git_history::DEFAULT_WINDOW_DAYS,
+        MAX_GIT_ENTRIES,
+    ) {
+        Ok(git) => git,
+        Err(e) => {
+            log::warn!("Falha ao ler o histórico do git: {}", e);
+            None
+        }
+    };
+
+    // Fontes do contexto na ordem de prioridade; o que não foi lido já entra como omitido
+    let sources: Vec<ContextSource> = prioritized
+        .iter()
+        .take(MAX_FILES_WITH_CONTENT)
+        .map(|path| ContextSource {
+            path: relative_display(&root, path),
+            content: contents.get(*path).cloned().flatten(),
+        })
+        .collect();
+    let omitted: Vec<OmittedFile> = prioritized
+        .iter()
+        .skip(MAX_FILES_WITH_CONTENT)
+        .map(|path| (*path, OmissionReason::Limit))
+        .chain(
+            code_files
+                .iter()
+                .filter(|(_, size)| *size >= MAX_FILE_SIZE)
+                .map(|(path, _)| (path, OmissionReason::Size)),
+        )
+        .map(|(path, reason)| OmittedFile {
+            path: relative_display(&root, path),
+            reason,
+        })
+        .collect();
+
     let mut largest_files: Vec<FileInfo> = Vec::new();
-    let mut files_added = 0;
     
     // Incluir TODOS os arquivos de código, não apenas os maiores
     for (path, size) in files_with_size.iter() {
         if let Ok(relative_path) = path.strip_prefix(&root) {
             let path_str = relative_path.to_string_lossy().to_string();
             
-            // Adiciona conteúdo para TODOS arquivos de código menores que MAX_FILE_SIZE
-            let content = if should_include_content(&path_str) && *size < MAX_FILE_SIZE && files_added < MAX_FILES_WITH_CONTENT {
-                files_added += 1;
-                fs::read_to_string(path).ok()
+            // Conteúdo só para os arquivos de código escolhidos para o contexto
+            let content = if candidates.contains(path) {
+                contents.remove(path).flatten()
             } else {
                 None
             };
@@ -125,100 +226,538 @@ pub fn analyze_project(base_path: Option<String>) -> Result<ProjectAnalysis> {
         }
     }
 
-    let suggestions = generate_suggestions(total_files, &file_type_stats);
+    control.check_cancelled()?;
+    control.set_phase("summarizing");
+
+    let relative_files: Vec<String> = files_with_size
+        .iter()
+        .map(|(path, _)| relative_display(&root, path))
+        .collect();
+    let suggestion_details = rules::evaluate(&rules::RuleInput {
+        root: &root,
+        files: &relative_files,
+        dependencies: &dependencies,
+        stack: &stack,
+        languages: &languages,
+        secrets: &secrets,
+        vulnerability_audit: vulnerability_audit.as_ref(),
+    });
+    let mut suggestions: Vec<String> = suggestion_details
+        .iter()
+        .map(|suggestion| suggestion.message.clone())
+        .collect();
+    if suggestions.is_empty() {
+        suggestions.push(
+            "Projeto bem estruturado! Continue mantendo boas práticas de desenvolvimento."
+                .to_string(),
+        );
+    }
 
     // Gerar resumo do projeto com conteúdo de arquivos
-    let summary = generate_project_summary(
+    let mut summary = generate_project_summary(
         &root,
         total_files,
         total_directories,
         &file_type_stats,
-        &largest_files,
+        &languages,
+        &secrets,
+        &stack,
     );
+    summary.push_str(&packages::summary(&packages));
+    if let Some(git) = &git {
+        summary.push_str(&git_history::summary(git));
+    }
+
+    // Configuração e código dividem o que sobra do orçamento depois do cabeçalho
+    let budget = context::DEFAULT_TOKEN_BUDGET.saturating_sub(context::estimate_tokens(&summary));
+    let config = context::config_sources(&root);
+    let (files, context_report) = context::build(&config, &sources, omitted, budget);
+    summary.push_str(&files);
 
-    Ok(ProjectAnalysis {
+    let analysis = ProjectAnalysis {
         total_files,
         total_directories,
         file_types: file_type_stats,
         largest_files,
         suggestions,
         summary,
-    })
+        languages,
+        directories,
+        dependencies,
+        vulnerability_audit,
+        secrets,
+        context: Some(context_report),
+        stack,
+        suggestion_details,
+        git,
+        packages: Some(packages),
+        scanned_files: snapshots::snapshot_files(&root, &files_with_size),
+    };
+    Ok(analysis)
+}
+
+// Grava a análise no histórico do workspace. Fica a cargo de quem chama: exportar um
+// relatório ou montar um prompt não devem criar snapshots. A lista de arquivos vem da
+// varredura que a própria análise fez
+pub fn save_analysis_snapshot(base_path: Option<String>, analysis: &ProjectAnalysis) -> Result<()> {
+    let root = if let Some(path) = base_path {
+        PathBuf::from(path)
+    } else {
+        std::env::current_dir()?
+    };
+    snapshots::save(&root, analysis)
+}
+
+// Auditoria de vulnerabilidades avulsa, com um banco de advisories escolhido pelo usuário
+pub fn audit_dependencies(base_path: &Path, db_dir: &Path) -> Result<VulnerabilityAudit> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    Ok(audit::audit(base_path, &scan.files, db_dir))
+}
+
+// Varredura de segredos avulsa (sem o restante da análise)
+pub fn scan_secrets(base_path: &Path) -> Result<Vec<SecretFinding>> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    let mut cache = AnalysisCache::load(base_path);
+    Ok(read_files(base_path, &scan.files, &HashSet::new(), &mut cache, &control)?.secrets)
+}
+
+// Mascara segredos em texto solto (prompts, respostas) com as mesmas regras da varredura
+pub fn redact_secrets(text: &str) -> String {
+    secrets::redact(Path::new(""), text)
 }
 
-fn is_ignored(path: &Path) -> bool {
-    path.components().any(|comp| {
-        if let Some(name) = comp.as_os_str().to_str() {
-            IGNORED_DIRS.contains(&name)
+// Complexidade e tamanho das funções (Rust, TS/JS, Python e Go); `limit` é quantas
+// das piores funções voltam na lista
+pub fn code_metrics(base_path: &Path, limit: usize) -> Result<CodeMetrics> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+
+    let per_file: Vec<Vec<FunctionMetrics>> = scan
+        .files
+        .par_iter()
+        .filter(|(path, size)| *size < MAX_FILE_SIZE && Grammar::detect(path).is_some())
+        .filter_map(|(path, _)| {
+            let content = fs::read_to_string(path).ok()?;
+            let relative = path.strip_prefix(base_path).unwrap_or(path);
+            complexity::analyze_file(relative, &content)
+        })
+        .collect();
+    let files_analyzed = per_file.len();
+
+    let mut files: Vec<FileMetrics> = per_file
+        .iter()
+        .filter_map(|functions| complexity::file_metrics(functions))
+        .collect();
+    files.sort_by(|a, b| {
+        b.max_cognitive
+            .cmp(&a.max_cognitive)
+            .then_with(|| b.total_cyclomatic.cmp(&a.total_cyclomatic))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+
+    let mut functions: Vec<FunctionMetrics> = per_file.into_iter().flatten().collect();
+    let functions_analyzed = functions.len();
+    let total_cyclomatic: usize = functions.iter().map(|f| f.cyclomatic).sum();
+    functions.sort_by(|a, b| {
+        b.cognitive
+            .cmp(&a.cognitive)
+            .then_with(|| b.cyclomatic.cmp(&a.cyclomatic))
+            .then_with(|| b.lines.cmp(&a.lines))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    functions.truncate(limit);
+
+    Ok(CodeMetrics {
+        files_analyzed,
+        functions_analyzed,
+        average_cyclomatic: if functions_analyzed == 0 {
+            0.0
         } else {
-            false
-        }
+            complexity::round(total_cyclomatic as f64 / functions_analyzed as f64)
+        },
+        worst_functions: functions,
+        files,
     })
 }
 
-fn generate_suggestions(total_files: usize, file_types: &[FileTypeStat]) -> Vec<String> {
-    let mut suggestions = Vec::new();
+// Código copiado e colado: blocos de pelo menos `min_tokens` tokens repetidos no workspace,
+// opcionalmente ignorando renomeação de identificadores e literais
+pub fn find_duplicates(
+    base_path: &Path,
+    min_tokens: usize,
+    normalize_identifiers: bool,
+    limit: usize,
+) -> Result<DuplicateReport> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    let mut report = duplicates::detect(base_path, &scan.files, min_tokens, normalize_identifiers);
+    report.groups.truncate(limit);
+    Ok(report)
+}
 
-    // Check for test files
-    let has_tests = file_types
-        .iter()
-        .any(|ft| ft.extension == "test.ts" || ft.extension == "spec.ts");
-    
-    if !has_tests && total_files > 20 {
-        suggestions.push(
-            "Considere adicionar testes automatizados para garantir qualidade do código.".to_string(),
-        );
+// Marcadores de dívida técnica (TODO, FIXME, HACK, XXX, todo!/unimplemented!) com contexto
+// e autoria do git blame
+pub fn debt_markers(base_path: &Path, filter: &MarkerFilter) -> Result<TechDebtReport> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    Ok(markers::collect(base_path, &scan.files, filter))
+}
+
+// Grafo de dependências entre os módulos do workspace (imports resolvidos para arquivos)
+pub fn build_module_graph(base_path: &Path) -> Result<ModuleGraph> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    Ok(module_graph::build(base_path, &scan.files))
+}
+
+pub fn render_module_graph(graph: &ModuleGraph, format: &str) -> Result<String> {
+    match format.to_lowercase().as_str() {
+        "json" => Ok(serde_json::to_string_pretty(graph)?),
+        "dot" | "graphviz" => Ok(module_graph::to_dot(graph)),
+        "mermaid" | "mmd" => Ok(module_graph::to_mermaid(graph)),
+        other => anyhow::bail!("Formato de grafo não suportado: {}", other),
     }
+}
 
-    // Check for documentation
-    let has_readme = file_types.iter().any(|ft| ft.extension == "md");
-    if !has_readme {
-        suggestions.push(
-            "Adicione um README.md para documentar o projeto e facilitar onboarding.".to_string(),
-        );
+// Snapshots gravados a cada análise do workspace, do mais recente para o mais antigo
+pub fn analysis_snapshots(base_path: &Path) -> Vec<SnapshotInfo> {
+    snapshots::list(base_path)
+}
+
+// O que mudou entre dois snapshots: arquivos, linhas por linguagem, arquivos grandes,
+// dependências e achados novos/corrigidos
+pub fn diff_analyses(from: &str, to: &str) -> Result<AnalysisDiff> {
+    Ok(snapshots::diff(&snapshots::load(from)?, &snapshots::load(to)?))
+}
+
+// Relatório da análise para anexar a PRs ou arquivar: "json" (a análise crua), "markdown",
+// "html" (arquivo único, sem recursos externos) ou "sarif" (achados para code scanning).
+// Usa a análise que quem chama já tem ou, sem ela, o snapshot mais recente do workspace;
+// sem `metrics` o relatório sai sem a seção e os achados de complexidade
+pub fn export_analysis(
+    base_path: &Path,
+    analysis: Option<ProjectAnalysis>,
+    metrics: Option<&CodeMetrics>,
+    format: &str,
+    output: &Path,
+) -> Result<()> {
+    let format = format.to_lowercase();
+    if !matches!(format.as_str(), "json" | "markdown" | "md" | "html" | "sarif") {
+        anyhow::bail!("Formato de relatório não suportado: {}", format);
     }
+    let analysis = match analysis {
+        Some(analysis) => analysis,
+        None => snapshots::latest(base_path)?.analysis,
+    };
+    let report = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&analysis)?,
+        "html" => report::to_html(base_path, &analysis, metrics),
+        "sarif" => report::to_sarif(base_path, &analysis, metrics)?,
+        _ => report::to_markdown(base_path, &analysis, metrics),
+    };
+    cache::write_atomic(output, report.as_bytes())
+}
 
-    // Check for TypeScript
-    let has_typescript = file_types.iter().any(|ft| ft.extension == "ts" || ft.extension == "tsx");
-    let has_javascript = file_types.iter().any(|ft| ft.extension == "js" || ft.extension == "jsx");
-    
-    if has_javascript && !has_typescript && total_files > 30 {
-        suggestions.push(
-            "Migrar para TypeScript pode melhorar a manutenibilidade e prevenir bugs.".to_string(),
-        );
+// Licença do projeto e das dependências diretas; sem `policy` vale a do projeto
+// (.rzonehub/license-policy.toml) ou a global do usuário. `dependencies` é a lista de uma
+// análise que quem chama já tem; sem ela os manifestos são lidos aqui
+pub fn license_report(
+    base_path: &Path,
+    dependencies: Option<Vec<Dependency>>,
+    policy: Option<LicensePolicy>,
+) -> Result<LicenseReport> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    let dependencies =
+        dependencies.unwrap_or_else(|| dependencies::collect(base_path, &scan.files));
+    let policy = policy.unwrap_or_else(|| licenses::load_policy(base_path));
+    Ok(licenses::report(
+        base_path,
+        &scan.files,
+        &dependencies,
+        policy,
+    ))
+}
+
+pub fn render_license_report(report: &LicenseReport, format: &str) -> Result<String> {
+    match format.to_lowercase().as_str() {
+        "json" => Ok(serde_json::to_string_pretty(report)?),
+        "markdown" | "md" => Ok(licenses::to_markdown(report)),
+        other => anyhow::bail!("Formato de relatório não suportado: {}", other),
     }
+}
 
-    // Check for configuration files
-    let has_eslint = file_types.iter().any(|ft| ft.extension.contains("eslint"));
-    if !has_eslint && (has_typescript || has_javascript) {
-        suggestions.push(
-            "Configure ESLint para manter consistência de código e identificar problemas.".to_string(),
-        );
+// Apaga o cache incremental de um workspace (ou de todos); devolve quantos foram removidos
+pub fn invalidate_analysis_cache(base_path: Option<&Path>) -> Result<usize> {
+    cache::invalidate(base_path)
+}
+
+// Churn, hotspots, donos por pasta e bus factor dos últimos `window_days` dias do git;
+// `limit` é quantos arquivos voltam nas listas de churn e hotspots
+pub fn git_analytics(base_path: &Path, window_days: u32, limit: usize) -> Result<GitAnalytics> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+    git_history::analyze(base_path, &scan.files, window_days, limit)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "O diretório não é um repositório git: {}",
+            base_path.display()
+        )
+    })
+}
+
+// Regras de sugestão ativas para o projeto (ou só as globais, sem `base_path`)
+pub fn suggestion_rules(base_path: Option<&Path>) -> Vec<SuggestionRuleInfo> {
+    rules::list_rules(base_path)
+}
+
+// Símbolos (funções, tipos, classes, constantes) de todos os arquivos suportados
+pub fn collect_symbols(base_path: &Path) -> Result<Vec<(PathBuf, Vec<SymbolInfo>)>> {
+    let cancel = AtomicBool::new(false);
+    let control = AnalysisControl::new(&cancel, &|_| {});
+    let scan = scan_files(base_path, &control)?;
+
+    Ok(scan
+        .files
+        .par_iter()
+        .filter_map(|(path, _)| Some((path.clone(), file_symbols(base_path, path)?)))
+        .collect())
+}
+
+// Símbolos de um arquivo só, para a atualização incremental do índice;
+// None quando a linguagem não é suportada ou o arquivo é grande demais
+pub fn file_symbols(base_path: &Path, path: &Path) -> Option<Vec<SymbolInfo>> {
+    Grammar::detect(path)?;
+    if fs::metadata(path).ok()?.len() >= MAX_FILE_SIZE {
+        return None;
     }
+    let content = fs::read_to_string(path).ok()?;
+    symbols::extract(base_path, path, &content)
+}
 
-    // Project size suggestions
-    if total_files > 100 {
-        suggestions.push(
-            "Projeto grande detectado. Considere modularizar o código em pacotes menores.".to_string(),
-        );
+pub fn source_language(path: &Path) -> Option<&'static str> {
+    Grammar::detect(path).map(Grammar::name)
+}
+
+struct ScanResult {
+    files: Vec<(PathBuf, u64)>,
+    total_directories: usize,
+    file_types: HashMap<String, usize>,
+}
+
+// Percorre a árvore em paralelo respeitando as regras de ignore
+fn scan_files(root: &Path, control: &AnalysisControl) -> Result<ScanResult> {
+    let ignore_rules = IgnoreRules::for_root(root);
+    let result = Mutex::new(ScanResult {
+        files: Vec::new(),
+        total_directories: 0,
+        file_types: HashMap::new(),
+    });
+
+    ignore_rules.walker(root).build_parallel().run(|| {
+        Box::new(|entry| {
+            if control.is_cancelled() {
+                return WalkState::Quit;
+            }
+
+            let entry = match entry {
+                Ok(e) => e,
+                Err(_) => return WalkState::Continue,
+            };
+
+            let path = entry.path();
+
+            if path.is_dir() {
+                control.directory_scanned(path);
+                result.lock().unwrap().total_directories += 1;
+            } else if path.is_file() {
+                control.file_scanned();
+                let size = entry.metadata().map(|m| m.len()).ok();
+
+                let mut result = result.lock().unwrap();
+
+                // Count file types
+                if let Some(ext_str) = path.extension().and_then(|ext| ext.to_str()) {
+                    if !IGNORED_EXTENSIONS.contains(&ext_str) {
+                        *result.file_types.entry(ext_str.to_string()).or_insert(0) += 1;
+                    }
+                }
+
+                // Collect file sizes
+                if let Some(size) = size {
+                    result.files.push((path.to_path_buf(), size));
+                }
+            }
+
+            WalkState::Continue
+        })
+    });
+
+    control.check_cancelled()?;
+    Ok(result.into_inner().unwrap())
+}
+
+struct ReadFiles {
+    contents: HashMap<PathBuf, Option<String>>,
+    line_counts: Vec<FileLines>,
+    secrets: Vec<SecretFinding>,
+}
+
+// Lê cada arquivo uma vez para contar linhas e procurar segredos; o conteúdo guardado
+// para o resumo já sai com os segredos removidos. Arquivos com o mesmo tamanho e mtime
+// do cache nem são lidos, e os que mudaram só são reprocessados se o hash mudou
+fn read_files(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    keep_content: &HashSet<&PathBuf>,
+    cache: &mut AnalysisCache,
+    control: &AnalysisControl,
+) -> Result<ReadFiles> {
+    struct FileResult<'a> {
+        path: &'a PathBuf,
+        key: String,
+        content: Option<String>,
+        cached: Option<CachedFile>,
+        reused: bool,
     }
 
-    // CI/CD suggestion
-    let has_ci = file_types.iter().any(|ft| ft.extension == "yml" || ft.extension == "yaml");
-    if !has_ci && total_files > 50 {
-        suggestions.push(
-            "Configure CI/CD (GitHub Actions, GitLab CI) para automatizar testes e deploys.".to_string(),
-        );
+    let previous = &*cache;
+    let results: Vec<FileResult> = files
+        .par_iter()
+        .filter(|(path, size)| *size <= MAX_LOC_FILE_SIZE || keep_content.contains(path))
+        .map(|(path, size)| {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let mut result = FileResult {
+                path,
+                key: relative.to_string_lossy().replace('\\', "/"),
+                content: None,
+                cached: None,
+                reused: false,
+            };
+            if control.is_cancelled() {
+                return result;
+            }
+
+            let modified = fs::metadata(path).ok().and_then(|m| cache::modified_nanos(&m));
+            let previous = previous.get(&result.key);
+
+            let keep = keep_content.contains(path);
+            if !keep {
+                if let Some(entry) = previous.filter(|entry| entry.matches(*size, modified)) {
+                    result.cached = Some(entry.clone());
+                    result.reused = true;
+                    return result;
+                }
+            }
+
+            let Ok(bytes) = fs::read(path) else {
+                return result;
+            };
+            control.bytes_read(bytes.len() as u64);
+
+            let hash = cache::content_hash(&bytes);
+            let (lines, found_secrets) = match previous.filter(|entry| entry.hash == hash) {
+                Some(entry) => {
+                    result.reused = true;
+                    (entry.lines.clone(), entry.secrets.clone())
+                }
+                None => {
+                    let is_binary = bytes.iter().take(BINARY_CHECK_BYTES).any(|b| *b == 0);
+                    if is_binary {
+                        (None, Vec::new())
+                    } else {
+                        let text = String::from_utf8_lossy(&bytes);
+                        (
+                            count_file_lines(relative, &text)
+                                .as_ref()
+                                .map(CachedLines::from_file_lines),
+                            secrets::scan(relative, &text),
+                        )
+                    }
+                }
+            };
+            result.cached = Some(CachedFile {
+                size: bytes.len() as u64,
+                modified: modified.unwrap_or_default(),
+                hash,
+                lines,
+                secrets: found_secrets,
+            });
+
+            if keep {
+                result.content = String::from_utf8(bytes)
+                    .ok()
+                    .map(|content| secrets::redact(relative, &content));
+            }
+            result
+        })
+        .collect();
+
+    control.check_cancelled()?;
+
+    let mut read = ReadFiles {
+        contents: HashMap::new(),
+        line_counts: Vec::new(),
+        secrets: Vec::new(),
+    };
+    let mut entries: HashMap<String, CachedFile> = HashMap::new();
+    let mut reused = 0;
+    for result in results {
+        if keep_content.contains(result.path) {
+            read.contents.insert(result.path.clone(), result.content);
+        }
+        let Some(entry) = result.cached else {
+            continue;
+        };
+        let relative = result.path.strip_prefix(root).unwrap_or(result.path);
+        read.line_counts.extend(entry.file_lines(relative));
+        read.secrets.extend(entry.secrets.iter().cloned());
+        if result.reused {
+            reused += 1;
+        }
+        entries.insert(result.key, entry);
     }
+    read.secrets
+        .sort_by(|a, b| a.file.cmp(&b.file).then_with(|| a.line.cmp(&b.line)));
 
-    // Generic best practice
-    if suggestions.is_empty() {
-        suggestions.push(
-            "Projeto bem estruturado! Continue mantendo boas práticas de desenvolvimento.".to_string(),
-        );
+    log::info!(
+        "Cache de análise: {} de {} arquivos reaproveitados",
+        reused,
+        entries.len()
+    );
+    cache.replace(entries);
+    if let Err(e) = cache.save() {
+        log::warn!("Não foi possível gravar o cache de análise: {}", e);
     }
 
-    suggestions
+    Ok(read)
+}
+
+fn relative_display(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+// Arquivos de linguagem desconhecida não entram na contagem
+fn count_file_lines(relative: &Path, text: &str) -> Option<FileLines> {
+    let language = languages::detect(relative, text.lines().next())?;
+
+    Some(FileLines {
+        relative_path: relative.to_path_buf(),
+        language,
+        counts: loc::count_lines(language, text),
+    })
 }
 
 fn generate_project_summary(
@@ -226,7 +765,9 @@ fn generate_project_summary(
     total_files: usize,
     total_directories: usize,
     file_types: &[FileTypeStat],
-    files_with_content: &[FileInfo],
+    languages: &[LanguageStats],
+    secrets: &[SecretFinding],
+    stack: &[DetectedTechnology],
 ) -> String {
     let mut summary = String::new();
     
@@ -235,10 +776,35 @@ fn generate_project_summary(
     summary.push_str(&format!("**Total de arquivos:** {}\n", total_files));
     summary.push_str(&format!("**Total de diretórios:** {}\n\n", total_directories));
     
-    // Detectar tipo de projeto
-    let project_type = detect_project_type(file_types);
-    if let Some(ptype) = project_type {
-        summary.push_str(&format!("**Tipo de projeto:** {}\n\n", ptype));
+    // Frameworks primeiro; sem nenhum, o tipo é dado pelas linguagens principais
+    let main_stack: Vec<&str> = stack
+        .iter()
+        .filter(|t| t.category == TechnologyCategory::Framework && t.confidence >= 0.8)
+        .chain(stack.iter().filter(|t| {
+            t.category == TechnologyCategory::Language && t.confidence >= 0.8
+        }))
+        .map(|t| t.name.as_str())
+        .collect();
+    if !main_stack.is_empty() {
+        summary.push_str(&format!("**Tipo de projeto:** {}\n\n", main_stack.join(", ")));
+    }
+
+    let tools: Vec<&DetectedTechnology> = stack
+        .iter()
+        .filter(|t| t.category != TechnologyCategory::Language)
+        .collect();
+    if !tools.is_empty() {
+        summary.push_str("## Stack detectada:\n");
+        for technology in tools {
+            summary.push_str(&format!(
+                "- {} ({}, {:.0}%): {}\n",
+                technology.name,
+                category_label(technology.category),
+                technology.confidence * 100.0,
+                technology.evidence.join("; ")
+            ));
+        }
+        summary.push('\n');
     }
     
     if !file_types.is_empty() {
@@ -253,33 +819,25 @@ fn generate_project_summary(
         }
         summary.push_str("\n");
     }
-    
-    // Adicionar conteúdo de arquivos de configuração importantes
-    summary.push_str(&read_important_files(root));
-    
-    // Adicionar conteúdo dos arquivos principais
-    let files_with_code: Vec<_> = files_with_content
-        .iter()
-        .filter(|f| f.content.is_some())
-        .collect();
-    
-    if !files_with_code.is_empty() {
-        summary.push_str("\n## Código-fonte do projeto (subpastas incluídas):\n\n");
-        
-        for file in files_with_code.iter().take(50) { // Aumentado de 10 para 50
-            if let Some(content) = &file.content {
-                let truncated = if content.len() > 3000 { // Aumentado de 1500 para 3000
-                    format!("{}...\n[Arquivo truncado - {} bytes totais]", 
-                        &content[..3000], 
-                        file.size
-                    )
-                } else {
-                    content.clone()
-                };
-                
-                summary.push_str(&format!("### {}\n```\n{}\n```\n\n", file.path, truncated));
-            }
+
+    if !languages.is_empty() {
+        summary.push_str("## Linhas de código por linguagem:\n");
+        summary.push_str("| Linguagem | Arquivos | Código | Comentários | Em branco |\n");
+        summary.push_str("|---|---|---|---|---|\n");
+        for language in languages.iter().take(10) {
+            summary.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                language.language, language.files, language.code, language.comments, language.blanks
+            ));
         }
+        summary.push('\n');
+    }
+
+    if !secrets.is_empty() {
+        summary.push_str(&format!(
+            "**Atenção:** {} possível(is) segredo(s) encontrado(s) no projeto. Os valores foram substituídos por [REDACTED] no conteúdo abaixo.\n\n",
+            secrets.len()
+        ));
     }
     
     summary
@@ -322,91 +880,14 @@ fn should_include_content(path: &str) -> bool {
     special_files.iter().any(|&f| filename.starts_with(f))
 }
 
-fn read_important_files(root: &PathBuf) -> String {
-    let mut content = String::new();
-    let important_files = [
-        "package.json",
-        "Cargo.toml",
-        "tsconfig.json",
-        "README.md",
-        "requirements.txt",
-        "go.mod",
-        "pyproject.toml",
-        "pom.xml",
-        "build.gradle",
-        "composer.json",
-        ".env.example",
-        "vite.config.ts",
-        "next.config.js",
-        "tailwind.config.js",
-    ];
-    
-    content.push_str("## Arquivos de configuração:\n\n");
-    let mut found_any = false;
-    
-    for file_name in &important_files {
-        let file_path = root.join(file_name);
-        if file_path.exists() {
-            if let Ok(file_content) = fs::read_to_string(&file_path) {
-                found_any = true;
-                // Limita o tamanho do conteúdo
-                let truncated = if file_content.len() > 2000 {
-                    format!("{}...\n[Conteúdo truncado - arquivo completo tem {} bytes]", 
-                        &file_content[..2000],
-                        file_content.len()
-                    )
-                } else {
-                    file_content
-                };
-                
-                content.push_str(&format!("### {}\n```\n{}\n```\n\n", file_name, truncated));
-            }
-        }
-    }
-    
-    if !found_any {
-        content.push_str("Nenhum arquivo de configuração padrão encontrado.\n\n");
+fn category_label(category: TechnologyCategory) -> &'static str {
+    match category {
+        TechnologyCategory::Language => "linguagem",
+        TechnologyCategory::Framework => "framework",
+        TechnologyCategory::BuildTool => "build",
+        TechnologyCategory::PackageManager => "gerenciador de pacotes",
+        TechnologyCategory::TestRunner => "testes",
+        TechnologyCategory::Ci => "CI",
+        TechnologyCategory::Tooling => "ferramenta",
     }
-    
-    content
-}
-
-fn detect_project_type(file_types: &[FileTypeStat]) -> Option<String> {
-    let extensions: Vec<&str> = file_types.iter().map(|ft| ft.extension.as_str()).collect();
-    
-    if extensions.contains(&"rs") {
-        return Some("Rust".to_string());
-    }
-    if extensions.contains(&"tsx") || extensions.contains(&"jsx") {
-        return Some("React/TypeScript".to_string());
-    }
-    if extensions.contains(&"ts") {
-        return Some("TypeScript".to_string());
-    }
-    if extensions.contains(&"js") {
-        return Some("JavaScript".to_string());
-    }
-    if extensions.contains(&"py") {
-        return Some("Python".to_string());
-    }
-    if extensions.contains(&"go") {
-        return Some("Go".to_string());
-    }
-    if extensions.contains(&"java") {
-        return Some("Java".to_string());
-    }
-    if extensions.contains(&"cs") {
-        return Some("C#".to_string());
-    }
-    if extensions.contains(&"cpp") || extensions.contains(&"cc") {
-        return Some("C++".to_string());
-    }
-    if extensions.contains(&"vue") {
-        return Some("Vue.js".to_string());
-    }
-    if extensions.contains(&"svelte") {
-        return Some("Svelte".to_string());
-    }
-    
-    None
 }
@@ -0,0 +1,721 @@
+use semver::{Version, VersionReq};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value as TomlValue;
+
+use super::dependencies::{self, CARGO, GO, NPM, PYPI};
+use crate::advisory_db::{self, OSV_DIR, OSV_INDEX_FILE, RUSTSEC_DIR};
+use crate::types::{Vulnerability, VulnerabilityAudit, VulnerabilitySeverity};
+
+struct LockedPackage {
+    ecosystem: &'static str,
+    name: String,
+    version: String,
+    lockfile: String,
+}
+
+struct Advisory {
+    id: String,
+    aliases: Vec<String>,
+    summary: Option<String>,
+    url: Option<String>,
+    cvss_vector: Option<String>,
+    severity_label: Option<String>,
+    rule: AffectedRule,
+}
+
+enum AffectedRule {
+    // RustSec: afetada quando a versão não atende nenhum requisito de patched/unaffected
+    RustSec {
+        patched: Vec<VersionReq>,
+        unaffected: Vec<VersionReq>,
+        fixed: Vec<String>,
+    },
+    // OSV: lista explícita de versões e/ou intervalos de eventos introduced/fixed
+    Osv {
+        versions: Vec<String>,
+        ranges: Vec<Vec<(String, String)>>,
+        fixed: Vec<String>,
+    },
+}
+
+impl Advisory {
+    fn affects(&self, version: &str) -> bool {
+        match &self.rule {
+            AffectedRule::RustSec {
+                patched,
+                unaffected,
+                ..
+            } => match Version::parse(version) {
+                Ok(version) => !patched
+                    .iter()
+                    .chain(unaffected)
+                    .any(|req| req.matches(&version)),
+                Err(_) => false,
+            },
+            AffectedRule::Osv {
+                versions, ranges, ..
+            } => {
+                let plain = version.trim_start_matches('v');
+                versions.iter().any(|v| v.trim_start_matches('v') == plain)
+                    || ranges.iter().any(|events| in_range(version, events))
+            }
+        }
+    }
+
+    fn fixed_versions(&self) -> Vec<String> {
+        match &self.rule {
+            AffectedRule::RustSec { fixed, .. } | AffectedRule::Osv { fixed, .. } => fixed.clone(),
+        }
+    }
+}
+
+// Nome do ecossistema usado pelo OSV
+fn osv_ecosystem(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        CARGO => "crates.io",
+        NPM => "npm",
+        PYPI => "PyPI",
+        GO => "Go",
+        _ => "",
+    }
+}
+
+// Cruza as versões travadas nos lockfiles do projeto com o banco local de advisories.
+// Não acessa a rede: fontes ausentes viram avisos no resultado.
+pub fn audit(root: &Path, files: &[(PathBuf, u64)], db_dir: &Path) -> VulnerabilityAudit {
+    let mut warnings = Vec::new();
+    let packages = locked_packages(root, files, &mut warnings);
+    let mut advisories_checked = HashSet::new();
+    let mut vulnerabilities = Vec::new();
+
+    let ecosystems: BTreeSet<&str> = packages.iter().map(|p| p.ecosystem).collect();
+    for ecosystem in ecosystems {
+        let osv_name = osv_ecosystem(ecosystem);
+        let names: HashSet<String> = packages
+            .iter()
+            .filter(|p| p.ecosystem == ecosystem)
+            .map(|p| advisory_db::index_key(osv_name, &p.name))
+            .collect();
+
+        let advisories = load_advisories(db_dir, ecosystem, &names, &mut warnings);
+        advisories_checked.extend(
+            advisories
+                .values()
+                .flatten()
+                .map(|advisory| advisory.id.clone()),
+        );
+
+        for package in packages.iter().filter(|p| p.ecosystem == ecosystem) {
+            let key = advisory_db::index_key(osv_name, &package.name);
+            for advisory in advisories.get(&key).into_iter().flatten() {
+                if advisory.affects(&package.version) {
+                    vulnerabilities.push(vulnerability(package, advisory));
+                }
+            }
+        }
+    }
+
+    vulnerabilities.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then_with(|| a.package.cmp(&b.package))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    vulnerabilities
+        .dedup_by(|a, b| a.id == b.id && a.package == b.package && a.version == b.version);
+
+    VulnerabilityAudit {
+        database_path: db_dir.display().to_string(),
+        packages_checked: packages.len(),
+        advisories_checked: advisories_checked.len(),
+        vulnerabilities,
+        warnings,
+    }
+}
+
+fn locked_packages(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    warnings: &mut Vec<String>,
+) -> Vec<LockedPackage> {
+    let mut packages = Vec::new();
+
+    for (path, _) in files {
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let Some(ecosystem) = dependencies::lockfile_ecosystem(&file_name) else {
+            continue;
+        };
+        let lockfile = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        match dependencies::parse_lockfile(path) {
+            Ok(locked) => {
+                packages.extend(locked.packages().map(|(name, version)| LockedPackage {
+                    ecosystem,
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    lockfile: lockfile.clone(),
+                }));
+            }
+            Err(e) => warnings.push(format!("Não foi possível ler {}: {:#}", lockfile, e)),
+        }
+    }
+
+    packages
+}
+
+fn load_advisories(
+    db_dir: &Path,
+    ecosystem: &str,
+    names: &HashSet<String>,
+    warnings: &mut Vec<String>,
+) -> HashMap<String, Vec<Advisory>> {
+    let mut advisories: HashMap<String, Vec<Advisory>> = HashMap::new();
+    let osv_name = osv_ecosystem(ecosystem);
+    let osv_dir = db_dir.join(OSV_DIR).join(osv_name);
+    let mut found_source = false;
+
+    if ecosystem == CARGO {
+        let rustsec_dir = db_dir.join(RUSTSEC_DIR).join("crates");
+        if rustsec_dir.is_dir() {
+            found_source = true;
+            for name in names {
+                let files = fs::read_dir(rustsec_dir.join(name)).into_iter().flatten();
+                for file in files.filter_map(|f| f.ok()) {
+                    let advisory = fs::read_to_string(file.path())
+                        .ok()
+                        .and_then(|content| parse_rustsec(&content));
+                    if let Some(advisory) = advisory {
+                        advisories.entry(name.clone()).or_default().push(advisory);
+                    }
+                }
+            }
+        }
+    }
+
+    if osv_dir.is_dir() {
+        found_source = true;
+        for file in osv_files(&osv_dir, names) {
+            let Some(data) = fs::read(&file)
+                .ok()
+                .and_then(|content| serde_json::from_slice::<Value>(&content).ok())
+            else {
+                continue;
+            };
+            for (name, advisory) in parse_osv(&data, osv_name) {
+                let key = advisory_db::index_key(osv_name, &name);
+                if names.contains(&key) {
+                    advisories.entry(key).or_default().push(advisory);
+                }
+            }
+        }
+    }
+
+    if !found_source {
+        warnings.push(format!(
+            "Nenhuma base de vulnerabilidades para {} em {}. Atualize o banco de advisories.",
+            osv_name,
+            db_dir.display()
+        ));
+    }
+
+    advisories
+}
+
+// Com index.json (gerado na atualização) lê só os arquivos dos pacotes usados;
+// sem índice (export extraído manualmente) lê o diretório inteiro
+fn osv_files(dir: &Path, names: &HashSet<String>) -> Vec<PathBuf> {
+    let index: Option<HashMap<String, Vec<String>>> = fs::read_to_string(dir.join(OSV_INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    match index {
+        Some(index) => {
+            let files: BTreeSet<&String> = names
+                .iter()
+                .filter_map(|name| index.get(name))
+                .flatten()
+                .collect();
+            files.into_iter().map(|file| dir.join(file)).collect()
+        }
+        None => fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|f| f.ok())
+            .map(|f| f.path())
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "json")
+                    && path.file_name().is_some_and(|name| name != OSV_INDEX_FILE)
+            })
+            .collect(),
+    }
+}
+
+// Advisory do RustSec: front matter TOML entre ```toml e ```, seguido do texto em Markdown
+fn parse_rustsec(content: &str) -> Option<Advisory> {
+    let body = content.trim_start().strip_prefix("```toml")?;
+    let (front_matter, text) = body.split_once("```")?;
+    let data: TomlValue = toml::from_str(front_matter).ok()?;
+    let advisory = data.get("advisory")?;
+
+    // Avisos informativos (unmaintained, unsound) e advisories retirados não são vulnerabilidades
+    if advisory.get("withdrawn").is_some() || advisory.get("informational").is_some() {
+        return None;
+    }
+
+    let text_of = |value: Option<&TomlValue>| value.and_then(|v| v.as_str()).map(|s| s.to_string());
+    let strings = |value: Option<&TomlValue>| -> Vec<String> {
+        value
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let requirements = |values: &[String]| -> Vec<VersionReq> {
+        values
+            .iter()
+            .filter_map(|req| VersionReq::parse(req).ok())
+            .collect()
+    };
+
+    let id = text_of(advisory.get("id"))?;
+    let versions = data.get("versions");
+    let patched = strings(versions.and_then(|v| v.get("patched")));
+    let unaffected = strings(versions.and_then(|v| v.get("unaffected")));
+    let title = text
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string());
+
+    Some(Advisory {
+        url: text_of(advisory.get("url"))
+            .or_else(|| Some(format!("https://rustsec.org/advisories/{}", id))),
+        aliases: strings(advisory.get("aliases")),
+        summary: title.or_else(|| text_of(advisory.get("title"))),
+        cvss_vector: text_of(advisory.get("cvss")),
+        severity_label: None,
+        rule: AffectedRule::RustSec {
+            patched: requirements(&patched),
+            unaffected: requirements(&unaffected),
+            fixed: patched,
+        },
+        id,
+    })
+}
+
+// Um advisory OSV pode afetar vários pacotes; devolve uma regra por pacote do ecossistema
+fn parse_osv(data: &Value, ecosystem: &str) -> Vec<(String, Advisory)> {
+    if data.get("withdrawn").is_some() {
+        return Vec::new();
+    }
+
+    let Some(id) = data["id"].as_str() else {
+        return Vec::new();
+    };
+    let strings = |value: &Value| -> Vec<String> {
+        value
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let summary = data["summary"]
+        .as_str()
+        .or_else(|| data["details"].as_str().and_then(|d| d.lines().next()))
+        .map(|s| s.trim().to_string());
+    let cvss_vector = data["severity"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|s| s["type"].as_str().is_some_and(|t| t.starts_with("CVSS")))
+        .filter_map(|s| s["score"].as_str())
+        .min_by_key(|vector| !vector.starts_with("CVSS:3"))
+        .map(|s| s.to_string());
+
+    data["affected"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|affected| affected["package"]["ecosystem"].as_str() == Some(ecosystem))
+        .filter_map(|affected| {
+            let name = affected["package"]["name"].as_str()?.to_string();
+
+            let mut fixed = Vec::new();
+            let ranges = affected["ranges"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|range| range["type"].as_str() != Some("GIT"))
+                .map(|range| {
+                    range["events"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|event| {
+                            let (kind, version) = event.as_object()?.iter().next()?;
+                            let version = version.as_str()?.to_string();
+                            if kind == "fixed" && !fixed.contains(&version) {
+                                fixed.push(version.clone());
+                            }
+                            Some((kind.clone(), version))
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let severity_label = data["database_specific"]["severity"]
+                .as_str()
+                .or_else(|| affected["ecosystem_specific"]["severity"].as_str())
+                .or_else(|| affected["database_specific"]["severity"].as_str())
+                .map(|s| s.to_string());
+
+            Some((
+                name,
+                Advisory {
+                    id: id.to_string(),
+                    aliases: strings(&data["aliases"]),
+                    summary: summary.clone(),
+                    url: Some(format!("https://osv.dev/vulnerability/{}", id)),
+                    cvss_vector: cvss_vector.clone(),
+                    severity_label,
+                    rule: AffectedRule::Osv {
+                        versions: strings(&affected["versions"]),
+                        ranges,
+                        fixed,
+                    },
+                },
+            ))
+        })
+        .collect()
+}
+
+// Avalia os eventos em ordem de versão: `introduced` abre o intervalo, `fixed` e
+// `last_affected` fecham
+fn in_range(version: &str, events: &[(String, String)]) -> bool {
+    let mut events: Vec<&(String, String)> = events.iter().collect();
+    events.sort_by(|a, b| compare_versions(&a.1, &b.1));
+
+    let mut affected = false;
+    for (kind, at) in events {
+        let ordering = compare_versions(version, at);
+        match kind.as_str() {
+            "introduced" if at == "0" || ordering != Ordering::Less => affected = true,
+            "fixed" if ordering != Ordering::Less => affected = false,
+            "last_affected" if ordering == Ordering::Greater => affected = false,
+            _ => {}
+        }
+    }
+    affected
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| Version::parse(v.trim_start_matches('v')).ok();
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => dependencies::version_numbers(a).cmp(&dependencies::version_numbers(b)),
+    }
+}
+
+fn vulnerability(package: &LockedPackage, advisory: &Advisory) -> Vulnerability {
+    let cvss_score = advisory.cvss_vector.as_deref().and_then(cvss3_base_score);
+    let severity = match (cvss_score, advisory.severity_label.as_deref()) {
+        (Some(score), _) if score >= 9.0 => VulnerabilitySeverity::Critical,
+        (Some(score), _) if score >= 7.0 => VulnerabilitySeverity::High,
+        (Some(score), _) if score >= 4.0 => VulnerabilitySeverity::Medium,
+        (Some(_), _) => VulnerabilitySeverity::Low,
+        (None, Some(label)) => match label.to_uppercase().as_str() {
+            "CRITICAL" => VulnerabilitySeverity::Critical,
+            "HIGH" => VulnerabilitySeverity::High,
+            "MODERATE" | "MEDIUM" => VulnerabilitySeverity::Medium,
+            "LOW" => VulnerabilitySeverity::Low,
+            _ => VulnerabilitySeverity::Unknown,
+        },
+        (None, None) => VulnerabilitySeverity::Unknown,
+    };
+
+    Vulnerability {
+        id: advisory.id.clone(),
+        aliases: advisory.aliases.clone(),
+        package: package.name.clone(),
+        version: package.version.clone(),
+        ecosystem: package.ecosystem.to_string(),
+        lockfile: package.lockfile.clone(),
+        severity,
+        cvss_score,
+        cvss_vector: advisory.cvss_vector.clone(),
+        summary: advisory.summary.clone(),
+        fixed_versions: advisory.fixed_versions(),
+        url: advisory.url.clone(),
+    }
+}
+
+// Nota base CVSS v3.x calculada a partir do vetor (especificação FIRST)
+fn cvss3_base_score(vector: &str) -> Option<f64> {
+    let metrics: HashMap<&str, &str> = vector
+        .strip_prefix("CVSS:3.")?
+        .split('/')
+        .skip(1)
+        .filter_map(|metric| metric.split_once(':'))
+        .collect();
+
+    let scope_changed = *metrics.get("S")? == "C";
+    let attack_vector = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        _ => 0.2,
+    };
+    let attack_complexity = if *metrics.get("AC")? == "L" {
+        0.77
+    } else {
+        0.44
+    };
+    let privileges = match (*metrics.get("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        (_, false) => 0.27,
+        (_, true) => 0.5,
+    };
+    let user_interaction = if *metrics.get("UI")? == "N" {
+        0.85
+    } else {
+        0.62
+    };
+    let impact_of = |key: &str| -> Option<f64> {
+        Some(match *metrics.get(key)? {
+            "H" => 0.56,
+            "L" => 0.22,
+            _ => 0.0,
+        })
+    };
+
+    let impact_subscore =
+        1.0 - (1.0 - impact_of("C")?) * (1.0 - impact_of("I")?) * (1.0 - impact_of("A")?);
+    let impact = if scope_changed {
+        7.52 * (impact_subscore - 0.029) - 3.25 * (impact_subscore - 0.02).powi(15)
+    } else {
+        6.42 * impact_subscore
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * user_interaction;
+    let score = if scope_changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+
+    // Arredondamento para cima em uma casa decimal
+    Some((score * 10.0 - 1e-9).ceil() / 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(kind, version)| (kind.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn in_range_opens_on_introduced_and_closes_on_fixed() {
+        let single = events(&[("introduced", "0"), ("fixed", "1.2.3")]);
+        assert!(in_range("0.1.0", &single));
+        assert!(in_range("1.2.2", &single));
+        assert!(!in_range("1.2.3", &single));
+        assert!(!in_range("2.0.0", &single));
+
+        // Eventos fora de ordem e dois intervalos no mesmo range
+        let split = events(&[
+            ("fixed", "2.0.5"),
+            ("introduced", "1.0.0"),
+            ("introduced", "2.0.0"),
+            ("fixed", "1.1.0"),
+        ]);
+        assert!(!in_range("0.9.0", &split));
+        assert!(in_range("1.0.0", &split));
+        assert!(!in_range("1.5.0", &split));
+        assert!(in_range("2.0.4", &split));
+        assert!(!in_range("2.0.5", &split));
+    }
+
+    #[test]
+    fn in_range_last_affected_and_prereleases() {
+        let last = events(&[("introduced", "1.0.0"), ("last_affected", "3.0.0")]);
+        assert!(in_range("3.0.0", &last));
+        assert!(!in_range("3.0.1", &last));
+
+        let fixed = events(&[("introduced", "0"), ("fixed", "2.0.0")]);
+        assert!(in_range("2.0.0-rc.1", &fixed));
+    }
+
+    #[test]
+    fn in_range_go_and_python_versions() {
+        let go = events(&[("introduced", "0"), ("fixed", "0.17.0")]);
+        assert!(in_range("v0.16.0", &go));
+        assert!(!in_range("v0.17.0", &go));
+
+        // Versões PyPI que não são semver caem na comparação numérica
+        let pypi = events(&[("introduced", "2.0"), ("fixed", "2.32.0")]);
+        assert!(in_range("2.31", &pypi));
+        assert!(!in_range("2.32.3", &pypi));
+        assert!(!in_range("1.9", &pypi));
+    }
+
+    #[test]
+    fn cvss3_base_score_matches_reference_values() {
+        let cases = [
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H", 10.0),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H", 7.5),
+            ("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:N", 5.9),
+            ("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H", 7.8),
+            ("CVSS:3.0/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N", 6.1),
+            ("CVSS:3.1/AV:P/AC:H/PR:H/UI:R/S:U/C:L/I:N/A:N", 1.6),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N", 0.0),
+        ];
+        for (vector, expected) in cases {
+            assert_eq!(cvss3_base_score(vector), Some(expected), "{}", vector);
+        }
+    }
+
+    #[test]
+    fn cvss3_base_score_rejects_other_versions_and_incomplete_vectors() {
+        assert_eq!(cvss3_base_score("AV:N/AC:L/Au:N/C:P/I:P/A:P"), None);
+        assert_eq!(
+            cvss3_base_score("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"),
+            None
+        );
+        assert_eq!(cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U"), None);
+    }
+
+    const RUSTSEC_ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2023-0001"
+package = "tokio"
+date = "2023-01-04"
+url = "https://github.com/tokio-rs/tokio/security/advisories/GHSA-7rrj-xr53-82p7"
+aliases = ["CVE-2023-22466", "GHSA-7rrj-xr53-82p7"]
+cvss = "CVSS:3.1/AV:L/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:H"
+
+[versions]
+patched = [">= 1.18.4, < 1.19.0", ">= 1.20.3, < 1.21.0", ">= 1.23.1"]
+unaffected = ["< 0.2.5"]
+```
+
+# reject_remote_clients configuration may get dropped when creating a Windows named pipe
+
+Texto do advisory.
+"#;
+
+    #[test]
+    fn parse_rustsec_reads_front_matter_and_title() {
+        let advisory = parse_rustsec(RUSTSEC_ADVISORY).unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2023-0001");
+        assert_eq!(advisory.aliases, ["CVE-2023-22466", "GHSA-7rrj-xr53-82p7"]);
+        assert_eq!(
+            advisory.summary.as_deref(),
+            Some(
+                "reject_remote_clients configuration may get dropped when creating a Windows named pipe"
+            )
+        );
+        assert_eq!(
+            advisory.url.as_deref(),
+            Some("https://github.com/tokio-rs/tokio/security/advisories/GHSA-7rrj-xr53-82p7")
+        );
+        assert_eq!(
+            advisory.fixed_versions(),
+            [">= 1.18.4, < 1.19.0", ">= 1.20.3, < 1.21.0", ">= 1.23.1"]
+        );
+        assert_eq!(
+            advisory.cvss_vector.as_deref().and_then(cvss3_base_score),
+            Some(5.1)
+        );
+
+        assert!(advisory.affects("1.18.3"));
+        assert!(!advisory.affects("1.18.4"));
+        assert!(advisory.affects("1.19.2"));
+        assert!(!advisory.affects("1.20.3"));
+        assert!(advisory.affects("1.22.0"));
+        assert!(!advisory.affects("1.40.0"));
+        assert!(!advisory.affects("0.2.4"));
+        assert!(!advisory.affects("não é versão"));
+    }
+
+    #[test]
+    fn parse_rustsec_skips_informational_withdrawn_and_invalid() {
+        let informational = RUSTSEC_ADVISORY.replace(
+            "package = \"tokio\"",
+            "package = \"tokio\"\ninformational = \"unmaintained\"",
+        );
+        assert!(parse_rustsec(&informational).is_none());
+
+        let withdrawn = RUSTSEC_ADVISORY.replace(
+            "package = \"tokio\"",
+            "package = \"tokio\"\nwithdrawn = \"2023-02-01\"",
+        );
+        assert!(parse_rustsec(&withdrawn).is_none());
+
+        assert!(parse_rustsec("# Sem front matter").is_none());
+        assert!(parse_rustsec("```toml\n[advisory]\npackage = \"x\"\n```\n").is_none());
+    }
+
+    #[test]
+    fn parse_osv_builds_one_rule_per_package_of_the_ecosystem() {
+        let data: Value = serde_json::from_str(
+            r#"{
+                "id": "GHSA-xxxx-yyyy-zzzz",
+                "aliases": ["CVE-2024-0001"],
+                "summary": "Prototype pollution",
+                "severity": [{ "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H" }],
+                "affected": [
+                    {
+                        "package": { "ecosystem": "npm", "name": "lodash" },
+                        "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "4.17.21" }] }]
+                    },
+                    {
+                        "package": { "ecosystem": "npm", "name": "lodash-es" },
+                        "versions": ["4.17.20"]
+                    },
+                    { "package": { "ecosystem": "PyPI", "name": "lodash" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let rules = parse_osv(&data, "npm");
+        assert_eq!(rules.len(), 2);
+        let (name, advisory) = &rules[0];
+        assert_eq!(name, "lodash");
+        assert!(advisory.affects("4.17.20"));
+        assert!(!advisory.affects("4.17.21"));
+        assert_eq!(advisory.fixed_versions(), ["4.17.21"]);
+        let (name, advisory) = &rules[1];
+        assert_eq!(name, "lodash-es");
+        assert!(advisory.affects("4.17.20"));
+        assert!(!advisory.affects("4.17.19"));
+    }
+}
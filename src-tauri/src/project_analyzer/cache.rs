@@ -0,0 +1,244 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+use super::languages;
+use super::loc::{FileLines, LineCounts};
+use crate::types::SecretFinding;
+
+pub const ANALYSIS_CACHE_ENV: &str = "RZONEHUB_ANALYSIS_CACHE";
+
+// Muda quando o formato ou as regras (linhas, segredos) mudam de um jeito que
+// invalida os resultados antigos
+const CACHE_VERSION: u32 = 2;
+
+// Resultado por arquivo da leitura da análise; um arquivo só é relido quando
+// tamanho ou mtime mudam, e só é reprocessado quando o hash do conteúdo muda
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    pub size: u64,
+    pub modified: u64,
+    pub hash: String,
+    pub lines: Option<CachedLines>,
+    pub secrets: Vec<SecretFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedLines {
+    pub language: String,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    app_version: String,
+    root: String,
+    files: HashMap<String, CachedFile>,
+}
+
+// Cache de um workspace, gravado em `<data dir>/RZOneHub/analysis-cache/<hash da raiz>.json`
+pub struct AnalysisCache {
+    root: PathBuf,
+    path: Option<PathBuf>,
+    files: HashMap<String, CachedFile>,
+}
+
+impl AnalysisCache {
+    // Cache ausente, corrompido ou de outra versão do app começa vazio
+    pub fn load(root: &Path) -> AnalysisCache {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let path = default_dir().map(|dir| dir.join(cache_file_name(&root)));
+        let files = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| {
+                cache.version == CACHE_VERSION && cache.app_version == env!("CARGO_PKG_VERSION")
+            })
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+
+        AnalysisCache { root, path, files }
+    }
+
+    pub fn get(&self, relative: &str) -> Option<&CachedFile> {
+        self.files.get(relative)
+    }
+
+    // Substitui todas as entradas: arquivos que sumiram do workspace saem do cache
+    pub fn replace(&mut self, files: HashMap<String, CachedFile>) {
+        self.files = files;
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            root: self.root.to_string_lossy().to_string(),
+            files: self.files.clone(),
+        };
+        write_atomic(path, &serde_json::to_vec(&cache)?)
+    }
+}
+
+impl CachedFile {
+    pub fn matches(&self, size: u64, modified: Option<u64>) -> bool {
+        self.size == size && modified == Some(self.modified)
+    }
+
+    pub fn file_lines(&self, relative: &Path) -> Option<FileLines> {
+        let lines = self.lines.as_ref()?;
+        Some(FileLines {
+            relative_path: relative.to_path_buf(),
+            language: languages::by_name(&lines.language)?,
+            counts: LineCounts {
+                code: lines.code,
+                comments: lines.comments,
+                blanks: lines.blanks,
+            },
+        })
+    }
+}
+
+impl CachedLines {
+    pub fn from_file_lines(lines: &FileLines) -> CachedLines {
+        CachedLines {
+            language: lines.language.name.to_string(),
+            code: lines.counts.code,
+            comments: lines.counts.comments,
+            blanks: lines.counts.blanks,
+        }
+    }
+}
+
+pub fn default_dir() -> Option<PathBuf> {
+    std::env::var_os(ANALYSIS_CACHE_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("RZOneHub").join("analysis-cache")))
+}
+
+// Remove o cache de um workspace (ou de todos, sem `root`); devolve quantos foram apagados
+pub fn invalidate(root: Option<&Path>) -> Result<usize> {
+    let Some(dir) = default_dir() else {
+        return Ok(0);
+    };
+    let targets: Vec<PathBuf> = match root {
+        Some(root) => {
+            let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+            vec![dir.join(cache_file_name(&root))]
+        }
+        None => match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+    };
+
+    let mut removed = 0;
+    for path in targets {
+        if path.is_file() {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// Grava num temporário e renomeia para não deixar um JSON pela metade. O temporário é
+// único por processo e gravação: duas análises do mesmo workspace não disputam o arquivo
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp, bytes)?;
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+pub fn modified_nanos(metadata: &fs::Metadata) -> Option<u64> {
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(modified.as_nanos()).ok()
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+fn cache_file_name(root: &Path) -> String {
+    format!("{}.json", workspace_key(root))
+}
+
+// Identificador curto do workspace (hash da raiz já canonicalizada)
+pub fn workspace_key(root: &Path) -> String {
+    content_hash(root.to_string_lossy().as_bytes())[..16].to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_replaces_file_without_leaving_temporaries() {
+        let dir = std::env::temp_dir().join(format!(
+            "rzonehub-cache-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("workspace.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    write_atomic(&path, format!("{{\"n\":{}}}", i).as_bytes())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&content).is_ok());
+        let names: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("workspace.json")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,572 @@
+use std::path::Path;
+use tree_sitter::Node;
+
+use super::syntax::{receiver_type, Grammar};
+use crate::types::{FileMetrics, FunctionMetrics};
+
+// Tipos de nó de cada gramática que importam para as métricas
+struct LanguageSpec {
+    // Funções que sempre viram uma unidade própria
+    functions: &'static [&'static str],
+    // Closures/lambdas: dentro de uma função contam como aninhamento da função
+    // que as contém; no nível de topo viram unidade própria
+    anonymous: &'static [&'static str],
+    // Nós que qualificam o nome (impl, classe, trait) e o campo com o nome
+    containers: &'static [(&'static str, &'static str)],
+    separator: &'static str,
+    ifs: &'static [&'static str],
+    // Laços, catch, ternários: +1 ciclomática, +1 + aninhamento cognitiva
+    branches: &'static [&'static str],
+    // switch/match: só a cognitiva conta o bloco; a ciclomática conta os casos
+    switches: &'static [&'static str],
+    cases: &'static [&'static str],
+    // elif do Python: funciona como um else if
+    else_ifs: &'static [&'static str],
+    logical: &'static str,
+    logical_operators: &'static [&'static str],
+}
+
+const RUST: LanguageSpec = LanguageSpec {
+    functions: &["function_item"],
+    anonymous: &["closure_expression"],
+    containers: &[("impl_item", "type"), ("trait_item", "name")],
+    separator: "::",
+    ifs: &["if_expression"],
+    branches: &["while_expression", "for_expression", "loop_expression"],
+    switches: &["match_expression"],
+    cases: &["match_arm"],
+    else_ifs: &[],
+    logical: "binary_expression",
+    logical_operators: &["&&", "||"],
+};
+
+// Serve para JavaScript e TypeScript (as gramáticas compartilham os nomes dos nós)
+const JAVASCRIPT: LanguageSpec = LanguageSpec {
+    functions: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "method_definition",
+    ],
+    anonymous: &[
+        "function_expression",
+        "generator_function",
+        "arrow_function",
+    ],
+    containers: &[
+        ("class_declaration", "name"),
+        ("abstract_class_declaration", "name"),
+        ("class", "name"),
+    ],
+    separator: ".",
+    ifs: &["if_statement"],
+    branches: &[
+        "for_statement",
+        "for_in_statement",
+        "while_statement",
+        "do_statement",
+        "catch_clause",
+        "ternary_expression",
+    ],
+    switches: &["switch_statement"],
+    cases: &["switch_case"],
+    else_ifs: &[],
+    logical: "binary_expression",
+    logical_operators: &["&&", "||", "??"],
+};
+
+const PYTHON: LanguageSpec = LanguageSpec {
+    functions: &["function_definition"],
+    anonymous: &["lambda"],
+    containers: &[("class_definition", "name")],
+    separator: ".",
+    ifs: &["if_statement"],
+    branches: &[
+        "for_statement",
+        "while_statement",
+        "except_clause",
+        "except_group_clause",
+        "conditional_expression",
+        "if_clause",
+    ],
+    switches: &["match_statement"],
+    cases: &["case_clause"],
+    else_ifs: &["elif_clause"],
+    logical: "boolean_operator",
+    logical_operators: &["and", "or"],
+};
+
+const GO: LanguageSpec = LanguageSpec {
+    functions: &["function_declaration", "method_declaration"],
+    anonymous: &["func_literal"],
+    containers: &[],
+    separator: ".",
+    ifs: &["if_statement"],
+    branches: &["for_statement"],
+    switches: &[
+        "expression_switch_statement",
+        "type_switch_statement",
+        "select_statement",
+    ],
+    cases: &["expression_case", "type_case", "communication_case"],
+    else_ifs: &[],
+    logical: "binary_expression",
+    logical_operators: &["&&", "||"],
+};
+
+// Nós pais de onde dá para tirar o nome de uma função anônima (`const f = () => ...`)
+const NAMING_PARENTS: &[(&str, &str)] = &[
+    ("variable_declarator", "name"),
+    ("pair", "key"),
+    ("public_field_definition", "name"),
+    ("field_definition", "property"),
+    ("assignment_expression", "left"),
+    ("assignment", "left"),
+    ("let_declaration", "pattern"),
+    ("static_item", "name"),
+    ("const_item", "name"),
+    ("short_var_declaration", "left"),
+];
+
+const ANONYMOUS_NAME: &str = "(anônima)";
+
+fn spec(grammar: Grammar) -> &'static LanguageSpec {
+    match grammar {
+        Grammar::Rust => &RUST,
+        Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript => &JAVASCRIPT,
+        Grammar::Python => &PYTHON,
+        Grammar::Go => &GO,
+    }
+}
+
+struct Unit {
+    name: String,
+    line: usize,
+    end_line: usize,
+    parameters: usize,
+    cyclomatic: usize,
+    cognitive: usize,
+    max_nesting: usize,
+}
+
+// Complexidade ciclomática (McCabe: 1 + pontos de decisão) e cognitiva (no estilo da
+// SonarSource, sem os incrementos de recursão e de break com label) de cada função
+pub fn analyze_file(relative: &Path, source: &str) -> Option<Vec<FunctionMetrics>> {
+    let grammar = Grammar::detect(relative)?;
+    let spec = spec(grammar);
+    let tree = grammar.parse(source)?;
+    let bytes = source.as_bytes();
+
+    let mut units: Vec<Unit> = Vec::new();
+    // Percurso iterativo: árvores de expressões longas estourariam a pilha na recursão
+    let mut stack: Vec<(Node, usize, Option<usize>)> = vec![(tree.root_node(), 0, None)];
+
+    while let Some((node, nesting, unit)) = stack.pop() {
+        let kind = node.kind();
+        let mut child_nesting = nesting;
+        let mut child_unit = unit;
+
+        let is_anonymous = spec.anonymous.contains(&kind);
+        if spec.functions.contains(&kind) || (is_anonymous && unit.is_none()) {
+            units.push(Unit {
+                name: function_name(spec, node, bytes),
+                line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+                parameters: count_parameters(node, bytes),
+                cyclomatic: 1,
+                cognitive: 0,
+                max_nesting: 0,
+            });
+            child_nesting = 0;
+            child_unit = Some(units.len() - 1);
+        } else if let Some(index) = unit {
+            let current = &mut units[index];
+
+            if is_anonymous {
+                child_nesting = nesting + 1;
+            } else if spec.ifs.contains(&kind) {
+                current.cyclomatic += 1;
+                if is_else_if(spec, node) {
+                    current.cognitive += 1;
+                } else {
+                    current.cognitive += 1 + nesting;
+                    child_nesting = nesting + 1;
+                }
+                // Go não tem nó de else: o bloco vem direto no campo `alternative`
+                if node
+                    .child_by_field_name("alternative")
+                    .is_some_and(|alternative| alternative.kind() == "block")
+                {
+                    current.cognitive += 1;
+                }
+            } else if spec.branches.contains(&kind) {
+                current.cyclomatic += 1;
+                current.cognitive += 1 + nesting;
+                child_nesting = nesting + 1;
+            } else if spec.else_ifs.contains(&kind) {
+                current.cyclomatic += 1;
+                current.cognitive += 1;
+            } else if kind == "else_clause" && is_plain_else(spec, node) {
+                current.cognitive += 1;
+            } else if spec.switches.contains(&kind) {
+                current.cognitive += 1 + nesting;
+                child_nesting = nesting + 1;
+            } else if spec.cases.contains(&kind) && !is_wildcard_case(node, bytes) {
+                current.cyclomatic += 1;
+            } else if let Some(operator) = logical_operator(spec, node) {
+                current.cyclomatic += 1;
+                // Uma sequência do mesmo operador (a && b && c) conta uma vez só
+                if node
+                    .parent()
+                    .and_then(|parent| logical_operator(spec, parent))
+                    != Some(operator)
+                {
+                    current.cognitive += 1;
+                }
+            }
+
+            current.max_nesting = current.max_nesting.max(child_nesting);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            stack.push((child, child_nesting, child_unit));
+        }
+    }
+
+    let file = relative.to_string_lossy().replace('\\', "/");
+    let mut functions: Vec<FunctionMetrics> = units
+        .into_iter()
+        .map(|unit| FunctionMetrics {
+            name: unit.name,
+            file: file.clone(),
+            language: grammar.name().to_string(),
+            line: unit.line,
+            end_line: unit.end_line,
+            lines: unit.end_line - unit.line + 1,
+            parameters: unit.parameters,
+            cyclomatic: unit.cyclomatic,
+            cognitive: unit.cognitive,
+            max_nesting: unit.max_nesting,
+        })
+        .collect();
+    functions.sort_by_key(|function| function.line);
+    Some(functions)
+}
+
+fn is_else_if(spec: &LanguageSpec, node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    parent.kind() == "else_clause"
+        || (spec.ifs.contains(&parent.kind())
+            && parent.child_by_field_name("alternative") == Some(node))
+}
+
+// Só o else de um if; o else de for/while/try do Python não conta
+fn is_plain_else(spec: &LanguageSpec, node: Node) -> bool {
+    let is_if_else = node
+        .parent()
+        .is_some_and(|parent| spec.ifs.contains(&parent.kind()));
+    let mut cursor = node.walk();
+    let wraps_if = node
+        .named_children(&mut cursor)
+        .any(|child| spec.ifs.contains(&child.kind()));
+    is_if_else && !wraps_if
+}
+
+// `_ =>` no Rust e `case _:` no Python fazem o papel do default
+fn is_wildcard_case(node: Node, source: &[u8]) -> bool {
+    node.child_by_field_name("pattern")
+        .or_else(|| node.named_child(0))
+        .and_then(|pattern| pattern.utf8_text(source).ok())
+        .is_some_and(|pattern| pattern.trim() == "_")
+}
+
+fn logical_operator<'a>(spec: &LanguageSpec, node: Node<'a>) -> Option<&'a str> {
+    if node.kind() != spec.logical {
+        return None;
+    }
+    let operator = node.child_by_field_name("operator")?.kind();
+    spec.logical_operators
+        .contains(&operator)
+        .then_some(operator)
+}
+
+fn function_name(spec: &LanguageSpec, node: Node, source: &[u8]) -> String {
+    let text = |node: Node| node.utf8_text(source).unwrap_or_default().to_string();
+
+    let name = node
+        .child_by_field_name("name")
+        .map(text)
+        .or_else(|| {
+            let parent = node.parent()?;
+            let (_, field) = NAMING_PARENTS
+                .iter()
+                .find(|(kind, _)| *kind == parent.kind())?;
+            parent.child_by_field_name(field).map(text)
+        })
+        .unwrap_or_else(|| ANONYMOUS_NAME.to_string());
+
+    let mut qualifiers: Vec<String> = Vec::new();
+    if let Some(receiver) = node.child_by_field_name("receiver") {
+        qualifiers.extend(receiver_type(receiver, source));
+    }
+    let mut ancestor = node.parent();
+    while let Some(current) = ancestor {
+        if let Some((_, field)) = spec
+            .containers
+            .iter()
+            .find(|(kind, _)| *kind == current.kind())
+        {
+            if let Some(container) = current.child_by_field_name(field) {
+                let container = text(container);
+                // `impl<T> Foo<T>` vira só `Foo`
+                let container = container.split('<').next().unwrap_or_default().trim();
+                qualifiers.insert(0, container.to_string());
+            }
+        }
+        ancestor = current.parent();
+    }
+
+    qualifiers.push(name);
+    qualifiers.join(spec.separator)
+}
+
+// Não conta `self`/`cls` nem comentários; no Go `a, b int` são dois parâmetros
+fn count_parameters(node: Node, source: &[u8]) -> usize {
+    if node.child_by_field_name("parameter").is_some() {
+        return 1;
+    }
+    let Some(parameters) = node.child_by_field_name("parameters") else {
+        return 0;
+    };
+
+    let mut cursor = parameters.walk();
+    let children: Vec<Node> = parameters.named_children(&mut cursor).collect();
+    children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| match child.kind() {
+            "comment" | "attribute_item" | "self_parameter" => 0,
+            "parameter_declaration" | "variadic_parameter_declaration" => {
+                let mut cursor = child.walk();
+                child
+                    .children_by_field_name("name", &mut cursor)
+                    .count()
+                    .max(1)
+            }
+            "identifier"
+                if index == 0 && matches!(child.utf8_text(source), Ok("self") | Ok("cls")) =>
+            {
+                0
+            }
+            _ => 1,
+        })
+        .sum()
+}
+
+// Totais por arquivo a partir das funções dele
+pub fn file_metrics(functions: &[FunctionMetrics]) -> Option<FileMetrics> {
+    let first = functions.first()?;
+    let total_cyclomatic: usize = functions.iter().map(|f| f.cyclomatic).sum();
+
+    Some(FileMetrics {
+        file: first.file.clone(),
+        language: first.language.clone(),
+        functions: functions.len(),
+        total_cyclomatic,
+        average_cyclomatic: round(total_cyclomatic as f64 / functions.len() as f64),
+        max_cyclomatic: functions.iter().map(|f| f.cyclomatic).max().unwrap_or(0),
+        max_cognitive: functions.iter().map(|f| f.cognitive).max().unwrap_or(0),
+        max_nesting: functions.iter().map(|f| f.max_nesting).max().unwrap_or(0),
+        longest_function: functions.iter().map(|f| f.lines).max().unwrap_or(0),
+    })
+}
+
+pub fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (nome, ciclomática, cognitiva, aninhamento máximo) de cada função
+    fn metrics(file: &str, source: &str) -> Vec<(String, usize, usize, usize)> {
+        analyze_file(Path::new(file), source)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.name, f.cyclomatic, f.cognitive, f.max_nesting))
+            .collect()
+    }
+
+    fn expected(
+        name: &str,
+        cyclomatic: usize,
+        cognitive: usize,
+        nesting: usize,
+    ) -> (String, usize, usize, usize) {
+        (name.to_string(), cyclomatic, cognitive, nesting)
+    }
+
+    #[test]
+    fn rust_else_if_match_and_logical_chains() {
+        let source = r#"
+impl<T> Parser<T> {
+    fn classify(&self, value: i32) -> &str {
+        if value > 10 {
+            "big"
+        } else if value > 5 {
+            "medium"
+        } else {
+            "small"
+        }
+    }
+}
+
+fn describe(value: Option<i32>) -> i32 {
+    match value {
+        Some(0) => 0,
+        Some(n) if n > 0 && n < 10 && n != 5 => 1,
+        Some(_) => 2,
+        _ => 3,
+    }
+}
+
+fn scan(items: &[i32]) -> usize {
+    let mut count = 0;
+    for item in items {
+        if *item > 0 || *item < -10 && *item != -20 {
+            count += 1;
+        }
+    }
+    count
+}
+"#;
+        assert_eq!(
+            metrics("src/lib.rs", source),
+            [
+                expected("Parser::classify", 3, 3, 1),
+                // O braço `_ =>` não conta; `a && b && c` soma 2 na ciclomática e 1 na cognitiva
+                expected("describe", 6, 2, 1),
+                // Operadores diferentes (`||` com `&&`) contam separadamente na cognitiva
+                expected("scan", 5, 5, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_else_block_comes_from_alternative() {
+        let source = r#"
+package main
+
+func (s *Server) Handle(a, b int) int {
+	if a > b {
+		return a
+	} else if b > 0 {
+		return b
+	} else {
+		return 0
+	}
+}
+
+func kind(v int) string {
+	switch {
+	case v < 0 && v > -10:
+		return "neg"
+	case v == 0:
+		return "zero"
+	default:
+		return "pos"
+	}
+}
+"#;
+        assert_eq!(
+            metrics("main.go", source),
+            [
+                expected("Server.Handle", 3, 3, 1),
+                expected("kind", 4, 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn javascript_methods_and_arrow_functions() {
+        let source = r#"
+class Cart {
+  total(items) {
+    let sum = 0;
+    for (const item of items) {
+      if (item.price > 0 && item.qty > 0 && item.ok) {
+        sum += item.price;
+      } else if (item.free) {
+        continue;
+      } else {
+        sum -= 1;
+      }
+    }
+    return sum;
+  }
+}
+
+const pick = (a, b) => (a ? b : a ?? b);
+"#;
+        let expected_js = [expected("Cart.total", 6, 6, 2), expected("pick", 3, 2, 1)];
+        assert_eq!(metrics("cart.js", source), expected_js);
+        assert_eq!(metrics("cart.ts", source), expected_js);
+    }
+
+    #[test]
+    fn python_elif_comprehension_and_match() {
+        let source = r#"
+class Job:
+    def run(self, tasks, retry=False):
+        for task in tasks:
+            if task.done:
+                continue
+            elif retry and task.failed and task.count < 3:
+                task.retry()
+            else:
+                task.start()
+        return [t for t in tasks if t.done]
+
+
+def label(code):
+    match code:
+        case 200:
+            return "ok"
+        case 404 | 410:
+            return "gone"
+        case _:
+            return "other"
+"#;
+        assert_eq!(
+            metrics("jobs.py", source),
+            [expected("Job.run", 7, 7, 2), expected("label", 3, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn closures_nest_inside_their_function() {
+        let source = r#"
+fn outer(values: Vec<i32>) -> Vec<i32> {
+    values
+        .into_iter()
+        .filter(|value| if *value > 0 { true } else { false })
+        .collect()
+}
+"#;
+        let functions = analyze_file(Path::new("lib.rs"), source).unwrap();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].parameters, 1);
+        // O if dentro da closure já está um nível abaixo da função
+        assert_eq!(
+            (
+                functions[0].cyclomatic,
+                functions[0].cognitive,
+                functions[0].max_nesting
+            ),
+            (2, 3, 2)
+        );
+    }
+}
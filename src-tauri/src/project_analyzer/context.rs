@@ -0,0 +1,391 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::secrets;
+use crate::types::{ContextFile, ContextReport, OmissionReason, OmittedFile};
+
+// Orçamento padrão do contexto enviado à IA, em tokens estimados (~4 caracteres por token)
+pub const DEFAULT_TOKEN_BUDGET: usize = 32_000;
+const CHARS_PER_TOKEN: usize = 4;
+
+// Configuração fica com no máximo 1/4 do orçamento; o que sobrar vai para o código
+const CONFIG_BUDGET_SHARE: usize = 4;
+const MAX_CONFIG_FILE_TOKENS: usize = 600;
+const MAX_SOURCE_FILE_TOKENS: usize = 1_000;
+// Abaixo disso não vale a pena incluir um pedaço do arquivo
+const MIN_USEFUL_TOKENS: usize = 64;
+const MAX_OMITTED_IN_SUMMARY: usize = 30;
+
+// Alterados até uma semana antes da mudança mais recente do workspace contam como recentes
+const RECENT_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const CONFIG_FILES: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "tsconfig.json",
+    "README.md",
+    "requirements.txt",
+    "go.mod",
+    "pyproject.toml",
+    "pom.xml",
+    "build.gradle",
+    "composer.json",
+    ".env.example",
+    "vite.config.ts",
+    "next.config.js",
+    "tailwind.config.js",
+];
+
+const ENTRY_POINTS: &[&str] = &[
+    "main.rs",
+    "lib.rs",
+    "main.ts",
+    "main.tsx",
+    "main.js",
+    "main.jsx",
+    "index.ts",
+    "index.tsx",
+    "index.js",
+    "app.tsx",
+    "app.jsx",
+    "app.ts",
+    "app.js",
+    "server.ts",
+    "server.js",
+    "main.py",
+    "__main__.py",
+    "app.py",
+    "manage.py",
+    "main.go",
+    "program.cs",
+    "main.java",
+];
+
+// Arquivo candidato ao contexto; `content` é None quando não pôde ser lido como texto
+pub struct ContextSource {
+    pub path: String,
+    pub content: Option<String>,
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+// Ordem de prioridade: pontos de entrada, arquivos alterados recentemente e, dentro de
+// cada grupo, os menores primeiro (cabem mais arquivos no mesmo orçamento)
+pub fn prioritize<'a>(
+    root: &Path,
+    files: impl Iterator<Item = &'a (PathBuf, u64)>,
+) -> Vec<&'a PathBuf> {
+    let files: Vec<(&PathBuf, u64, Option<SystemTime>)> = files
+        .map(|(path, size)| {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+            (path, *size, modified)
+        })
+        .collect();
+    let recent_since = files
+        .iter()
+        .filter_map(|(_, _, modified)| *modified)
+        .max()
+        .and_then(|newest| newest.checked_sub(RECENT_WINDOW));
+
+    let mut ranked: Vec<(u8, usize, u64, &PathBuf)> = files
+        .into_iter()
+        .map(|(path, size, modified)| {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let depth = relative.components().count();
+            let tier = if is_entry_point(relative) {
+                0
+            } else if modified
+                .zip(recent_since)
+                .is_some_and(|(m, since)| m >= since)
+            {
+                1
+            } else {
+                2
+            };
+            // Profundidade só desempata entre pontos de entrada (src/main.rs antes de exemplos)
+            let depth = if tier == 0 { depth } else { 0 };
+            (tier, depth, size, path)
+        })
+        .collect();
+    ranked.sort();
+    ranked.into_iter().map(|(_, _, _, path)| path).collect()
+}
+
+fn is_entry_point(relative: &Path) -> bool {
+    relative
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .is_some_and(|name| ENTRY_POINTS.contains(&name.as_str()))
+}
+
+// Arquivos de configuração conhecidos na raiz, com segredos já removidos
+pub fn config_sources(root: &Path) -> Vec<ContextSource> {
+    CONFIG_FILES
+        .iter()
+        .filter(|name| root.join(name).is_file())
+        .map(|name| ContextSource {
+            path: name.to_string(),
+            content: fs::read_to_string(root.join(name))
+                .ok()
+                .map(|content| secrets::redact(Path::new(name), &content)),
+        })
+        .collect()
+}
+
+// Monta as seções de configuração e código dentro de `budget` tokens e anota o que ficou
+// de fora; `omitted` traz os arquivos já descartados antes da leitura
+pub fn build(
+    config: &[ContextSource],
+    sources: &[ContextSource],
+    mut omitted: Vec<OmittedFile>,
+    budget: usize,
+) -> (String, ContextReport) {
+    let mut context = String::new();
+    let mut included: Vec<ContextFile> = Vec::new();
+    let mut used = 0;
+
+    context.push_str("## Arquivos de configuração:\n\n");
+    let config_budget = budget / CONFIG_BUDGET_SHARE;
+    for source in config {
+        let Some(content) = &source.content else {
+            omitted.push(omit(&source.path, OmissionReason::Unreadable));
+            continue;
+        };
+        let available = config_budget
+            .saturating_sub(used)
+            .min(MAX_CONFIG_FILE_TOKENS);
+        match render_file(&source.path, content, available) {
+            Some((block, file)) => {
+                used += file.tokens;
+                context.push_str(&block);
+                included.push(file);
+            }
+            None => omitted.push(omit(&source.path, OmissionReason::Budget)),
+        }
+    }
+    if config.is_empty() {
+        context.push_str("Nenhum arquivo de configuração padrão encontrado.\n\n");
+    }
+
+    let config_paths: HashSet<&str> = config.iter().map(|s| s.path.as_str()).collect();
+    let sources: Vec<&ContextSource> = sources
+        .iter()
+        .filter(|s| !config_paths.contains(s.path.as_str()))
+        .collect();
+    if !sources.is_empty() {
+        context.push_str("\n## Código-fonte do projeto (subpastas incluídas):\n\n");
+    }
+    for source in sources {
+        let Some(content) = &source.content else {
+            omitted.push(omit(&source.path, OmissionReason::Unreadable));
+            continue;
+        };
+        let available = budget.saturating_sub(used).min(MAX_SOURCE_FILE_TOKENS);
+        match render_file(&source.path, content, available) {
+            Some((block, file)) => {
+                used += file.tokens;
+                context.push_str(&block);
+                included.push(file);
+            }
+            None => omitted.push(omit(&source.path, OmissionReason::Budget)),
+        }
+    }
+
+    let truncated: Vec<&ContextFile> = included.iter().filter(|f| f.truncated).collect();
+    if !truncated.is_empty() || !omitted.is_empty() {
+        context.push_str("## Conteúdo fora do contexto\n\n");
+        for file in &truncated {
+            context.push_str(&format!(
+                "- `{}`: truncado ({} de {} linhas)\n",
+                file.path, file.lines_included, file.total_lines
+            ));
+        }
+        for file in omitted.iter().take(MAX_OMITTED_IN_SUMMARY) {
+            context.push_str(&format!(
+                "- `{}`: omitido ({})\n",
+                file.path,
+                reason_label(file.reason)
+            ));
+        }
+        if omitted.len() > MAX_OMITTED_IN_SUMMARY {
+            context.push_str(&format!(
+                "- ... e mais {} arquivo(s) omitido(s)\n",
+                omitted.len() - MAX_OMITTED_IN_SUMMARY
+            ));
+        }
+        context.push('\n');
+    }
+
+    let report = ContextReport {
+        token_budget: budget,
+        tokens_used: used,
+        included,
+        omitted,
+    };
+    (context, report)
+}
+
+// Bloco markdown do arquivo dentro de `max_tokens`, ou None se nem um trecho útil cabe
+fn render_file(path: &str, content: &str, max_tokens: usize) -> Option<(String, ContextFile)> {
+    let total_lines = content.lines().count();
+    let full = format!("### {}\n```\n{}\n```\n\n", path, content.trim_end());
+    let full_tokens = estimate_tokens(&full);
+    if full_tokens <= max_tokens {
+        let file = ContextFile {
+            path: path.to_string(),
+            tokens: full_tokens,
+            lines_included: total_lines,
+            total_lines,
+            truncated: false,
+        };
+        return Some((full, file));
+    }
+
+    // Reserva espaço para o cabeçalho, as cercas e o aviso de truncamento
+    let overhead = estimate_tokens(&format!(
+        "### {}\n```\n\n```\n[Arquivo truncado: {} de {} linhas]\n\n",
+        path, total_lines, total_lines
+    ));
+    let available = max_tokens.saturating_sub(overhead);
+    if available < MIN_USEFUL_TOKENS {
+        return None;
+    }
+    let (excerpt, lines_included) = truncate(content, available * CHARS_PER_TOKEN);
+    let block = format!(
+        "### {}\n```\n{}\n```\n[Arquivo truncado: {} de {} linhas]\n\n",
+        path,
+        excerpt.trim_end(),
+        lines_included,
+        total_lines
+    );
+    let file = ContextFile {
+        path: path.to_string(),
+        tokens: estimate_tokens(&block),
+        lines_included,
+        total_lines,
+        truncated: true,
+    };
+    Some((block, file))
+}
+
+// Corta em fim de linha sem passar de `max_chars` caracteres; se nem a primeira linha
+// cabe (arquivo minificado), corta nela mesma, sempre em fronteira de caractere
+fn truncate(content: &str, max_chars: usize) -> (&str, usize) {
+    let mut chars = 0;
+    let mut end = 0;
+    let mut lines = 0;
+    for line in content.split_inclusive('\n') {
+        let line_chars = line.chars().count();
+        if chars + line_chars > max_chars {
+            break;
+        }
+        chars += line_chars;
+        end += line.len();
+        lines += 1;
+    }
+
+    if lines == 0 {
+        let end = content
+            .char_indices()
+            .nth(max_chars)
+            .map(|(index, _)| index)
+            .unwrap_or(content.len());
+        return (&content[..end], 1);
+    }
+    (&content[..end], lines)
+}
+
+fn omit(path: &str, reason: OmissionReason) -> OmittedFile {
+    OmittedFile {
+        path: path.to_string(),
+        reason,
+    }
+}
+
+fn reason_label(reason: OmissionReason) -> &'static str {
+    match reason {
+        OmissionReason::Budget => "orçamento de tokens esgotado",
+        OmissionReason::Limit => "limite de arquivos lidos",
+        OmissionReason::Size => "arquivo grande demais",
+        OmissionReason::Unreadable => "não é texto UTF-8",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORTUGUESE: &str =
+        "// Configuração básica\nfn início() { println!(\"ação 🚀\"); }\n// 🎉🎉 fim\n";
+
+    #[test]
+    fn truncate_cuts_multibyte_lines_on_line_boundaries() {
+        let total_chars = PORTUGUESE.chars().count();
+        for max_chars in 0..=total_chars + 2 {
+            let (excerpt, lines) = truncate(PORTUGUESE, max_chars);
+            assert!(PORTUGUESE.starts_with(excerpt));
+            assert!(PORTUGUESE.is_char_boundary(excerpt.len()));
+            assert!(lines >= 1);
+
+            let first_line = PORTUGUESE.split_inclusive('\n').next().unwrap();
+            if max_chars >= first_line.chars().count() {
+                // Cabe ao menos uma linha: o corte cai em fim de linha e dentro do limite
+                assert!(
+                    excerpt.ends_with('\n'),
+                    "corte no meio da linha: {:?}",
+                    excerpt
+                );
+                assert!(excerpt.chars().count() <= max_chars);
+                assert_eq!(excerpt.lines().count(), lines);
+            } else {
+                assert_eq!(excerpt.chars().count(), max_chars);
+                assert_eq!(lines, 1);
+            }
+        }
+        assert_eq!(truncate(PORTUGUESE, total_chars), (PORTUGUESE, 3));
+    }
+
+    #[test]
+    fn truncate_single_long_line_stops_on_char_boundary() {
+        // Linha minificada só com caracteres de 2 e 4 bytes
+        let line = "çã🚀".repeat(50);
+        for max_chars in [0, 1, 2, 3, 4, 149, 150, 151] {
+            let (excerpt, lines) = truncate(&line, max_chars);
+            assert_eq!(lines, 1);
+            assert_eq!(excerpt.chars().count(), max_chars.min(150));
+            assert!(line.is_char_boundary(excerpt.len()));
+        }
+    }
+
+    #[test]
+    fn render_file_truncates_multibyte_content_within_budget() {
+        let content = "// ação ✅ 🚀 não\n".repeat(400);
+        for max_tokens in [0, 80, 81, 100, 250, 1_000] {
+            let Some((block, file)) = render_file("src/ação.rs", &content, max_tokens) else {
+                assert!(max_tokens < 100, "sem trecho com {} tokens", max_tokens);
+                continue;
+            };
+            assert!(file.truncated);
+            assert!(
+                file.tokens <= max_tokens,
+                "{} > {}",
+                file.tokens,
+                max_tokens
+            );
+            assert!(file.lines_included < file.total_lines);
+            assert!(block.contains(&format!(
+                "[Arquivo truncado: {} de 400 linhas]",
+                file.lines_included
+            )));
+        }
+
+        let (block, file) = render_file("src/ação.rs", &content, 100_000).unwrap();
+        assert!(!file.truncated);
+        assert_eq!(file.lines_included, 400);
+        assert!(block.contains(content.trim_end()));
+    }
+}
@@ -0,0 +1,1391 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml::Value as TomlValue;
+
+use crate::types::{Dependency, DependencyKind};
+
+pub const CARGO: &str = "cargo";
+pub const NPM: &str = "npm";
+pub const GO: &str = "go";
+pub const PYPI: &str = "pypi";
+
+const CARGO_SECTIONS: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Runtime),
+    ("dev-dependencies", DependencyKind::Dev),
+    ("build-dependencies", DependencyKind::Build),
+];
+
+const PROJECT_MANIFESTS: &[&str] = &[
+    "cargo.toml",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "go.mod",
+    "go.work",
+    "pyproject.toml",
+];
+
+const NPM_SECTIONS: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Runtime),
+    ("devDependencies", DependencyKind::Dev),
+    ("peerDependencies", DependencyKind::Peer),
+    ("optionalDependencies", DependencyKind::Optional),
+];
+
+struct Manifest {
+    // Caminho relativo à raiz do projeto
+    path: PathBuf,
+    ecosystem: &'static str,
+    package_name: Option<String>,
+    dependencies: Vec<Dependency>,
+    // Dependências Cargo com `workspace = true`, resolvidas pelo manifesto do workspace
+    inherited: Vec<usize>,
+    workspace: Option<Workspace>,
+}
+
+#[derive(Default)]
+struct Workspace {
+    members: Vec<String>,
+    exclude: Vec<String>,
+    // [workspace.dependencies] do Cargo
+    dependencies: HashMap<String, String>,
+}
+
+impl Manifest {
+    fn new(path: &Path, ecosystem: &'static str) -> Self {
+        Manifest {
+            path: path.to_path_buf(),
+            ecosystem,
+            package_name: None,
+            dependencies: Vec::new(),
+            inherited: Vec::new(),
+            workspace: None,
+        }
+    }
+
+    fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+
+    fn push(&mut self, name: &str, requirement: Option<String>, kind: DependencyKind) {
+        self.dependencies.push(Dependency {
+            name: name.to_string(),
+            requirement,
+            resolved_version: None,
+            kind,
+            ecosystem: self.ecosystem.to_string(),
+            manifest: slash_path(&self.path),
+            workspace_member: None,
+        });
+    }
+}
+
+// Lê os manifestos encontrados na varredura e devolve as dependências declaradas,
+// com membro de workspace e versão resolvida pelo lockfile mais próximo
+pub fn collect(root: &Path, files: &[(PathBuf, u64)]) -> Vec<Dependency> {
+    collect_with_manifests(root, files).0
+}
+
+// Como `collect`, devolvendo também os manifestos de projeto já lidos, para a árvore de
+// pacotes não abrir e interpretar os mesmos arquivos de novo
+pub fn collect_with_manifests(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+) -> (Vec<Dependency>, Vec<ProjectManifest>) {
+    let mut manifests: Vec<Manifest> = files
+        .iter()
+        .filter_map(|(path, _)| {
+            let relative = path.strip_prefix(root).ok()?;
+            match parse_manifest(path, relative) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    log::warn!("Erro ao ler manifesto {:?}: {:#}", path, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    manifests.sort_by(|a, b| a.path.cmp(&b.path));
+
+    resolve_workspaces(&mut manifests);
+
+    let mut lockfiles: HashMap<PathBuf, Option<LockedVersions>> = HashMap::new();
+    let mut dependencies = Vec::new();
+    let mut projects = Vec::new();
+
+    for mut manifest in manifests {
+        if let Some(lockfile) = find_lockfile(root, manifest.dir(), manifest.ecosystem) {
+            let locked = lockfiles.entry(lockfile.clone()).or_insert_with(|| {
+                parse_lockfile(&lockfile)
+                    .map_err(|e| log::warn!("Erro ao ler lockfile {:?}: {:#}", lockfile, e))
+                    .ok()
+            });
+
+            if let Some(locked) = locked {
+                for dependency in &mut manifest.dependencies {
+                    if dependency.resolved_version.is_none() {
+                        dependency.resolved_version = locked.resolve(
+                            &dependency.name,
+                            dependency.requirement.as_deref(),
+                            manifest.ecosystem,
+                        );
+                    }
+                }
+            }
+        }
+
+        dependencies.append(&mut manifest.dependencies);
+        if let Some(project) = project_manifest(manifest) {
+            projects.push(project);
+        }
+    }
+
+    (dependencies, projects)
+}
+
+fn parse_manifest(path: &Path, relative: &Path) -> Result<Option<Manifest>> {
+    let file_name = match relative.file_name() {
+        Some(name) => name.to_string_lossy().to_lowercase(),
+        None => return Ok(None),
+    };
+    let in_requirements_dir = relative
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|dir| dir == "requirements");
+
+    let manifest = match file_name.as_str() {
+        "cargo.toml" => parse_cargo_toml(relative, &read_toml(path)?),
+        "package.json" => parse_package_json(relative, &read_json(path)?),
+        "go.mod" => parse_go_mod(relative, &fs::read_to_string(path)?),
+        "go.work" => parse_go_work(relative, &fs::read_to_string(path)?),
+        "pnpm-workspace.yaml" => parse_pnpm_workspace(relative, &fs::read_to_string(path)?),
+        "pyproject.toml" => parse_pyproject(relative, &read_toml(path)?),
+        name if name.ends_with(".txt")
+            && (name.starts_with("requirements") || in_requirements_dir) =>
+        {
+            parse_requirements_txt(relative, &file_name, &fs::read_to_string(path)?)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(manifest))
+}
+
+fn read_toml(path: &Path) -> Result<TomlValue> {
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content).context("TOML inválido")
+}
+
+fn read_json(path: &Path) -> Result<JsonValue> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).context("JSON inválido")
+}
+
+fn parse_cargo_toml(relative: &Path, data: &TomlValue) -> Manifest {
+    let mut manifest = Manifest::new(relative, CARGO);
+    manifest.package_name = data
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|n| n.to_string());
+
+    let mut tables: Vec<&TomlValue> = vec![data];
+    if let Some(targets) = data.get("target").and_then(|t| t.as_table()) {
+        tables.extend(targets.values());
+    }
+
+    for table in tables {
+        for (section, kind) in CARGO_SECTIONS {
+            let Some(entries) = table.get(*section).and_then(|s| s.as_table()) else {
+                continue;
+            };
+            for (key, value) in entries {
+                // `foo = { package = "bar" }` renomeia a crate; o lockfile usa o nome real
+                let name = value.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+                let optional = value
+                    .get("optional")
+                    .and_then(|o| o.as_bool())
+                    .unwrap_or(false);
+                let kind = if optional {
+                    DependencyKind::Optional
+                } else {
+                    *kind
+                };
+
+                if value.get("workspace").and_then(|w| w.as_bool()) == Some(true) {
+                    manifest.inherited.push(manifest.dependencies.len());
+                }
+                manifest.push(name, cargo_requirement(value), kind);
+            }
+        }
+    }
+
+    if let Some(workspace) = data.get("workspace") {
+        manifest.workspace = Some(Workspace {
+            members: string_array(workspace.get("members")),
+            exclude: string_array(workspace.get("exclude")),
+            dependencies: workspace
+                .get("dependencies")
+                .and_then(|d| d.as_table())
+                .map(|deps| {
+                    deps.iter()
+                        .filter_map(|(name, value)| Some((name.clone(), cargo_requirement(value)?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        });
+    }
+
+    manifest
+}
+
+fn cargo_requirement(value: &TomlValue) -> Option<String> {
+    if let Some(version) = value.as_str() {
+        return Some(version.to_string());
+    }
+    if let Some(version) = value.get("version").and_then(|v| v.as_str()) {
+        return Some(version.to_string());
+    }
+    if let Some(path) = value.get("path").and_then(|p| p.as_str()) {
+        return Some(format!("path:{}", path));
+    }
+    value
+        .get("git")
+        .and_then(|g| g.as_str())
+        .map(|git| format!("git:{}", git))
+}
+
+fn string_array(value: Option<&TomlValue>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_package_json(relative: &Path, data: &JsonValue) -> Manifest {
+    let mut manifest = Manifest::new(relative, NPM);
+    manifest.package_name = data["name"].as_str().map(|n| n.to_string());
+
+    for (section, kind) in NPM_SECTIONS {
+        if let Some(entries) = data[*section].as_object() {
+            for (name, requirement) in entries {
+                manifest.push(name, requirement.as_str().map(|r| r.to_string()), *kind);
+            }
+        }
+    }
+
+    // "workspaces": ["packages/*"] ou { "packages": [...] } (yarn)
+    let workspaces = match &data["workspaces"] {
+        JsonValue::Array(items) => Some(items),
+        JsonValue::Object(object) => object.get("packages").and_then(|p| p.as_array()),
+        _ => None,
+    };
+    if let Some(patterns) = workspaces {
+        let patterns = patterns.iter().filter_map(|p| p.as_str());
+        manifest.workspace = Some(Workspace {
+            members: patterns
+                .clone()
+                .filter(|p| !p.starts_with('!'))
+                .map(|p| p.to_string())
+                .collect(),
+            exclude: patterns
+                .filter_map(|p| p.strip_prefix('!'))
+                .map(|p| p.to_string())
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    manifest
+}
+
+fn parse_go_mod(relative: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(relative, GO);
+    let mut in_require_block = false;
+
+    for line in content.lines() {
+        let (code, comment) = match line.split_once("//") {
+            Some((code, comment)) => (code.trim(), comment.trim()),
+            None => (line.trim(), ""),
+        };
+
+        let requirement = if in_require_block {
+            if code == ")" {
+                in_require_block = false;
+                continue;
+            }
+            code
+        } else if let Some(module) = code.strip_prefix("module ") {
+            manifest.package_name = Some(module.trim().trim_matches('"').to_string());
+            continue;
+        } else if code == "require (" || code == "require(" {
+            in_require_block = true;
+            continue;
+        } else if let Some(requirement) = code.strip_prefix("require ") {
+            requirement.trim()
+        } else {
+            continue;
+        };
+
+        let mut parts = requirement.split_whitespace();
+        if let (Some(name), Some(version)) = (parts.next(), parts.next()) {
+            let kind = if comment.starts_with("indirect") {
+                DependencyKind::Indirect
+            } else {
+                DependencyKind::Runtime
+            };
+            manifest.push(name.trim_matches('"'), Some(version.to_string()), kind);
+        }
+    }
+
+    manifest
+}
+
+fn parse_go_work(relative: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(relative, GO);
+    let mut members = Vec::new();
+    let mut in_use_block = false;
+
+    for line in content.lines() {
+        let code = line.split("//").next().unwrap_or_default().trim();
+        if in_use_block {
+            if code == ")" {
+                in_use_block = false;
+            } else if !code.is_empty() {
+                members.push(code.to_string());
+            }
+        } else if code == "use (" || code == "use(" {
+            in_use_block = true;
+        } else if let Some(dir) = code.strip_prefix("use ") {
+            members.push(dir.trim().to_string());
+        }
+    }
+
+    manifest.workspace = Some(Workspace {
+        members,
+        ..Default::default()
+    });
+    manifest
+}
+
+// Só a lista `packages:` interessa; o resto do YAML é ignorado
+fn parse_pnpm_workspace(relative: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(relative, NPM);
+    let mut workspace = Workspace::default();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let code = line.split(" #").next().unwrap_or_default().trim_end();
+        if code.trim().is_empty() || code.trim_start().starts_with('#') {
+            continue;
+        }
+        if !code.starts_with([' ', '\t', '-']) {
+            in_packages = code.trim() == "packages:";
+            continue;
+        }
+        let Some(pattern) = code.trim().strip_prefix('-').filter(|_| in_packages) else {
+            continue;
+        };
+        let pattern = pattern.trim().trim_matches(['"', '\'']);
+        match pattern.strip_prefix('!') {
+            Some(excluded) => workspace.exclude.push(excluded.to_string()),
+            None => workspace.members.push(pattern.to_string()),
+        }
+    }
+
+    manifest.workspace = Some(workspace);
+    manifest
+}
+
+fn parse_requirements_txt(relative: &Path, file_name: &str, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(relative, PYPI);
+    let kind = if file_name.contains("dev") || file_name.contains("test") {
+        DependencyKind::Dev
+    } else {
+        DependencyKind::Runtime
+    };
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        // Opções (-r, -e, --index-url) e comentários não são dependências
+        if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+            continue;
+        }
+        if let Some((name, requirement)) = parse_pep508(line) {
+            manifest.push(&name, requirement, kind);
+        }
+    }
+
+    // Versões fixadas com == já são a versão resolvida
+    for dependency in &mut manifest.dependencies {
+        dependency.resolved_version = dependency
+            .requirement
+            .as_deref()
+            .and_then(|r| r.strip_prefix("=="))
+            .filter(|v| !v.contains(['*', ',']))
+            .map(|v| v.trim().to_string());
+    }
+
+    manifest
+}
+
+fn parse_pyproject(relative: &Path, data: &TomlValue) -> Manifest {
+    let mut manifest = Manifest::new(relative, PYPI);
+    let project = data.get("project");
+    let poetry = data.get("tool").and_then(|t| t.get("poetry"));
+
+    manifest.package_name = project
+        .or(poetry)
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|n| n.to_string());
+
+    // PEP 621
+    for spec in string_array(project.and_then(|p| p.get("dependencies"))) {
+        if let Some((name, requirement)) = parse_pep508(&spec) {
+            manifest.push(&name, requirement, DependencyKind::Runtime);
+        }
+    }
+    let groups = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|o| o.as_table());
+    for (group, specs) in groups.into_iter().flatten() {
+        let kind = if is_dev_group(group) {
+            DependencyKind::Dev
+        } else {
+            DependencyKind::Optional
+        };
+        for spec in string_array(Some(specs)) {
+            if let Some((name, requirement)) = parse_pep508(&spec) {
+                manifest.push(&name, requirement, kind);
+            }
+        }
+    }
+
+    // PEP 735
+    let groups = data.get("dependency-groups").and_then(|g| g.as_table());
+    for specs in groups.into_iter().flat_map(|g| g.values()) {
+        for spec in string_array(Some(specs)) {
+            if let Some((name, requirement)) = parse_pep508(&spec) {
+                manifest.push(&name, requirement, DependencyKind::Dev);
+            }
+        }
+    }
+
+    // Poetry
+    if let Some(poetry) = poetry {
+        push_poetry_table(
+            &mut manifest,
+            poetry.get("dependencies"),
+            DependencyKind::Runtime,
+        );
+        push_poetry_table(
+            &mut manifest,
+            poetry.get("dev-dependencies"),
+            DependencyKind::Dev,
+        );
+        let groups = poetry.get("group").and_then(|g| g.as_table());
+        for group in groups.into_iter().flat_map(|g| g.values()) {
+            push_poetry_table(
+                &mut manifest,
+                group.get("dependencies"),
+                DependencyKind::Dev,
+            );
+        }
+    }
+
+    let uv_workspace = data
+        .get("tool")
+        .and_then(|t| t.get("uv"))
+        .and_then(|uv| uv.get("workspace"));
+    if let Some(workspace) = uv_workspace {
+        manifest.workspace = Some(Workspace {
+            members: string_array(workspace.get("members")),
+            exclude: string_array(workspace.get("exclude")),
+            ..Default::default()
+        });
+    }
+
+    manifest
+}
+
+fn push_poetry_table(manifest: &mut Manifest, table: Option<&TomlValue>, kind: DependencyKind) {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return;
+    };
+    for (name, value) in table {
+        if name == "python" {
+            continue;
+        }
+        let requirement = value
+            .as_str()
+            .or_else(|| value.get("version").and_then(|v| v.as_str()))
+            .map(|r| r.to_string());
+        let optional = value
+            .get("optional")
+            .and_then(|o| o.as_bool())
+            .unwrap_or(false);
+        let kind = if optional && kind == DependencyKind::Runtime {
+            DependencyKind::Optional
+        } else {
+            kind
+        };
+        manifest.push(name, requirement, kind);
+    }
+}
+
+fn is_dev_group(group: &str) -> bool {
+    let group = group.to_lowercase();
+    ["dev", "test", "lint", "doc", "typing"]
+        .iter()
+        .any(|g| group.contains(g))
+}
+
+// `nome[extras] (especificador) ; marcadores` ou `nome @ url`
+fn parse_pep508(spec: &str) -> Option<(String, Option<String>)> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"^\s*([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*(.*)$").unwrap()
+    });
+
+    let spec = spec.split(';').next().unwrap_or_default();
+    let captures = pattern.captures(spec)?;
+    let name = captures[1].to_string();
+    let requirement = captures[2]
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    let requirement = requirement
+        .strip_prefix('@')
+        .map(|url| url.trim())
+        .unwrap_or(requirement);
+
+    Some((
+        name,
+        (!requirement.is_empty()).then(|| requirement.replace(' ', "")),
+    ))
+}
+
+// Marca os manifestos que pertencem a um workspace e herda as versões de
+// `[workspace.dependencies]` nas dependências Cargo com `workspace = true`
+fn resolve_workspaces(manifests: &mut [Manifest]) {
+    let mut membership: Vec<Option<usize>> = vec![None; manifests.len()];
+
+    for (index, manifest) in manifests.iter().enumerate() {
+        membership[index] = manifests.iter().position(|candidate| {
+            let Some(workspace) = &candidate.workspace else {
+                return false;
+            };
+            if candidate.ecosystem != manifest.ecosystem {
+                return false;
+            }
+            let Ok(relative) = manifest.dir().strip_prefix(candidate.dir()) else {
+                return false;
+            };
+            if relative.as_os_str().is_empty() {
+                return manifest.package_name.is_some();
+            }
+
+            let relative = slash_path(relative);
+            workspace
+                .members
+                .iter()
+                .any(|pattern| matches_pattern(pattern, &relative))
+                && !workspace
+                    .exclude
+                    .iter()
+                    .any(|pattern| matches_pattern(pattern, &relative))
+        });
+    }
+
+    for (index, workspace_index) in membership.into_iter().enumerate() {
+        let Some(workspace_index) = workspace_index else {
+            continue;
+        };
+
+        let inherited: Vec<(usize, Option<String>)> = manifests[index]
+            .inherited
+            .iter()
+            .map(|&dependency| {
+                let name = &manifests[index].dependencies[dependency].name;
+                let requirement = manifests[workspace_index]
+                    .workspace
+                    .as_ref()
+                    .and_then(|w| w.dependencies.get(name).cloned());
+                (dependency, requirement)
+            })
+            .collect();
+
+        let manifest = &mut manifests[index];
+        let member = manifest.package_name.clone().or_else(|| {
+            manifest
+                .dir()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        });
+        for dependency in &mut manifest.dependencies {
+            dependency.workspace_member = member.clone();
+        }
+        for (dependency, requirement) in inherited {
+            if requirement.is_some() {
+                manifest.dependencies[dependency].requirement = requirement;
+            }
+        }
+    }
+}
+
+// Manifesto que define um (sub)projeto ou um workspace; requirements*.txt não contam
+pub struct ProjectManifest {
+    // Caminho relativo à raiz do projeto
+    pub path: PathBuf,
+    pub ecosystem: &'static str,
+    pub name: Option<String>,
+    // Padrões de membros e exclusões quando o manifesto declara um workspace
+    pub members: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+fn project_manifest(manifest: Manifest) -> Option<ProjectManifest> {
+    let file_name = manifest.path.file_name()?.to_string_lossy().to_lowercase();
+    if !PROJECT_MANIFESTS.contains(&file_name.as_str()) {
+        return None;
+    }
+    let (members, exclude) = manifest
+        .workspace
+        .map(|workspace| (workspace.members, workspace.exclude))
+        .unwrap_or_default();
+    Some(ProjectManifest {
+        path: manifest.path,
+        ecosystem: manifest.ecosystem,
+        name: manifest.package_name,
+        members,
+        exclude,
+    })
+}
+
+// Glob simples dos workspaces: `*` dentro de um segmento e `**` para qualquer profundidade
+pub fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let pattern = pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    let pattern: Vec<&str> = pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    matches_segments(&pattern, &path)
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&pattern[1..], path)
+                || (!path.is_empty() && matches_segments(pattern, &path[1..]))
+        }
+        (Some(segment), Some(name)) => {
+            wildcard_match(segment.as_bytes(), name.as_bytes())
+                && matches_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') => {
+            wildcard_match(&pattern[1..], text)
+                || (!text.is_empty() && wildcard_match(pattern, &text[1..]))
+        }
+        Some(b'?') => !text.is_empty() && wildcard_match(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && wildcard_match(&pattern[1..], &text[1..]),
+    }
+}
+
+pub fn lockfile_names(ecosystem: &str) -> &'static [&'static str] {
+    match ecosystem {
+        CARGO => &["Cargo.lock"],
+        NPM => &[
+            "package-lock.json",
+            "npm-shrinkwrap.json",
+            "yarn.lock",
+            "pnpm-lock.yaml",
+        ],
+        GO => &["go.sum"],
+        PYPI => &["poetry.lock", "uv.lock", "pdm.lock", "Pipfile.lock"],
+        _ => &[],
+    }
+}
+
+pub fn lockfile_ecosystem(file_name: &str) -> Option<&'static str> {
+    [CARGO, NPM, GO, PYPI]
+        .into_iter()
+        .find(|ecosystem| lockfile_names(ecosystem).contains(&file_name))
+}
+
+// Procura o lockfile no diretório do manifesto e acima dele (raiz do workspace)
+fn find_lockfile(root: &Path, manifest_dir: &Path, ecosystem: &str) -> Option<PathBuf> {
+    let names = lockfile_names(ecosystem);
+    manifest_dir.ancestors().find_map(|dir| {
+        names
+            .iter()
+            .map(|name| root.join(dir).join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+#[derive(Default)]
+pub struct LockedVersions {
+    versions: HashMap<String, Vec<String>>,
+}
+
+impl LockedVersions {
+    pub fn packages(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.versions.iter().flat_map(|(name, versions)| {
+            versions
+                .iter()
+                .map(move |version| (name.as_str(), version.as_str()))
+        })
+    }
+
+    fn insert(&mut self, name: &str, version: &str) {
+        let versions = self.versions.entry(name.to_string()).or_default();
+        if !versions.iter().any(|v| v == version) {
+            versions.push(version.to_string());
+        }
+    }
+
+    // Com várias versões travadas, escolhe a maior compatível com o requisito
+    fn resolve(&self, name: &str, requirement: Option<&str>, ecosystem: &str) -> Option<String> {
+        let key = if ecosystem == PYPI {
+            normalize_python_name(name)
+        } else {
+            name.to_string()
+        };
+        let versions = self.versions.get(&key)?;
+        if let [only] = versions.as_slice() {
+            return Some(only.clone());
+        }
+
+        let requirement = requirement?.trim();
+        if let Some(exact) = versions
+            .iter()
+            .find(|v| *v == requirement || requirement.trim_start_matches('=') == v.as_str())
+        {
+            return Some(exact.clone());
+        }
+
+        let wanted = version_numbers(requirement);
+        let (&major, rest) = wanted.split_first()?;
+        versions
+            .iter()
+            .filter(|v| {
+                let numbers = version_numbers(v);
+                numbers.first() == Some(&major)
+                    && (major != 0 || rest.is_empty() || numbers.get(1) == rest.first())
+            })
+            .max_by_key(|v| version_numbers(v))
+            .cloned()
+    }
+}
+
+pub fn version_numbers(version: &str) -> Vec<u64> {
+    let start = version
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(version.len());
+    version[start..]
+        .split(['-', '+', ' ', ','])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn normalize_python_name(name: &str) -> String {
+    static SEPARATORS: OnceLock<Regex> = OnceLock::new();
+    SEPARATORS
+        .get_or_init(|| Regex::new(r"[-_.]+").unwrap())
+        .replace_all(&name.to_lowercase(), "-")
+        .to_string()
+}
+
+pub fn parse_lockfile(path: &Path) -> Result<LockedVersions> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    parse_lockfile_content(&file_name, &fs::read_to_string(path)?)
+}
+
+fn parse_lockfile_content(file_name: &str, content: &str) -> Result<LockedVersions> {
+    let mut locked = LockedVersions::default();
+
+    match file_name {
+        "Cargo.lock" | "poetry.lock" | "uv.lock" | "pdm.lock" => {
+            let data: TomlValue = toml::from_str(content).context("TOML inválido")?;
+            let python = file_name != "Cargo.lock";
+            let packages = data.get("package").and_then(|p| p.as_array());
+            for package in packages.into_iter().flatten() {
+                if let (Some(name), Some(version)) = (
+                    package.get("name").and_then(|n| n.as_str()),
+                    package.get("version").and_then(|v| v.as_str()),
+                ) {
+                    let name = if python {
+                        normalize_python_name(name)
+                    } else {
+                        name.to_string()
+                    };
+                    locked.insert(&name, version);
+                }
+            }
+        }
+        "package-lock.json" | "npm-shrinkwrap.json" => {
+            let data: JsonValue = serde_json::from_str(content).context("JSON inválido")?;
+            // lockfileVersion 2/3: "packages" indexado pelo caminho em node_modules
+            if let Some(packages) = data["packages"].as_object() {
+                for (key, package) in packages {
+                    let Some((_, name)) = key.rsplit_once("node_modules/") else {
+                        continue;
+                    };
+                    let name = package["name"].as_str().unwrap_or(name);
+                    if let Some(version) = package["version"].as_str() {
+                        locked.insert(name, version);
+                    }
+                }
+            } else {
+                collect_npm_v1(&data["dependencies"], &mut locked);
+            }
+        }
+        "yarn.lock" => parse_yarn_lock(content, &mut locked),
+        "pnpm-lock.yaml" => parse_pnpm_lock(content, &mut locked),
+        "go.sum" => {
+            // Linhas `<versão>/go.mod` só guardam o hash do go.mod de módulos do grafo
+            // (inclusive versões descartadas na seleção); o código usado tem a linha sem sufixo
+            for line in content.lines() {
+                let mut parts = line.split_whitespace();
+                if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+                    if !version.ends_with("/go.mod") {
+                        locked.insert(module, version);
+                    }
+                }
+            }
+        }
+        "Pipfile.lock" => {
+            let data: JsonValue = serde_json::from_str(content).context("JSON inválido")?;
+            for section in ["default", "develop"] {
+                for (name, package) in data[section].as_object().into_iter().flatten() {
+                    if let Some(version) = package["version"].as_str() {
+                        locked.insert(
+                            &normalize_python_name(name),
+                            version.trim_start_matches("=="),
+                        );
+                    }
+                }
+            }
+        }
+        _ => anyhow::bail!("Lockfile não suportado: {}", file_name),
+    }
+
+    Ok(locked)
+}
+
+// lockfileVersion 1: árvore aninhada em "dependencies"
+fn collect_npm_v1(dependencies: &JsonValue, locked: &mut LockedVersions) {
+    for (name, package) in dependencies.as_object().into_iter().flatten() {
+        if let Some(version) = package["version"].as_str() {
+            locked.insert(name, version);
+        }
+        collect_npm_v1(&package["dependencies"], locked);
+    }
+}
+
+// Cabeçalhos `"nome@^1.0.0", nome@^1.2.0:` seguidos de `version "1.2.3"` (ou `version: 1.2.3` no Yarn Berry)
+fn parse_yarn_lock(content: &str, locked: &mut LockedVersions) {
+    let mut names: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            names = line
+                .trim_end_matches(':')
+                .split(',')
+                .filter_map(|spec| package_name_from_spec(spec.trim().trim_matches('"')))
+                .collect();
+            continue;
+        }
+
+        let line = line.trim();
+        if let Some(version) = line.strip_prefix("version") {
+            let version = version.trim_start_matches(':').trim().trim_matches('"');
+            for name in &names {
+                locked.insert(name, version);
+            }
+        }
+    }
+}
+
+// Chaves da seção `packages:`: `/nome@1.0.0`, `nome@1.0.0(peer@2)` ou `/nome/1.0.0` (v5)
+fn parse_pnpm_lock(content: &str, locked: &mut LockedVersions) {
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.trim().is_empty() {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if !in_packages || !line.starts_with("  ") || line.starts_with("   ") {
+            continue;
+        }
+
+        let key = line
+            .trim()
+            .trim_end_matches(':')
+            .trim_matches(|c| c == '\'' || c == '"')
+            .trim_start_matches('/');
+        let key = key.split('(').next().unwrap_or_default();
+
+        let entry = match key.rfind('@') {
+            Some(at) if at > 0 => Some((&key[..at], &key[at + 1..])),
+            _ => key.rsplit_once('/'),
+        };
+        if let Some((name, version)) = entry {
+            locked.insert(name, version);
+        }
+    }
+}
+
+fn package_name_from_spec(spec: &str) -> Option<String> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    Some(spec[..at].to_string())
+}
+
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(file_name: &str, content: &str) -> LockedVersions {
+        parse_lockfile_content(file_name, content).unwrap()
+    }
+
+    fn versions<'a>(locked: &'a LockedVersions, name: &str) -> Vec<&'a str> {
+        let mut versions: Vec<&str> = locked
+            .packages()
+            .filter(|(package, _)| *package == name)
+            .map(|(_, version)| version)
+            .collect();
+        versions.sort();
+        versions
+    }
+
+    #[test]
+    fn cargo_lock_keeps_every_locked_version() {
+        let locked = lock(
+            "Cargo.lock",
+            r#"
+version = 3
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+
+[[package]]
+name = "syn"
+version = "2.0.87"
+
+[[package]]
+name = "serde"
+version = "1.0.214"
+"#,
+        );
+        assert_eq!(versions(&locked, "syn"), ["1.0.109", "2.0.87"]);
+        assert_eq!(versions(&locked, "serde"), ["1.0.214"]);
+    }
+
+    #[test]
+    fn python_toml_lockfiles_normalize_names() {
+        let content = r#"
+[[package]]
+name = "Flask_SQLAlchemy"
+version = "3.1.1"
+
+[[package]]
+name = "requests"
+version = "2.32.3"
+"#;
+        for file_name in ["poetry.lock", "uv.lock", "pdm.lock"] {
+            let locked = lock(file_name, content);
+            assert_eq!(
+                versions(&locked, "flask-sqlalchemy"),
+                ["3.1.1"],
+                "{}",
+                file_name
+            );
+            assert_eq!(versions(&locked, "requests"), ["2.32.3"], "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn npm_lock_v1_walks_nested_dependencies() {
+        let locked = lock(
+            "package-lock.json",
+            r#"{
+                "lockfileVersion": 1,
+                "dependencies": {
+                    "debug": {
+                        "version": "4.3.4",
+                        "dependencies": { "ms": { "version": "2.1.2" } }
+                    },
+                    "ms": { "version": "2.1.3" }
+                }
+            }"#,
+        );
+        assert_eq!(versions(&locked, "debug"), ["4.3.4"]);
+        assert_eq!(versions(&locked, "ms"), ["2.1.2", "2.1.3"]);
+    }
+
+    #[test]
+    fn npm_lock_v2_and_v3_read_packages_by_path() {
+        for lockfile_version in [2, 3] {
+            let locked = lock(
+                "package-lock.json",
+                &format!(
+                    r#"{{
+                        "lockfileVersion": {},
+                        "packages": {{
+                            "": {{ "name": "app", "version": "0.1.0" }},
+                            "node_modules/@scope/ui": {{ "version": "1.2.0" }},
+                            "node_modules/@scope/ui/node_modules/react": {{ "version": "17.0.2" }},
+                            "node_modules/react": {{ "version": "18.3.1" }},
+                            "node_modules/string-width-cjs": {{ "name": "string-width", "version": "4.2.3" }}
+                        }}
+                    }}"#,
+                    lockfile_version
+                ),
+            );
+            assert_eq!(versions(&locked, "app"), Vec::<&str>::new());
+            assert_eq!(versions(&locked, "@scope/ui"), ["1.2.0"]);
+            assert_eq!(versions(&locked, "react"), ["17.0.2", "18.3.1"]);
+            assert_eq!(versions(&locked, "string-width"), ["4.2.3"]);
+        }
+    }
+
+    #[test]
+    fn yarn_lock_classic_and_berry() {
+        let classic = lock(
+            "yarn.lock",
+            r#"# yarn lockfile v1
+
+"@babel/core@^7.0.0", "@babel/core@^7.20.0":
+  version "7.24.0"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz"
+
+lodash@^4.17.21:
+  version "4.17.21"
+"#,
+        );
+        assert_eq!(versions(&classic, "@babel/core"), ["7.24.0"]);
+        assert_eq!(versions(&classic, "lodash"), ["4.17.21"]);
+
+        let berry = lock(
+            "yarn.lock",
+            r#"__metadata:
+  version: 8
+
+"lodash@npm:^4.17.21":
+  version: 4.17.21
+  resolution: "lodash@npm:4.17.21"
+"#,
+        );
+        assert_eq!(versions(&berry, "lodash"), ["4.17.21"]);
+    }
+
+    #[test]
+    fn pnpm_lock_v5_v6_and_v9_keys() {
+        let locked = lock(
+            "pnpm-lock.yaml",
+            r#"lockfileVersion: '9.0'
+
+importers:
+  .:
+    dependencies:
+      react:
+        specifier: ^18.3.1
+        version: 18.3.1
+
+packages:
+  /left-pad/1.3.0:
+    resolution: {integrity: sha512-abc}
+  /@types/node@20.11.0:
+    resolution: {integrity: sha512-def}
+  react-dom@18.3.1(react@18.3.1):
+    resolution: {integrity: sha512-ghi}
+  '@vitejs/plugin-react@4.3.1':
+    resolution: {integrity: sha512-jkl}
+"#,
+        );
+        assert_eq!(versions(&locked, "left-pad"), ["1.3.0"]);
+        assert_eq!(versions(&locked, "@types/node"), ["20.11.0"]);
+        assert_eq!(versions(&locked, "react-dom"), ["18.3.1"]);
+        assert_eq!(versions(&locked, "@vitejs/plugin-react"), ["4.3.1"]);
+        // A seção importers não é lida como pacote
+        assert_eq!(versions(&locked, "react"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn go_sum_skips_go_mod_only_lines() {
+        let locked = lock(
+            "go.sum",
+            "golang.org/x/net v0.17.0 h1:abc=\n\
+             golang.org/x/net v0.17.0/go.mod h1:def=\n\
+             golang.org/x/net v0.10.0/go.mod h1:ghi=\n\
+             golang.org/x/text v0.13.0/go.mod h1:jkl=\n",
+        );
+        assert_eq!(versions(&locked, "golang.org/x/net"), ["v0.17.0"]);
+        assert_eq!(versions(&locked, "golang.org/x/text"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn pipfile_lock_reads_default_and_develop() {
+        let locked = lock(
+            "Pipfile.lock",
+            r#"{
+                "_meta": { "hash": { "sha256": "x" } },
+                "default": { "Django": { "version": "==4.2.7" } },
+                "develop": { "pytest_cov": { "version": "==4.1.0" } }
+            }"#,
+        );
+        assert_eq!(versions(&locked, "django"), ["4.2.7"]);
+        assert_eq!(versions(&locked, "pytest-cov"), ["4.1.0"]);
+    }
+
+    #[test]
+    fn unsupported_or_invalid_lockfiles_are_errors() {
+        assert!(parse_lockfile_content("Gemfile.lock", "").is_err());
+        assert!(parse_lockfile_content("Cargo.lock", "[[package]").is_err());
+        assert!(parse_lockfile_content("package-lock.json", "{").is_err());
+    }
+
+    #[test]
+    fn resolve_picks_version_matching_requirement() {
+        let mut locked = LockedVersions::default();
+        for version in ["1.0.109", "2.0.87", "2.0.50"] {
+            locked.insert("syn", version);
+        }
+        locked.insert("serde", "1.0.214");
+        for version in ["0.3.1", "0.4.2"] {
+            locked.insert("rand", version);
+        }
+        locked.insert("pyyaml", "6.0.1");
+
+        // Uma só versão travada vale para qualquer requisito
+        assert_eq!(
+            locked.resolve("serde", None, CARGO).as_deref(),
+            Some("1.0.214")
+        );
+        assert_eq!(locked.resolve("syn", None, CARGO), None);
+        assert_eq!(
+            locked.resolve("syn", Some("1"), CARGO).as_deref(),
+            Some("1.0.109")
+        );
+        assert_eq!(
+            locked.resolve("syn", Some("^2.0"), CARGO).as_deref(),
+            Some("2.0.87")
+        );
+        assert_eq!(
+            locked.resolve("syn", Some("=2.0.50"), CARGO).as_deref(),
+            Some("2.0.50")
+        );
+        // Em 0.x o minor também precisa bater
+        assert_eq!(
+            locked.resolve("rand", Some("0.3"), CARGO).as_deref(),
+            Some("0.3.1")
+        );
+        assert_eq!(locked.resolve("syn", Some("3.0"), CARGO), None);
+        assert_eq!(
+            locked.resolve("PyYAML", Some(">=6"), PYPI).as_deref(),
+            Some("6.0.1")
+        );
+    }
+
+    #[test]
+    fn version_numbers_ignore_prefixes_and_suffixes() {
+        assert_eq!(version_numbers("^1.2.3"), [1, 2, 3]);
+        assert_eq!(version_numbers(">=0.4, <0.5"), [0, 4]);
+        assert_eq!(version_numbers("v0.17.0"), [0, 17, 0]);
+        assert_eq!(version_numbers("2.0.0-beta.1+build"), [2, 0, 0]);
+        assert_eq!(version_numbers("~=3.1.x"), [3, 1]);
+        assert!(version_numbers("*").is_empty());
+    }
+
+    #[test]
+    fn normalize_python_name_follows_pep503() {
+        assert_eq!(
+            normalize_python_name("Flask_SQLAlchemy"),
+            "flask-sqlalchemy"
+        );
+        assert_eq!(normalize_python_name("zope.interface"), "zope-interface");
+        assert_eq!(normalize_python_name("a-_.b"), "a-b");
+    }
+
+    #[test]
+    fn cargo_workspace_dependencies_are_inherited() {
+        let mut manifests = vec![
+            parse_cargo_toml(
+                Path::new("Cargo.toml"),
+                &toml::from_str(
+                    r#"
+[workspace]
+members = ["crates/*"]
+exclude = ["crates/legacy"]
+
+[workspace.dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#,
+                )
+                .unwrap(),
+            ),
+            parse_cargo_toml(
+                Path::new("crates/core/Cargo.toml"),
+                &toml::from_str(
+                    r#"
+[package]
+name = "core"
+
+[dependencies]
+serde = { workspace = true }
+rand = { version = "0.8", optional = true }
+local = { path = "../local" }
+
+[dev-dependencies]
+tokio = "1"
+"#,
+                )
+                .unwrap(),
+            ),
+            parse_cargo_toml(
+                Path::new("crates/legacy/Cargo.toml"),
+                &toml::from_str("[package]\nname = \"legacy\"\n").unwrap(),
+            ),
+        ];
+        resolve_workspaces(&mut manifests);
+
+        let core = &manifests[1].dependencies;
+        let find = |name: &str| core.iter().find(|d| d.name == name).unwrap();
+        assert_eq!(find("serde").requirement.as_deref(), Some("1.0"));
+        assert_eq!(find("serde").workspace_member.as_deref(), Some("core"));
+        assert_eq!(find("rand").kind, DependencyKind::Optional);
+        assert_eq!(find("local").requirement.as_deref(), Some("path:../local"));
+        assert_eq!(find("tokio").kind, DependencyKind::Dev);
+        assert!(manifests[2].dependencies.is_empty());
+    }
+
+    #[test]
+    fn go_mod_marks_indirect_requirements() {
+        let manifest = parse_go_mod(
+            Path::new("go.mod"),
+            "module example.com/app\n\n\
+             require github.com/google/uuid v1.6.0\n\n\
+             require (\n\
+             \tgolang.org/x/net v0.17.0 // indirect\n\
+             \tgithub.com/spf13/cobra v1.8.0\n\
+             )\n",
+        );
+        assert_eq!(manifest.package_name.as_deref(), Some("example.com/app"));
+        let kinds: Vec<(&str, DependencyKind)> = manifest
+            .dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("github.com/google/uuid", DependencyKind::Runtime),
+                ("golang.org/x/net", DependencyKind::Indirect),
+                ("github.com/spf13/cobra", DependencyKind::Runtime),
+            ]
+        );
+    }
+
+    #[test]
+    fn requirements_txt_pins_become_resolved_versions() {
+        let manifest = parse_requirements_txt(
+            Path::new("requirements-dev.txt"),
+            "requirements-dev.txt",
+            "-r requirements.txt\n\
+             # comentário\n\
+             pytest==8.0.0\n\
+             black>=24 ; python_version >= \"3.8\"\n\
+             requests[socks]==2.31.* # curinga\n",
+        );
+        let resolved: Vec<(&str, Option<&str>)> = manifest
+            .dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.resolved_version.as_deref()))
+            .collect();
+        assert_eq!(
+            resolved,
+            [
+                ("pytest", Some("8.0.0")),
+                ("black", None),
+                ("requests", None)
+            ]
+        );
+        assert!(manifest
+            .dependencies
+            .iter()
+            .all(|d| d.kind == DependencyKind::Dev));
+    }
+
+    #[test]
+    fn collect_returns_project_manifests_it_parsed() {
+        let root = std::env::temp_dir().join(format!(
+            "rzonehub-dependencies-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let files: Vec<(PathBuf, u64)> = [
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            (
+                "crates/core/Cargo.toml",
+                "[package]\nname = \"core\"\n\n[dependencies]\nserde = \"1\"\n",
+            ),
+            ("requirements.txt", "requests==2.31.0\n"),
+        ]
+        .iter()
+        .map(|(path, content)| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            (path, content.len() as u64)
+        })
+        .collect();
+
+        let (dependencies, manifests) = collect_with_manifests(&root, &files);
+        assert_eq!(dependencies.len(), 2);
+        // requirements.txt declara dependências, mas não define um projeto
+        let paths: Vec<String> = manifests.iter().map(|m| slash_path(&m.path)).collect();
+        assert_eq!(paths, ["Cargo.toml", "crates/core/Cargo.toml"]);
+        assert_eq!(manifests[0].members, ["crates/*"]);
+        assert_eq!(manifests[1].name.as_deref(), Some("core"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,623 @@
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use super::languages::{self, Language};
+use crate::types::{CloneFragment, CloneGroup, DuplicateReport};
+
+const MAX_FILE_SIZE: u64 = 500_000;
+// Trechos repetidos dezenas de vezes (boilerplate gerado) explodiriam em pares
+const MAX_OCCURRENCES: usize = 64;
+const ROLLING_BASE: u64 = 1_000_003;
+// Palavras reservadas não são normalizadas: `if` e `while` não são renomeações um do outro.
+// Uma lista só para todas as linguagens (em ordem, para a busca binária); um nome que é
+// reservado em outra linguagem apenas deixa de ser normalizado
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "and",
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "del",
+    "do",
+    "elif",
+    "else",
+    "elsif",
+    "end",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "final",
+    "finally",
+    "fn",
+    "for",
+    "foreach",
+    "from",
+    "fun",
+    "func",
+    "function",
+    "go",
+    "goto",
+    "if",
+    "impl",
+    "implements",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "lambda",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "module",
+    "mut",
+    "new",
+    "not",
+    "or",
+    "package",
+    "pass",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "raise",
+    "ref",
+    "return",
+    "select",
+    "static",
+    "struct",
+    "switch",
+    "then",
+    "throw",
+    "throws",
+    "trait",
+    "try",
+    "type",
+    "unless",
+    "until",
+    "use",
+    "val",
+    "var",
+    "void",
+    "when",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+struct Token {
+    value: u64,
+    line: usize,
+}
+
+struct SourceFile {
+    path: String,
+    tokens: Vec<Token>,
+    lines: usize,
+}
+
+// Ocorrência de um trecho duplicado: arquivo, primeiro token e tamanho em tokens
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Fragment {
+    file: usize,
+    start: usize,
+    len: usize,
+}
+
+// Clones por sequência de tokens (espaços e comentários não contam). Com
+// `normalize_identifiers`, nomes e literais (mas não palavras reservadas) viram marcadores
+// genéricos e trechos que só diferem por renomeação também são encontrados
+pub fn detect(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    min_tokens: usize,
+    normalize_identifiers: bool,
+) -> DuplicateReport {
+    let min_tokens = min_tokens.max(1);
+    let mut sources: Vec<SourceFile> = files
+        .par_iter()
+        .filter(|(_, size)| *size < MAX_FILE_SIZE)
+        .filter_map(|(path, _)| {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let content = fs::read_to_string(path).ok()?;
+            let language = languages::detect(relative, content.lines().next())?;
+            if languages::is_data(language) {
+                return None;
+            }
+            Some(SourceFile {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                tokens: tokenize(language, &content, normalize_identifiers),
+                lines: content.lines().count(),
+            })
+        })
+        .collect();
+    // Ordem estável para que os grupos saiam sempre iguais
+    sources.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut windows: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    for (file, source) in sources.iter().enumerate() {
+        for (start, hash) in window_hashes(&source.tokens, min_tokens) {
+            windows.entry(hash).or_default().push((file, start));
+        }
+    }
+
+    let mut fragments: Vec<Fragment> = Vec::new();
+    let mut ids: HashMap<Fragment, usize> = HashMap::new();
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for occurrences in windows.values().filter(|o| o.len() > 1) {
+        let occurrences = &occurrences[..occurrences.len().min(MAX_OCCURRENCES)];
+        for (index, &a) in occurrences.iter().enumerate() {
+            for &b in &occurrences[index + 1..] {
+                let Some(len) = match_length(&sources, a, b, min_tokens) else {
+                    continue;
+                };
+                let mut id = |(file, start): (usize, usize)| {
+                    let fragment = Fragment { file, start, len };
+                    *ids.entry(fragment).or_insert_with(|| {
+                        fragments.push(fragment);
+                        fragments.len() - 1
+                    })
+                };
+                pairs.push((id(a), id(b)));
+            }
+        }
+    }
+
+    // Cada par liga os dois trechos; trechos do mesmo arquivo que se sobrepõem em pelo
+    // menos metade também, e cada componente vira um grupo de clones
+    let mut parent: Vec<usize> = (0..fragments.len()).collect();
+    for (a, b) in pairs {
+        union(&mut parent, a, b);
+    }
+    let mut order: Vec<usize> = (0..fragments.len()).collect();
+    order.sort_by_key(|&i| (fragments[i].file, fragments[i].start, fragments[i].len));
+    for (position, &i) in order.iter().enumerate() {
+        let a = fragments[i];
+        for &j in &order[position + 1..] {
+            let b = fragments[j];
+            if b.file != a.file || b.start >= a.start + a.len {
+                break;
+            }
+            let overlap = (a.start + a.len).min(b.start + b.len) - b.start;
+            if overlap * 2 >= a.len.min(b.len) {
+                union(&mut parent, i, j);
+            }
+        }
+    }
+
+    let mut components: HashMap<usize, Vec<usize>> = HashMap::new();
+    for &i in &order {
+        let root = find(&mut parent, i);
+        components.entry(root).or_default().push(i);
+    }
+
+    let mut duplicated: HashSet<(usize, usize)> = HashSet::new();
+    let mut clone_groups: Vec<CloneGroup> = Vec::new();
+    for members in components.into_values() {
+        // Trechos sobrepostos do mesmo arquivo viram uma região só
+        let mut regions: Vec<(usize, usize, usize)> = Vec::new();
+        for &i in &members {
+            let fragment = fragments[i];
+            let end = fragment.start + fragment.len;
+            match regions.last_mut() {
+                Some((file, _, region_end))
+                    if *file == fragment.file && fragment.start < *region_end =>
+                {
+                    *region_end = (*region_end).max(end);
+                }
+                _ => regions.push((fragment.file, fragment.start, end)),
+            }
+        }
+        if regions.len() < 2 {
+            continue;
+        }
+
+        let fragments_out: Vec<CloneFragment> = regions
+            .iter()
+            .map(|&(file, start, end)| {
+                let tokens = &sources[file].tokens;
+                let start_line = tokens[start].line;
+                let end_line = tokens[end - 1].line;
+                duplicated.extend((start_line..=end_line).map(|line| (file, line)));
+                CloneFragment {
+                    file: sources[file].path.clone(),
+                    start_line,
+                    end_line,
+                }
+            })
+            .collect();
+        clone_groups.push(CloneGroup {
+            tokens: members.iter().map(|&i| fragments[i].len).max().unwrap_or(0),
+            lines: fragments_out
+                .iter()
+                .map(|f| f.end_line - f.start_line + 1)
+                .max()
+                .unwrap_or(0),
+            fragments: fragments_out,
+        });
+    }
+    // Mais tokens repetidos primeiro (tamanho × cópias além da original)
+    clone_groups.sort_by(|a, b| {
+        (b.tokens * (b.fragments.len() - 1))
+            .cmp(&(a.tokens * (a.fragments.len() - 1)))
+            .then_with(|| a.fragments[0].file.cmp(&b.fragments[0].file))
+            .then_with(|| a.fragments[0].start_line.cmp(&b.fragments[0].start_line))
+    });
+
+    DuplicateReport {
+        files_analyzed: sources.len(),
+        tokens_analyzed: sources.iter().map(|s| s.tokens.len()).sum(),
+        total_lines: sources.iter().map(|s| s.lines).sum(),
+        duplicated_lines: duplicated.len(),
+        min_tokens,
+        normalize_identifiers,
+        groups: clone_groups,
+    }
+}
+
+// Tokens significativos do arquivo, usando a sintaxe de comentários e strings da
+// linguagem; strings viram um token só e operadores, um token por caractere
+fn tokenize(language: &Language, content: &str, normalize: bool) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut index = 0;
+
+    while index < content.len() {
+        let rest = &content[index..];
+        let c = rest.chars().next().unwrap_or(' ');
+
+        if c.is_whitespace() {
+            if c == '\n' {
+                line += 1;
+            }
+            index += c.len_utf8();
+            continue;
+        }
+
+        if let Some((open, close)) = language
+            .block_comments
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            let end = languages::block_comment_end(language, rest, open, close);
+            line += rest[..end].matches('\n').count();
+            index += end;
+            continue;
+        }
+
+        if language.line_comments.iter().any(|c| rest.starts_with(c)) {
+            index += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+
+        let start_line = line;
+        let (len, text) = if let Some((open, close)) = language
+            .quotes
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            let len = languages::string_end(language, rest, open, close);
+            line += rest[..len].matches('\n').count();
+            (len, if normalize { "\"\"" } else { &rest[..len] })
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            let text = match normalize {
+                true if c.is_ascii_digit() => "0",
+                true if KEYWORDS.binary_search(&word).is_ok() => word,
+                true => "$id",
+                false => word,
+            };
+            (len, text)
+        } else {
+            (c.len_utf8(), &rest[..c.len_utf8()])
+        };
+
+        tokens.push(Token {
+            value: hash_text(text),
+            line: start_line,
+        });
+        index += len;
+    }
+
+    tokens
+}
+
+// Hash de cada janela de `size` tokens (rolling hash polinomial)
+fn window_hashes(tokens: &[Token], size: usize) -> Vec<(usize, u64)> {
+    if tokens.len() < size {
+        return Vec::new();
+    }
+    let high = (1..size).fold(1u64, |power, _| power.wrapping_mul(ROLLING_BASE));
+    let mut hash = tokens[..size].iter().fold(0u64, |hash, t| {
+        hash.wrapping_mul(ROLLING_BASE).wrapping_add(t.value)
+    });
+
+    let mut hashes = Vec::with_capacity(tokens.len() - size + 1);
+    hashes.push((0, hash));
+    for start in 1..=tokens.len() - size {
+        hash = hash
+            .wrapping_sub(tokens[start - 1].value.wrapping_mul(high))
+            .wrapping_mul(ROLLING_BASE)
+            .wrapping_add(tokens[start + size - 1].value);
+        hashes.push((start, hash));
+    }
+    hashes
+}
+
+// Tamanho máximo da repetição que começa em `a` e `b`; None quando o par é só
+// continuação de uma repetição que começou antes, colisão de hash ou sobreposição
+fn match_length(
+    sources: &[SourceFile],
+    a: (usize, usize),
+    b: (usize, usize),
+    min_tokens: usize,
+) -> Option<usize> {
+    let left = &sources[a.0].tokens;
+    let right = &sources[b.0].tokens;
+    if a.1 > 0 && b.1 > 0 && left[a.1 - 1].value == right[b.1 - 1].value {
+        return None;
+    }
+
+    let mut len = 0;
+    while a.1 + len < left.len()
+        && b.1 + len < right.len()
+        && left[a.1 + len].value == right[b.1 + len].value
+    {
+        len += 1;
+    }
+    // No mesmo arquivo, a primeira cópia não pode invadir a segunda
+    if a.0 == b.0 {
+        len = len.min(b.1.abs_diff(a.1));
+    }
+    (len >= min_tokens).then_some(len)
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    if a != b {
+        parent[b] = a;
+    }
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Duas funções de provedor como as do ai_client.rs: mesma estrutura, só URL,
+    // modelo, mensagens e o tratamento de erro da Groq mudam
+    const PROVIDERS: &str = r#"use anyhow::{Context, Result};
+
+async fn mistral_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
+    let api_key = request.api_key.context("Mistral API key não fornecida")?;
+    let model = request.model.unwrap_or_else(|| MISTRAL_DEFAULT_MODEL.to_string());
+
+    let mut messages: Vec<Value> = request
+        .history
+        .iter()
+        .map(|msg| {
+            json!({
+                "role": msg.role,
+                "content": msg.content
+            })
+        })
+        .collect();
+
+    messages.push(json!({
+        "role": "user",
+        "content": request.prompt
+    }));
+
+    let body = json!({
+        "model": model,
+        "messages": messages
+    });
+
+    let response = client
+        .post(MISTRAL_CHAT_URL)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .context("Falha ao enviar requisição para Mistral")?;
+
+    let data: Value = response.json().await.context("Falha ao parsear resposta")?;
+
+    let content = data["choices"][0]["message"]["content"]
+        .as_str()
+        .context("Resposta inválida da Mistral")?
+        .to_string();
+
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
+}
+
+// Groq usa a mesma API compatível com a OpenAI
+async fn groq_chat(client: &Client, request: ChatRequest) -> Result<ChatResponse> {
+    let api_key = request.api_key.context("Groq API key não fornecida")?;
+    let model = request.model.unwrap_or_else(|| GROQ_DEFAULT_MODEL.to_string());
+
+    let mut messages: Vec<Value> = request
+        .history
+        .iter()
+        .map(|msg| {
+            json!({
+                "role": msg.role,
+                "content": msg.content
+            })
+        })
+        .collect();
+
+    messages.push(json!({
+        "role": "user",
+        "content": request.prompt
+    }));
+
+    let body = json!({
+        "model": model,
+        "messages": messages
+    });
+
+    let response = client
+        .post(GROQ_CHAT_URL)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .context("Falha ao enviar requisição para Groq")?;
+
+    let status = response.status();
+    let data: Value = response.json().await.context("Falha ao parsear resposta")?;
+
+    if let Some(error) = data.get("error") {
+        return Err(anyhow::anyhow!("Groq API error: {}", error));
+    }
+
+    let content = data["choices"][0]["message"]["content"]
+        .as_str()
+        .context("Resposta inválida da Groq")?
+        .to_string();
+
+    Ok(ChatResponse {
+        content,
+        usage: parse_usage(&data["usage"]),
+    })
+}
+"#;
+
+    fn detect_in(content: &str, min_tokens: usize, normalize: bool) -> DuplicateReport {
+        let root = std::env::temp_dir().join(format!(
+            "rzonehub-duplicates-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(root.join("src")).unwrap();
+        let path = root.join("src").join("ai_client.rs");
+        fs::write(&path, content).unwrap();
+        let size = content.len() as u64;
+
+        let report = detect(&root, &[(path, size)], min_tokens, normalize);
+        fs::remove_dir_all(&root).unwrap();
+        report
+    }
+
+    fn line_of(needle: &str) -> usize {
+        PROVIDERS
+            .lines()
+            .position(|line| line.contains(needle))
+            .unwrap()
+            + 1
+    }
+
+    #[test]
+    fn finds_copied_provider_functions() {
+        let report = detect_in(PROVIDERS, 50, false);
+        let group = &report.groups[0];
+        assert_eq!(group.fragments.len(), 2);
+        assert!(group.fragments.iter().all(|f| f.file == "src/ai_client.rs"));
+
+        // Sem normalização, o maior trecho idêntico é a montagem das mensagens
+        let mistral = line_of("async fn mistral_chat");
+        let groq = line_of("async fn groq_chat");
+        assert!(group.fragments[0].start_line > mistral);
+        assert!(group.fragments[0].end_line < groq);
+        assert!(group.fragments[1].start_line > groq);
+        assert!(group.lines >= 20, "grupo pequeno demais: {:?}", group);
+    }
+
+    #[test]
+    fn normalized_identifiers_match_whole_provider_functions() {
+        let report = detect_in(PROVIDERS, 50, true);
+        let group = &report.groups[0];
+        let starts: Vec<usize> = group.fragments.iter().map(|f| f.start_line).collect();
+        let ends: Vec<usize> = group.fragments.iter().map(|f| f.end_line).collect();
+
+        // Com nomes e literais normalizados, a cópia vai da assinatura até depois do
+        // envio, onde a Groq passa a ler o status antes do corpo
+        assert_eq!(
+            starts,
+            [
+                line_of("async fn mistral_chat"),
+                line_of("async fn groq_chat")
+            ]
+        );
+        assert_eq!(ends, [line_of("let data: Value"), line_of("let status")]);
+        assert!(report.duplicated_lines >= 2 * group.lines);
+    }
+
+    #[test]
+    fn unrelated_code_has_no_clones() {
+        let content = "fn soma(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                       fn dobro(valores: &[u32]) -> Vec<u32> {\n    valores.iter().map(|v| v * 2).collect()\n}\n";
+        let report = detect_in(content, 10, true);
+        assert!(report.groups.is_empty());
+        assert_eq!(report.duplicated_lines, 0);
+        assert_eq!(report.files_analyzed, 1);
+    }
+
+    #[test]
+    fn keywords_are_not_normalized() {
+        assert!(KEYWORDS.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // Mesmos nomes e a mesma forma, mas `if`/`return` viraram `while`/`break`
+        let content = "fn primeiro(itens: &[i32], limite: i32) -> i32 {\n\
+                       \x20   let mut total = 0;\n\
+                       \x20   if total < limite {\n\
+                       \x20       total = total + itens[0] * 2;\n\
+                       \x20       return total;\n\
+                       \x20   }\n\
+                       \x20   total\n\
+                       }\n\n\
+                       fn segundo(itens: &[i32], limite: i32) -> i32 {\n\
+                       \x20   let mut total = 0;\n\
+                       \x20   while total < limite {\n\
+                       \x20       total = total + itens[0] * 2;\n\
+                       \x20       break total;\n\
+                       \x20   }\n\
+                       \x20   total\n\
+                       }\n";
+        assert!(detect_in(content, 25, true).groups.is_empty());
+
+        // A mesma função só com nomes trocados continua sendo clone
+        let renamed = content.replace("while", "if").replace("break", "return");
+        let renamed = renamed.replace("fn segundo(itens", "fn segundo(valores");
+        let report = detect_in(&renamed, 25, true);
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].fragments.len(), 2);
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{complexity, languages};
+use crate::types::{Contributor, DirectoryOwnership, FileChurn, GitAnalytics, Hotspot};
+
+pub const DEFAULT_WINDOW_DAYS: u32 = 90;
+const MAX_CONTRIBUTORS_PER_DIRECTORY: usize = 5;
+// Pastas agrupadas até este nível (`src-tauri/src`, `src/components`)
+const DIRECTORY_DEPTH: usize = 2;
+const MAX_COMPLEXITY_FILE_SIZE: u64 = 500_000;
+const MAX_HOTSPOTS_IN_SUMMARY: usize = 5;
+
+#[derive(Default)]
+struct FileStats {
+    commits: usize,
+    additions: usize,
+    deletions: usize,
+    // Linhas alteradas por autor (e-mail)
+    authors: HashMap<String, usize>,
+    last_changed: i64,
+}
+
+#[derive(Default)]
+struct AuthorStats {
+    name: String,
+    commits: usize,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Default)]
+struct DirectoryStats {
+    commits: usize,
+    authors: HashMap<String, AuthorStats>,
+}
+
+#[derive(Default)]
+struct LogStats {
+    files: HashMap<String, FileStats>,
+    directories: HashMap<String, DirectoryStats>,
+    authors: HashMap<String, AuthorStats>,
+    commits: usize,
+    first_commit: Option<i64>,
+    last_commit: Option<i64>,
+}
+
+// Histórico local do git (sem rede) nos últimos `window_days` dias; None quando `root`
+// não está dentro de um repositório
+pub fn analyze(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    window_days: u32,
+    limit: usize,
+) -> Result<Option<GitAnalytics>> {
+    let Ok(toplevel) = git(root, &["rev-parse", "--show-toplevel"]) else {
+        return Ok(None);
+    };
+
+    // Repositório sem commits ainda não tem HEAD
+    let total_commits = git(root, &["rev-list", "--count", "HEAD", "--", "."])
+        .ok()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0);
+
+    let since = format!("--since={} days ago", window_days);
+    let log = if total_commits > 0 {
+        git(
+            root,
+            &[
+                "log",
+                &since,
+                "--no-merges",
+                "--no-renames",
+                "--relative",
+                "--numstat",
+                "--format=%x1e%aN%x09%aE%x09%at",
+                "--",
+                ".",
+            ],
+        )?
+    } else {
+        String::new()
+    };
+
+    let LogStats {
+        files: file_stats,
+        directories,
+        authors,
+        commits: commits_in_window,
+        first_commit,
+        last_commit,
+    } = parse_log(&log);
+
+    // Só arquivos que ainda existem (e não são ignorados) entram em churn e hotspots
+    let existing: HashMap<String, (&PathBuf, u64)> = files
+        .iter()
+        .filter_map(|(path, size)| {
+            let relative = path.strip_prefix(root).ok()?;
+            Some((relative.to_string_lossy().replace('\\', "/"), (path, *size)))
+        })
+        .collect();
+
+    let mut churn: Vec<FileChurn> = file_stats
+        .iter()
+        .filter(|(file, _)| existing.contains_key(*file))
+        .map(|(file, stats)| FileChurn {
+            file: file.clone(),
+            commits: stats.commits,
+            additions: stats.additions,
+            deletions: stats.deletions,
+            authors: stats.authors.len(),
+            last_changed: format_timestamp(stats.last_changed),
+        })
+        .collect();
+    churn.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+
+    let mut hotspots: Vec<Hotspot> = churn
+        .iter()
+        .filter_map(|file| {
+            let (path, size) = existing.get(&file.file)?;
+            let (complexity, lines) = file_complexity(&file.file, path, *size)?;
+            Some(Hotspot {
+                file: file.file.clone(),
+                commits: file.commits,
+                churn: file.additions + file.deletions,
+                complexity,
+                lines,
+                score: file.commits * complexity,
+            })
+        })
+        .filter(|hotspot| hotspot.score > 0)
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.churn.cmp(&a.churn))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    hotspots.truncate(limit);
+    churn.truncate(limit);
+
+    // Conhecimento sobre arquivos apagados não conta no bus factor
+    let current_files = || {
+        file_stats
+            .iter()
+            .filter(|(file, _)| existing.contains_key(*file))
+    };
+    let mut directory_ownership: Vec<DirectoryOwnership> = directories
+        .into_iter()
+        .map(|(directory, stats)| {
+            let owners =
+                file_owners(current_files().filter(|(file, _)| directory_of(file) == directory));
+            DirectoryOwnership {
+                commits: stats.commits,
+                contributors: contributors(stats.authors, MAX_CONTRIBUTORS_PER_DIRECTORY),
+                bus_factor: bus_factor(&owners),
+                directory,
+            }
+        })
+        .collect();
+    directory_ownership.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| a.directory.cmp(&b.directory))
+    });
+
+    let owners = file_owners(current_files());
+    Ok(Some(GitAnalytics {
+        repository_root: toplevel.trim().to_string(),
+        window_days,
+        total_commits,
+        commits_in_window,
+        first_commit: first_commit.map(format_timestamp),
+        last_commit: last_commit.map(format_timestamp),
+        bus_factor: bus_factor(&owners),
+        contributors: contributors(authors, usize::MAX),
+        files: churn,
+        hotspots,
+        directories: directory_ownership,
+    }))
+}
+
+// Totais de um `git log --numstat --format=%x1e%aN%x09%aE%x09%at`
+fn parse_log(log: &str) -> LogStats {
+    let mut stats = LogStats::default();
+
+    for record in log.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let mut fields = header.splitn(3, '\t');
+        let (Some(name), Some(email), Some(timestamp)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let timestamp: i64 = timestamp.trim().parse().unwrap_or_default();
+        let email = email.to_lowercase();
+        stats.commits += 1;
+        stats.first_commit = Some(stats.first_commit.map_or(timestamp, |t| t.min(timestamp)));
+        stats.last_commit = Some(stats.last_commit.map_or(timestamp, |t| t.max(timestamp)));
+
+        let author = stats.authors.entry(email.clone()).or_default();
+        author.name = name.to_string();
+        author.commits += 1;
+
+        // Um commit conta uma vez por pasta, mesmo mexendo em vários arquivos dela
+        let mut touched: BTreeSet<String> = BTreeSet::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(added), Some(deleted), Some(path)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            // Arquivos binários aparecem como "-"
+            let added: usize = added.parse().unwrap_or(0);
+            let deleted: usize = deleted.parse().unwrap_or(0);
+
+            author.additions += added;
+            author.deletions += deleted;
+
+            let file = stats.files.entry(path.to_string()).or_default();
+            file.commits += 1;
+            file.additions += added;
+            file.deletions += deleted;
+            *file.authors.entry(email.clone()).or_default() += added + deleted;
+            file.last_changed = file.last_changed.max(timestamp);
+
+            let directory_name = directory_of(path);
+            let directory = stats.directories.entry(directory_name.clone()).or_default();
+            let directory_author = directory.authors.entry(email.clone()).or_default();
+            if directory_author.name.is_empty() {
+                directory_author.name = name.to_string();
+            }
+            directory_author.additions += added;
+            directory_author.deletions += deleted;
+            touched.insert(directory_name);
+        }
+        for directory_name in touched {
+            if let Some(directory) = stats.directories.get_mut(&directory_name) {
+                directory.commits += 1;
+                if let Some(author) = directory.authors.get_mut(&email) {
+                    author.commits += 1;
+                }
+            }
+        }
+    }
+    stats
+}
+
+// Seção do resumo enviado à IA: hotspots e concentração de conhecimento
+pub fn summary(git: &GitAnalytics) -> String {
+    let mut summary = format!(
+        "## Histórico do git (últimos {} dias):\n- Commits: {} no período, {} no total\n- Bus factor: {}\n",
+        git.window_days, git.commits_in_window, git.total_commits, git.bus_factor
+    );
+    if !git.hotspots.is_empty() {
+        summary.push_str("- Hotspots (commits × complexidade):\n");
+        for hotspot in git.hotspots.iter().take(MAX_HOTSPOTS_IN_SUMMARY) {
+            summary.push_str(&format!(
+                "  - `{}`: {} commit(s), complexidade {}\n",
+                hotspot.file, hotspot.commits, hotspot.complexity
+            ));
+        }
+    }
+    let concentrated: Vec<&DirectoryOwnership> = git
+        .directories
+        .iter()
+        .filter(|directory| directory.bus_factor == 1)
+        .collect();
+    // Com um só contribuidor no período toda pasta teria bus factor 1; a lista não diria nada
+    if git.contributors.len() > 1 && !concentrated.is_empty() {
+        summary.push_str("- Pastas que dependem de uma só pessoa:\n");
+        for directory in concentrated.iter().take(MAX_HOTSPOTS_IN_SUMMARY) {
+            let owner = &directory.contributors[0];
+            summary.push_str(&format!(
+                "  - `{}`: principal contribuidor {}\n",
+                directory.directory, owner.name
+            ));
+        }
+    }
+    summary.push('\n');
+    summary
+}
+
+pub fn git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotepath=off"])
+        .args(args)
+        .output()
+        .context("Erro ao executar git. Certifique-se de que o Git está instalado.")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git falhou: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn directory_of(file: &str) -> String {
+    let parts: Vec<&str> = file.split('/').collect();
+    if parts.len() <= 1 {
+        return ".".to_string();
+    }
+    parts[..(parts.len() - 1).min(DIRECTORY_DEPTH)].join("/")
+}
+
+// Complexidade ciclomática somada das funções quando há gramática para a linguagem;
+// nos demais arquivos de código o tamanho em linhas faz o papel de complexidade.
+// Dados e documentação (lockfiles, manifestos, Markdown) não entram como hotspot
+fn file_complexity(relative: &str, path: &Path, size: u64) -> Option<(usize, usize)> {
+    let relative = Path::new(relative);
+    let language = languages::detect(relative, None)?;
+    if size > MAX_COMPLEXITY_FILE_SIZE || languages::is_data(language) {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    let lines = content.lines().filter(|l| !l.trim().is_empty()).count();
+    let complexity = complexity::analyze_file(relative, &content)
+        .map(|functions| functions.iter().map(|f| f.cyclomatic).sum())
+        .unwrap_or(lines);
+    Some((complexity, lines))
+}
+
+fn contributors(authors: HashMap<String, AuthorStats>, limit: usize) -> Vec<Contributor> {
+    let mut contributors: Vec<Contributor> = authors
+        .into_iter()
+        .map(|(email, stats)| Contributor {
+            name: stats.name,
+            email,
+            commits: stats.commits,
+            additions: stats.additions,
+            deletions: stats.deletions,
+        })
+        .collect();
+    contributors.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    contributors.truncate(limit);
+    contributors
+}
+
+// Dono de cada arquivo = quem mais alterou linhas dele na janela
+fn file_owners<'a>(files: impl Iterator<Item = (&'a String, &'a FileStats)>) -> Vec<&'a str> {
+    files
+        .filter_map(|(_, stats)| {
+            stats
+                .authors
+                .iter()
+                .max_by_key(|(email, lines)| (**lines, Reverse(email.as_str())))
+                .map(|(email, _)| email.as_str())
+        })
+        .collect()
+}
+
+// Quantas pessoas precisam sair para que mais da metade dos arquivos fique sem dono
+fn bus_factor(owners: &[&str]) -> usize {
+    let mut files_per_owner: HashMap<&str, usize> = HashMap::new();
+    for owner in owners {
+        *files_per_owner.entry(owner).or_default() += 1;
+    }
+    let mut counts: Vec<usize> = files_per_owner.into_values().collect();
+    counts.sort_by_key(|count| Reverse(*count));
+
+    let mut orphaned = 0;
+    for (index, count) in counts.iter().enumerate() {
+        orphaned += count;
+        if orphaned * 2 > owners.len() {
+            return index + 1;
+        }
+    }
+    counts.len()
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\x1eAna\tANA@exemplo.com\t1700000000\n\n10\t2\tsrc/lib.rs\n-\t-\tassets/logo.png\n3\t0\tREADME.md\n\
+\x1eBruno\tbruno@exemplo.com\t1700100000\n\n1\t1\tsrc/lib.rs\n5\t5\tsrc/ui/app/view.rs\n\
+\x1eAna\tana@exemplo.com\t1699900000\n\n4\t0\tsrc/main.rs\n";
+
+    #[test]
+    fn parse_log_totals_commits_files_and_authors() {
+        let stats = parse_log(LOG);
+        assert_eq!(stats.commits, 3);
+        assert_eq!(stats.first_commit, Some(1699900000));
+        assert_eq!(stats.last_commit, Some(1700100000));
+
+        // E-mails diferentes só na caixa são a mesma pessoa; binários somam 0 linhas
+        let ana = &stats.authors["ana@exemplo.com"];
+        assert_eq!((ana.name.as_str(), ana.commits), ("Ana", 2));
+        assert_eq!((ana.additions, ana.deletions), (17, 2));
+        let bruno = &stats.authors["bruno@exemplo.com"];
+        assert_eq!((bruno.commits, bruno.additions, bruno.deletions), (1, 6, 6));
+
+        let lib = &stats.files["src/lib.rs"];
+        assert_eq!((lib.commits, lib.additions, lib.deletions), (2, 11, 3));
+        assert_eq!(lib.authors["ana@exemplo.com"], 12);
+        assert_eq!(lib.authors["bruno@exemplo.com"], 2);
+        assert_eq!(lib.last_changed, 1700100000);
+        let logo = &stats.files["assets/logo.png"];
+        assert_eq!((logo.commits, logo.additions, logo.deletions), (1, 0, 0));
+
+        // Cada commit conta uma vez por pasta
+        let src = &stats.directories["src"];
+        assert_eq!(src.commits, 3);
+        assert_eq!(src.authors["ana@exemplo.com"].commits, 2);
+        assert_eq!(stats.directories["src/ui"].commits, 1);
+        assert_eq!(stats.directories["."].commits, 1);
+        assert_eq!(stats.directories["assets"].commits, 1);
+
+        let owners = file_owners(stats.files.iter().filter(|(file, _)| *file == "src/lib.rs"));
+        assert_eq!(owners, ["ana@exemplo.com"]);
+    }
+
+    #[test]
+    fn parse_log_ignores_empty_and_malformed_records() {
+        let stats = parse_log("");
+        assert_eq!(stats.commits, 0);
+        assert_eq!(stats.first_commit, None);
+
+        let stats = parse_log("\x1e\n\x1esem tabs\n1\t1\tsrc/a.rs\n");
+        assert_eq!(stats.commits, 0);
+        assert!(stats.files.is_empty());
+    }
+
+    #[test]
+    fn bus_factor_counts_owners_of_most_files() {
+        assert_eq!(bus_factor(&[]), 0);
+        assert_eq!(bus_factor(&["ana"]), 1);
+        assert_eq!(bus_factor(&["ana", "ana", "bruno"]), 1);
+        assert_eq!(bus_factor(&["ana", "ana", "bruno", "bruno"]), 2);
+        assert_eq!(bus_factor(&["ana", "bruno", "carla", "davi"]), 3);
+    }
+
+    #[test]
+    fn directory_of_groups_up_to_the_configured_depth() {
+        assert_eq!(directory_of("README.md"), ".");
+        assert_eq!(directory_of("src/lib.rs"), "src");
+        assert_eq!(directory_of("src/ui/button.tsx"), "src/ui");
+        assert_eq!(directory_of("src/ui/app/view.rs"), "src/ui");
+    }
+}
@@ -0,0 +1,406 @@
+use std::path::Path;
+
+// Sintaxe de comentários e strings usada na contagem de linhas
+pub struct Language {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub filenames: &'static [&'static str],
+    pub line_comments: &'static [&'static str],
+    pub block_comments: &'static [(&'static str, &'static str)],
+    pub nested_comments: bool,
+    pub quotes: &'static [(&'static str, &'static str)],
+    // Se strings com aspas simples/duplas atravessam linhas (Rust). Nas demais só
+    // crases e aspas triplas continuam abertas na linha seguinte.
+    pub multiline_strings: bool,
+}
+
+const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+const C_LINE: &[&str] = &["//"];
+const HASH: &[&str] = &["#"];
+const DOUBLE_QUOTE: &[(&str, &str)] = &[("\"", "\"")];
+const C_QUOTES: &[(&str, &str)] = &[("\"", "\""), ("'", "'")];
+const JS_QUOTES: &[(&str, &str)] = &[("\"", "\""), ("'", "'"), ("`", "`")];
+const PY_QUOTES: &[(&str, &str)] = &[
+    ("\"\"\"", "\"\"\""),
+    ("'''", "'''"),
+    ("\"", "\""),
+    ("'", "'"),
+];
+const HTML_BLOCK: &[(&str, &str)] = &[("<!--", "-->")];
+
+const fn lang(
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    quotes: &'static [(&'static str, &'static str)],
+) -> Language {
+    Language {
+        name,
+        extensions,
+        filenames: &[],
+        line_comments,
+        block_comments,
+        nested_comments: false,
+        quotes,
+        multiline_strings: false,
+    }
+}
+
+pub static LANGUAGES: &[Language] = &[
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        filenames: &[],
+        line_comments: C_LINE,
+        block_comments: C_BLOCK,
+        nested_comments: true,
+        // Aspas simples ficam de fora por causa de lifetimes ('a); só os literais
+        // '"' e '\"' são tratados para não abrir uma string
+        quotes: &[("'\"", "'"), ("'\\\"", "'"), ("r#\"", "\"#"), ("\"", "\"")],
+        multiline_strings: true,
+    },
+    lang(
+        "TypeScript",
+        &["ts", "mts", "cts"],
+        C_LINE,
+        C_BLOCK,
+        JS_QUOTES,
+    ),
+    lang("TSX", &["tsx"], C_LINE, C_BLOCK, JS_QUOTES),
+    lang(
+        "JavaScript",
+        &["js", "mjs", "cjs"],
+        C_LINE,
+        C_BLOCK,
+        JS_QUOTES,
+    ),
+    lang("JSX", &["jsx"], C_LINE, C_BLOCK, JS_QUOTES),
+    lang("Python", &["py", "pyw", "pyi"], HASH, &[], PY_QUOTES),
+    lang(
+        "Go",
+        &["go"],
+        C_LINE,
+        C_BLOCK,
+        &[("\"", "\""), ("`", "`"), ("'", "'")],
+    ),
+    lang("Java", &["java"], C_LINE, C_BLOCK, C_QUOTES),
+    lang(
+        "Kotlin",
+        &["kt", "kts"],
+        C_LINE,
+        C_BLOCK,
+        &[("\"\"\"", "\"\"\""), ("\"", "\""), ("'", "'")],
+    ),
+    lang(
+        "Scala",
+        &["scala", "sc"],
+        C_LINE,
+        C_BLOCK,
+        &[("\"\"\"", "\"\"\""), ("\"", "\"")],
+    ),
+    Language {
+        name: "Swift",
+        extensions: &["swift"],
+        filenames: &[],
+        line_comments: C_LINE,
+        block_comments: C_BLOCK,
+        nested_comments: true,
+        quotes: &[("\"\"\"", "\"\"\""), ("\"", "\"")],
+        multiline_strings: false,
+    },
+    lang("C", &["c", "h"], C_LINE, C_BLOCK, C_QUOTES),
+    lang(
+        "C++",
+        &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        C_LINE,
+        C_BLOCK,
+        C_QUOTES,
+    ),
+    lang("C#", &["cs"], C_LINE, C_BLOCK, C_QUOTES),
+    lang("Objective-C", &["m", "mm"], C_LINE, C_BLOCK, C_QUOTES),
+    lang("Dart", &["dart"], C_LINE, C_BLOCK, PY_QUOTES),
+    lang("PHP", &["php"], &["//", "#"], C_BLOCK, C_QUOTES),
+    lang(
+        "Ruby",
+        &["rb", "rake", "gemspec"],
+        HASH,
+        &[("=begin", "=end")],
+        C_QUOTES,
+    ),
+    lang("Perl", &["pl", "pm"], HASH, &[], C_QUOTES),
+    lang("Lua", &["lua"], &["--"], &[("--[[", "]]")], C_QUOTES),
+    lang("R", &["r"], HASH, &[], C_QUOTES),
+    lang(
+        "Elixir",
+        &["ex", "exs"],
+        HASH,
+        &[],
+        &[("\"\"\"", "\"\"\""), ("\"", "\"")],
+    ),
+    lang("Erlang", &["erl", "hrl"], &["%"], &[], DOUBLE_QUOTE),
+    Language {
+        name: "Haskell",
+        extensions: &["hs"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: &[("{-", "-}")],
+        nested_comments: true,
+        quotes: DOUBLE_QUOTE,
+        multiline_strings: false,
+    },
+    lang("Zig", &["zig"], C_LINE, &[], DOUBLE_QUOTE),
+    lang("Groovy", &["groovy", "gradle"], C_LINE, C_BLOCK, C_QUOTES),
+    lang(
+        "Shell",
+        &["sh", "bash", "zsh", "ksh", "fish"],
+        HASH,
+        &[],
+        C_QUOTES,
+    ),
+    lang(
+        "PowerShell",
+        &["ps1", "psm1", "psd1"],
+        HASH,
+        &[("<#", "#>")],
+        C_QUOTES,
+    ),
+    lang("Batch", &["bat", "cmd"], &["REM ", "rem ", "::"], &[], &[]),
+    lang("HTML", &["html", "htm"], &[], HTML_BLOCK, &[]),
+    lang("XML", &["xml", "xsd", "xsl", "plist"], &[], HTML_BLOCK, &[]),
+    lang("SVG", &["svg"], &[], HTML_BLOCK, &[]),
+    lang(
+        "Vue",
+        &["vue"],
+        C_LINE,
+        &[("<!--", "-->"), ("/*", "*/")],
+        JS_QUOTES,
+    ),
+    lang(
+        "Svelte",
+        &["svelte"],
+        C_LINE,
+        &[("<!--", "-->"), ("/*", "*/")],
+        JS_QUOTES,
+    ),
+    lang("CSS", &["css"], &[], C_BLOCK, C_QUOTES),
+    lang("SCSS", &["scss"], C_LINE, C_BLOCK, C_QUOTES),
+    lang("Sass", &["sass"], C_LINE, C_BLOCK, C_QUOTES),
+    lang("Less", &["less"], C_LINE, C_BLOCK, C_QUOTES),
+    lang("JSON", &["json", "jsonc"], C_LINE, C_BLOCK, DOUBLE_QUOTE),
+    lang(
+        "TOML",
+        &["toml"],
+        HASH,
+        &[],
+        &[("\"\"\"", "\"\"\""), ("\"", "\""), ("'", "'")],
+    ),
+    lang("YAML", &["yml", "yaml"], HASH, &[], C_QUOTES),
+    lang(
+        "INI",
+        &["ini", "cfg", "conf", "properties"],
+        &[";", "#"],
+        &[],
+        &[],
+    ),
+    lang("SQL", &["sql"], &["--"], C_BLOCK, C_QUOTES),
+    lang(
+        "GraphQL",
+        &["graphql", "gql"],
+        HASH,
+        &[],
+        &[("\"\"\"", "\"\"\""), ("\"", "\"")],
+    ),
+    lang("Protocol Buffers", &["proto"], C_LINE, C_BLOCK, C_QUOTES),
+    lang(
+        "Terraform",
+        &["tf", "tfvars"],
+        &["#", "//"],
+        C_BLOCK,
+        DOUBLE_QUOTE,
+    ),
+    lang("Markdown", &["md", "markdown", "mdx"], &[], HTML_BLOCK, &[]),
+    lang("Plain Text", &["txt"], &[], &[], &[]),
+    Language {
+        name: "Dockerfile",
+        extensions: &["dockerfile"],
+        filenames: &["dockerfile", "containerfile"],
+        line_comments: HASH,
+        block_comments: &[],
+        nested_comments: false,
+        quotes: C_QUOTES,
+        multiline_strings: false,
+    },
+    Language {
+        name: "Makefile",
+        extensions: &["mk", "mak"],
+        filenames: &["makefile", "gnumakefile"],
+        line_comments: HASH,
+        block_comments: &[],
+        nested_comments: false,
+        quotes: &[],
+        multiline_strings: false,
+    },
+    Language {
+        name: "CMake",
+        extensions: &["cmake"],
+        filenames: &["cmakelists.txt"],
+        line_comments: HASH,
+        block_comments: &[],
+        nested_comments: false,
+        quotes: DOUBLE_QUOTE,
+        multiline_strings: false,
+    },
+];
+
+// Arquivos conhecidos que usam a sintaxe de outra linguagem
+const FILENAME_ALIASES: &[(&str, &str)] = &[
+    ("gemfile", "Ruby"),
+    ("rakefile", "Ruby"),
+    ("vagrantfile", "Ruby"),
+    ("podfile", "Ruby"),
+    ("jenkinsfile", "Groovy"),
+    (".bashrc", "Shell"),
+    (".bash_profile", "Shell"),
+    (".zshrc", "Shell"),
+    (".profile", "Shell"),
+];
+
+// Interpretadores reconhecidos na linha shebang
+const SHEBANG_INTERPRETERS: &[(&str, &str)] = &[
+    ("python", "Python"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("sh", "Shell"),
+    ("dash", "Shell"),
+    ("ksh", "Shell"),
+    ("fish", "Shell"),
+    ("node", "JavaScript"),
+    ("deno", "TypeScript"),
+    ("bun", "JavaScript"),
+    ("ruby", "Ruby"),
+    ("perl", "Perl"),
+    ("php", "PHP"),
+    ("lua", "Lua"),
+    ("pwsh", "PowerShell"),
+];
+
+// Formatos de dados, configuração e documentação: contam linhas, mas não são código
+const DATA_LANGUAGES: &[&str] = &[
+    "JSON",
+    "TOML",
+    "YAML",
+    "INI",
+    "XML",
+    "SVG",
+    "Markdown",
+    "Plain Text",
+];
+
+pub fn is_data(language: &Language) -> bool {
+    DATA_LANGUAGES.contains(&language.name)
+}
+
+pub fn by_name(name: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.name == name)
+}
+
+// Detecta pela ordem: nome de arquivo conhecido, extensão e, sem extensão, shebang
+pub fn detect(path: &Path, first_line: Option<&str>) -> Option<&'static Language> {
+    let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+
+    if let Some(language) = LANGUAGES.iter().find(|l| {
+        l.filenames
+            .iter()
+            .any(|f| file_name == *f || file_name.starts_with(&format!("{}.", f)))
+    }) {
+        return Some(language);
+    }
+
+    if let Some((_, name)) = FILENAME_ALIASES.iter().find(|(f, _)| file_name == *f) {
+        return by_name(name);
+    }
+
+    if let Some(extension) = path.extension() {
+        let extension = extension.to_string_lossy().to_lowercase();
+        if let Some(language) = LANGUAGES
+            .iter()
+            .find(|l| l.extensions.contains(&extension.as_str()))
+        {
+            return Some(language);
+        }
+    }
+
+    if path.extension().is_none() {
+        return first_line.and_then(detect_shebang);
+    }
+
+    None
+}
+
+// Fim do comentário de bloco que começa em `text` (incluindo o fechamento)
+pub fn block_comment_end(language: &Language, text: &str, open: &str, close: &str) -> usize {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        if rest.starts_with(close) && depth > 0 {
+            depth -= 1;
+            index += close.len();
+            if depth == 0 {
+                return index;
+            }
+        } else if rest.starts_with(open) && (depth == 0 || language.nested_comments) {
+            depth += 1;
+            index += open.len();
+        } else {
+            index += next_char_len(rest);
+        }
+    }
+    text.len()
+}
+
+// Fim da string (incluindo o fechamento); escapes com barra são pulados e, nas
+// linguagens sem strings multilinha, aspas simples/duplas terminam no fim da linha
+pub fn string_end(language: &Language, text: &str, open: &str, close: &str) -> usize {
+    let single_line = !language.multiline_strings && (close == "\"" || close == "'");
+    let mut index = open.len();
+    while index < text.len() {
+        let rest = &text[index..];
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            index += 1 + next_char_len(escaped);
+        } else if rest.starts_with(close) {
+            return index + close.len();
+        } else if single_line && rest.starts_with('\n') {
+            return index;
+        } else {
+            index += next_char_len(rest);
+        }
+    }
+    text.len()
+}
+
+fn next_char_len(text: &str) -> usize {
+    text.chars().next().map(|c| c.len_utf8()).unwrap_or(1)
+}
+
+fn detect_shebang(line: &str) -> Option<&'static Language> {
+    let command = line.strip_prefix("#!")?.trim();
+    let mut parts = command.split_whitespace();
+    let mut program = parts.next()?.rsplit('/').next()?;
+
+    // #!/usr/bin/env [-S] python3
+    if program == "env" {
+        program = parts.find(|p| !p.starts_with('-'))?;
+    }
+
+    SHEBANG_INTERPRETERS
+        .iter()
+        .find(|(interpreter, _)| {
+            program == *interpreter
+                || program
+                    .strip_prefix(interpreter)
+                    .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        })
+        .and_then(|(_, name)| by_name(name))
+}
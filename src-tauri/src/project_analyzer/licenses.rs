@@ -0,0 +1,1038 @@
+use anyhow::Context;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml::Value as TomlValue;
+
+use super::dependencies;
+use crate::types::{
+    Dependency, DependencyKind, DependencyLicense, LicenseEvidence, LicenseEvidenceKind,
+    LicensePolicy, LicenseReport, LicenseStatus, ProjectLicense,
+};
+
+// Política do projeto; sem ela vale a global em <config>/RZOneHub/license-policy.toml
+pub const PROJECT_POLICY_FILE: &str = ".rzonehub/license-policy.toml";
+const POLICY_FILE_NAME: &str = "license-policy.toml";
+
+const LICENSE_FILE_PREFIXES: &[&str] = &["license", "licence", "copying", "unlicense"];
+// Arquivos de licença só na raiz e um nível abaixo (ex.: src-tauri/LICENSE)
+const MAX_LICENSE_FILE_DEPTH: usize = 2;
+const MAX_LICENSE_TEXT: u64 = 200_000;
+const MAX_HEADER_FILE_SIZE: u64 = 500_000;
+const MAX_HEADER_LINES: usize = 20;
+const VIRTUALENV_DIRS: &[&str] = &[".venv", "venv", "env"];
+
+// Trechos característicos do texto de cada licença, das variantes mais específicas para
+// as genéricas. A comparação diferencia maiúsculas: o texto da GPL cita a "GNU Lesser
+// General Public License", mas só a LGPL tem o título em maiúsculas
+const LICENSE_TEXTS: &[(&str, &[&str])] = &[
+    ("AGPL-3.0", &["GNU AFFERO GENERAL PUBLIC LICENSE"]),
+    (
+        "LGPL-3.0",
+        &["GNU LESSER GENERAL PUBLIC LICENSE", "Version 3"],
+    ),
+    (
+        "LGPL-2.1",
+        &["GNU LESSER GENERAL PUBLIC LICENSE", "Version 2.1"],
+    ),
+    ("GPL-3.0", &["GNU GENERAL PUBLIC LICENSE", "Version 3"]),
+    ("GPL-2.0", &["GNU GENERAL PUBLIC LICENSE", "Version 2"]),
+    ("Apache-2.0", &["Apache License", "Version 2.0"]),
+    ("MPL-2.0", &["Mozilla Public License", "2.0"]),
+    ("EPL-2.0", &["Eclipse Public License", "2.0"]),
+    ("BSL-1.0", &["Boost Software License"]),
+    (
+        "Unlicense",
+        &["This is free and unencumbered software released into the public domain"],
+    ),
+    ("CC0-1.0", &["CC0 1.0 Universal"]),
+    (
+        "ISC",
+        &["Permission to use, copy, modify, and/or distribute this software for any purpose"],
+    ),
+    (
+        "BSD-3-Clause",
+        &[
+            "Redistribution and use in source and binary forms",
+            "Neither the name",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["Redistribution and use in source and binary forms"],
+    ),
+    (
+        "Zlib",
+        &[
+            "This software is provided 'as-is'",
+            "must not be misrepresented",
+        ],
+    ),
+    ("MIT", &["Permission is hereby granted, free of charge"]),
+];
+
+// Nomes comuns (manifestos antigos, classifiers do PyPI) para o identificador SPDX
+const LICENSE_ALIASES: &[(&str, &str)] = &[
+    ("mit license", "MIT"),
+    ("the mit license", "MIT"),
+    ("apache 2.0", "Apache-2.0"),
+    ("apache-2", "Apache-2.0"),
+    ("apache license 2.0", "Apache-2.0"),
+    ("apache license, version 2.0", "Apache-2.0"),
+    ("apache software license", "Apache-2.0"),
+    ("isc license", "ISC"),
+    ("isc license (iscl)", "ISC"),
+    ("mozilla public license 2.0 (mpl 2.0)", "MPL-2.0"),
+    ("gplv2", "GPL-2.0"),
+    ("gplv3", "GPL-3.0"),
+    ("gnu general public license v2 (gplv2)", "GPL-2.0"),
+    ("gnu general public license v3 (gplv3)", "GPL-3.0"),
+    ("gnu lesser general public license v3 (lgplv3)", "LGPL-3.0"),
+    ("gnu affero general public license v3", "AGPL-3.0"),
+    ("the unlicense (unlicense)", "Unlicense"),
+];
+
+// Copyleft forte: obriga a distribuir o projeto inteiro sob a mesma licença
+const STRONG_COPYLEFT: &[&str] = &["GPL-", "AGPL-", "SSPL-", "EUPL-", "OSL-"];
+
+// Licença do próprio projeto, licenças das dependências diretas e a checagem contra a
+// política (permitidas/proibidas) e contra copyleft forte num projeto permissivo. As
+// dependências vêm já coletadas, como em `stack::detect`
+pub fn report(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    dependencies: &[Dependency],
+    policy: LicensePolicy,
+) -> LicenseReport {
+    let project = project_license(root, files);
+    // Só conta como copyleft se todas as alternativas da licença do projeto forem
+    let project_copyleft =
+        project
+            .license
+            .as_deref()
+            .map(alternatives)
+            .is_some_and(|alternatives| {
+                !alternatives.is_empty()
+                    && alternatives
+                        .iter()
+                        .all(|ids| ids.iter().any(|id| is_copyleft(id)))
+            });
+
+    let mut lookup = Lookup::new(root);
+    let declared = dependencies;
+    let mut dependencies: Vec<DependencyLicense> = Vec::new();
+    let mut seen: HashMap<(String, String, Option<String>), usize> = HashMap::new();
+    for dependency in declared {
+        let key = (
+            dependency.ecosystem.clone(),
+            dependency.name.clone(),
+            dependency.resolved_version.clone(),
+        );
+        // A mesma dependência em vários manifestos aparece uma vez, como runtime se
+        // algum deles a distribui
+        if let Some(&index) = seen.get(&key) {
+            if is_distributed(dependency.kind) && !is_distributed(dependencies[index].kind) {
+                dependencies[index].kind = dependency.kind;
+            }
+            continue;
+        }
+        let (license, source) = match lookup.find(dependency) {
+            Some((license, source)) => (Some(license), Some(source)),
+            None => (None, None),
+        };
+        seen.insert(key, dependencies.len());
+        dependencies.push(DependencyLicense {
+            name: dependency.name.clone(),
+            version: dependency.resolved_version.clone(),
+            ecosystem: dependency.ecosystem.clone(),
+            kind: dependency.kind,
+            manifest: dependency.manifest.clone(),
+            license,
+            source,
+            status: LicenseStatus::Unknown,
+            reason: None,
+        });
+    }
+
+    for dependency in &mut dependencies {
+        let (status, reason) = evaluate(dependency, &policy, project_copyleft);
+        dependency.status = status;
+        dependency.reason = reason;
+    }
+    dependencies.sort_by(|a, b| {
+        b.status
+            .cmp(&a.status)
+            .then_with(|| a.ecosystem.cmp(&b.ecosystem))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    LicenseReport {
+        issues: dependencies
+            .iter()
+            .filter(|d| d.status != LicenseStatus::Allowed)
+            .count(),
+        project,
+        policy,
+        dependencies,
+    }
+}
+
+pub fn policy_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("RZOneHub"))
+}
+
+// Política do projeto, senão a global do usuário; arquivo inválido vale como sem política
+pub fn load_policy(root: &Path) -> LicensePolicy {
+    let path = Some(root.join(PROJECT_POLICY_FILE))
+        .filter(|path| path.is_file())
+        .or_else(|| policy_dir().map(|dir| dir.join(POLICY_FILE_NAME)))
+        .filter(|path| path.is_file());
+    let Some(path) = path else {
+        return LicensePolicy::default();
+    };
+
+    fs::read_to_string(&path)
+        .context("Erro ao ler arquivo")
+        .and_then(|content| toml::from_str(&content).context("TOML inválido"))
+        .unwrap_or_else(|e| {
+            log::warn!(
+                "Ignorando política de licenças inválida {:?}: {:#}",
+                path,
+                e
+            );
+            LicensePolicy::default()
+        })
+}
+
+fn project_license(root: &Path, files: &[(PathBuf, u64)]) -> ProjectLicense {
+    let mut evidence: Vec<LicenseEvidence> = Vec::new();
+    let mut headers: BTreeMap<String, (usize, String)> = BTreeMap::new();
+
+    for (path, size) in files {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let display = relative.to_string_lossy().replace('\\', "/");
+        let file_name = relative
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let depth = relative.components().count();
+
+        if depth <= MAX_LICENSE_FILE_DEPTH
+            && LICENSE_FILE_PREFIXES
+                .iter()
+                .any(|p| file_name.starts_with(p))
+            && *size < MAX_LICENSE_TEXT
+        {
+            if let Some(license) = fs::read_to_string(path)
+                .ok()
+                .and_then(|t| identify_text(&t))
+            {
+                evidence.push(LicenseEvidence {
+                    kind: LicenseEvidenceKind::File,
+                    path: display.clone(),
+                    license,
+                    occurrences: 1,
+                });
+                continue;
+            }
+        }
+
+        if let Some(license) = manifest_license(path, &file_name) {
+            evidence.push(LicenseEvidence {
+                kind: LicenseEvidenceKind::Manifest,
+                path: display.clone(),
+                license,
+                occurrences: 1,
+            });
+            continue;
+        }
+
+        if *size < MAX_HEADER_FILE_SIZE {
+            if let Some(license) = spdx_header(path) {
+                let entry = headers.entry(license).or_insert((0, display));
+                entry.0 += 1;
+            }
+        }
+    }
+
+    // Manifesto mais perto da raiz, depois arquivos de licença (LICENSE-MIT e
+    // LICENSE-APACHE juntos indicam licença dupla) e por último os cabeçalhos SPDX
+    let manifest = evidence
+        .iter()
+        .filter(|e| e.kind == LicenseEvidenceKind::Manifest)
+        .min_by_key(|e| (e.path.matches('/').count(), e.path.clone()))
+        .map(|e| e.license.clone());
+    let mut license_files: Vec<&str> = evidence
+        .iter()
+        .filter(|e| e.kind == LicenseEvidenceKind::File && !e.path.contains('/'))
+        .map(|e| e.license.as_str())
+        .collect();
+    license_files.dedup();
+    let header = headers
+        .iter()
+        .max_by_key(|(_, (count, _))| *count)
+        .map(|(license, _)| license.clone());
+    let license = manifest
+        .or_else(|| (!license_files.is_empty()).then(|| license_files.join(" OR ")))
+        .or(header);
+
+    evidence.extend(
+        headers
+            .into_iter()
+            .map(|(license, (occurrences, path))| LicenseEvidence {
+                kind: LicenseEvidenceKind::Header,
+                path,
+                license,
+                occurrences,
+            }),
+    );
+    ProjectLicense { license, evidence }
+}
+
+// Campo de licença de Cargo.toml, package.json ou pyproject.toml (vazio conta como ausente)
+fn manifest_license(path: &Path, file_name: &str) -> Option<String> {
+    let license = match file_name {
+        "cargo.toml" => {
+            let data: TomlValue = toml::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            let package = data
+                .get("package")
+                .or_else(|| data.get("workspace").and_then(|w| w.get("package")))?;
+            cargo_license(package, path.parent()?)?
+        }
+        "package.json" => {
+            let data: JsonValue = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            npm_license(&data)?
+        }
+        "pyproject.toml" => {
+            let data: TomlValue = toml::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            let license = data
+                .get("project")
+                .and_then(|p| p.get("license"))
+                .or_else(|| {
+                    data.get("tool")
+                        .and_then(|t| t.get("poetry"))
+                        .and_then(|p| p.get("license"))
+                })?;
+            match license {
+                TomlValue::String(license) => license.clone(),
+                TomlValue::Table(table) => match (table.get("text"), table.get("file")) {
+                    (Some(TomlValue::String(text)), _) => normalize_id(text),
+                    (_, Some(TomlValue::String(file))) => {
+                        identify_text(&fs::read_to_string(path.parent()?.join(file)).ok()?)?
+                    }
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let license = license.trim().to_string();
+    (!license.is_empty()).then_some(license)
+}
+
+fn cargo_license(package: &TomlValue, dir: &Path) -> Option<String> {
+    if let Some(license) = package
+        .get("license")
+        .and_then(|l| l.as_str())
+        .filter(|l| !l.trim().is_empty())
+    {
+        return Some(license.trim().to_string());
+    }
+    let file = package.get("license-file").and_then(|f| f.as_str())?;
+    identify_text(&fs::read_to_string(dir.join(file)).ok()?)
+}
+
+// "license": "MIT", o formato antigo { "type": "MIT" } ou "licenses": [{ "type": ... }]
+fn npm_license(data: &JsonValue) -> Option<String> {
+    let license = match &data["license"] {
+        JsonValue::String(license) => license.clone(),
+        JsonValue::Object(object) => object.get("type")?.as_str()?.to_string(),
+        _ => {
+            let types: Vec<&str> = data["licenses"]
+                .as_array()?
+                .iter()
+                .filter_map(|l| l["type"].as_str())
+                .collect();
+            if types.is_empty() {
+                return None;
+            }
+            types.join(" OR ")
+        }
+    };
+    let license = license.trim();
+    (!license.is_empty()).then(|| license.to_string())
+}
+
+fn spdx_header(path: &Path) -> Option<String> {
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    let header =
+        HEADER.get_or_init(|| Regex::new(r"SPDX-License-Identifier:\s*([^*\r\n]+)").unwrap());
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().take(MAX_HEADER_LINES).find_map(|line| {
+        let license = header.captures(line)?.get(1)?.as_str();
+        let license = license.trim().trim_end_matches("-->").trim();
+        (!license.is_empty()).then(|| license.to_string())
+    })
+}
+
+// Identificador SPDX a partir do texto completo da licença
+pub fn identify_text(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    LICENSE_TEXTS
+        .iter()
+        .find(|(_, needles)| needles.iter().all(|needle| text.contains(needle)))
+        .map(|(id, _)| id.to_string())
+}
+
+fn normalize_id(license: &str) -> String {
+    let license = license.trim();
+    let lower = license.to_lowercase();
+    LICENSE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == lower)
+        .map(|(_, id)| id.to_string())
+        .unwrap_or_else(|| license.to_string())
+}
+
+// Alternativas de uma expressão SPDX: `A OR (B AND C)` -> [[A], [B, C]]. `WITH exceção`
+// é descartado, `/` (formato antigo do Cargo) vale como OR e os parênteses são ignorados
+fn alternatives(expression: &str) -> Vec<Vec<String>> {
+    static OR: OnceLock<Regex> = OnceLock::new();
+    static AND: OnceLock<Regex> = OnceLock::new();
+    static WITH: OnceLock<Regex> = OnceLock::new();
+    let or = OR.get_or_init(|| Regex::new(r"(?i)\s+OR\s+|/").unwrap());
+    let and = AND.get_or_init(|| Regex::new(r"(?i)\s+AND\s+").unwrap());
+    let with = WITH.get_or_init(|| Regex::new(r"(?i)\s+WITH\s+").unwrap());
+
+    let expression = expression.replace(['(', ')'], " ");
+    or.split(expression.trim())
+        .map(|alternative| {
+            and.split(alternative)
+                .map(|id| normalize_id(with.split(id).next().unwrap_or(id)))
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<String>>()
+        })
+        .filter(|ids| !ids.is_empty())
+        .collect()
+}
+
+fn is_copyleft(id: &str) -> bool {
+    let id = id.to_uppercase();
+    id == "GPL" || STRONG_COPYLEFT.iter().any(|prefix| id.starts_with(prefix))
+}
+
+// Dependências de desenvolvimento e de build não vão junto com o produto
+fn is_distributed(kind: DependencyKind) -> bool {
+    !matches!(kind, DependencyKind::Dev | DependencyKind::Build)
+}
+
+// `*` no fim do padrão casa por prefixo (ex.: `GPL-*`)
+fn matches_policy(pattern: &str, id: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let id = id.to_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => id.starts_with(prefix),
+        None => pattern == id,
+    }
+}
+
+// Com licença dupla basta uma alternativa aceitável; numa combinação com AND vale a
+// pior das licenças
+fn evaluate(
+    dependency: &DependencyLicense,
+    policy: &LicensePolicy,
+    project_copyleft: bool,
+) -> (LicenseStatus, Option<String>) {
+    if policy
+        .exceptions
+        .iter()
+        .any(|name| name == &dependency.name)
+    {
+        return (
+            LicenseStatus::Allowed,
+            Some("Exceção na política de licenças".to_string()),
+        );
+    }
+    let Some(license) = &dependency.license else {
+        return (
+            LicenseStatus::Unknown,
+            Some(
+                "Licença não encontrada nos manifestos, lockfiles ou metadados locais".to_string(),
+            ),
+        );
+    };
+
+    alternatives(license)
+        .iter()
+        .map(|ids| {
+            ids.iter()
+                .map(|id| check_id(id, dependency.kind, policy, project_copyleft))
+                .max_by_key(|(status, _)| *status)
+                .unwrap_or((LicenseStatus::Allowed, None))
+        })
+        .min_by_key(|(status, _)| *status)
+        .unwrap_or((LicenseStatus::Unknown, None))
+}
+
+fn check_id(
+    id: &str,
+    kind: DependencyKind,
+    policy: &LicensePolicy,
+    project_copyleft: bool,
+) -> (LicenseStatus, Option<String>) {
+    if policy
+        .deny
+        .iter()
+        .any(|pattern| matches_policy(pattern, id))
+    {
+        return (
+            LicenseStatus::Denied,
+            Some(format!("Licença {} proibida pela política", id)),
+        );
+    }
+    let allowed = policy
+        .allow
+        .iter()
+        .any(|pattern| matches_policy(pattern, id));
+    if !policy.allow.is_empty() && !allowed {
+        return (
+            LicenseStatus::Unlisted,
+            Some(format!("Licença {} fora da lista de permitidas", id)),
+        );
+    }
+    if !allowed && !project_copyleft && is_distributed(kind) && is_copyleft(id) {
+        return (
+            LicenseStatus::Incompatible,
+            Some(format!(
+                "Licença copyleft {} em dependência distribuída de um projeto sem copyleft",
+                id
+            )),
+        );
+    }
+    (LicenseStatus::Allowed, None)
+}
+
+// Onde procurar a licença declarada de cada dependência, sem acessar a rede
+struct Lookup {
+    root: PathBuf,
+    npm_lockfiles: HashMap<PathBuf, Option<JsonValue>>,
+    cargo_registries: Vec<PathBuf>,
+    go_module_cache: Option<PathBuf>,
+    site_packages: Option<Vec<PathBuf>>,
+}
+
+impl Lookup {
+    fn new(root: &Path) -> Lookup {
+        let cargo_home = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")));
+        let cargo_registries = cargo_home
+            .and_then(|home| fs::read_dir(home.join("registry").join("src")).ok())
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        let go_module_cache = std::env::var_os("GOMODCACHE")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("GOPATH")
+                    .map(PathBuf::from)
+                    .or_else(|| dirs::home_dir().map(|home| home.join("go")))
+                    .map(|gopath| gopath.join("pkg").join("mod"))
+            });
+
+        Lookup {
+            root: root.to_path_buf(),
+            npm_lockfiles: HashMap::new(),
+            cargo_registries,
+            go_module_cache,
+            site_packages: None,
+        }
+    }
+
+    // (licença, de onde veio)
+    fn find(&mut self, dependency: &Dependency) -> Option<(String, String)> {
+        let manifest_dir = Path::new(&dependency.manifest)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let version = dependency.resolved_version.as_deref();
+        match dependency.ecosystem.as_str() {
+            dependencies::NPM => self.npm(&dependency.name, &manifest_dir),
+            dependencies::CARGO => self.cargo(&dependency.name, version, &manifest_dir),
+            dependencies::GO => self.go(&dependency.name, version),
+            dependencies::PYPI => self.pypi(&dependency.name),
+            _ => None,
+        }
+    }
+
+    fn npm(&mut self, name: &str, manifest_dir: &Path) -> Option<(String, String)> {
+        for dir in manifest_dir.ancestors() {
+            let lockfile = self.root.join(dir).join("package-lock.json");
+            let data = self
+                .npm_lockfiles
+                .entry(lockfile.clone())
+                .or_insert_with(|| {
+                    fs::read_to_string(&lockfile)
+                        .ok()
+                        .and_then(|content| serde_json::from_str(&content).ok())
+                });
+            if let Some(license) = data
+                .as_ref()
+                .and_then(|data| npm_license(&data["packages"][format!("node_modules/{}", name)]))
+            {
+                return Some((license, self.display(&lockfile)));
+            }
+
+            let package = self
+                .root
+                .join(dir)
+                .join("node_modules")
+                .join(name)
+                .join("package.json");
+            if let Some(license) = fs::read_to_string(&package)
+                .ok()
+                .and_then(|content| serde_json::from_str::<JsonValue>(&content).ok())
+                .and_then(|data| npm_license(&data))
+            {
+                return Some((license, self.display(&package)));
+            }
+        }
+        None
+    }
+
+    fn cargo(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        manifest_dir: &Path,
+    ) -> Option<(String, String)> {
+        let versioned = version.map(|version| format!("{}-{}", name, version));
+        // `cargo vendor` primeiro; depois o código-fonte já baixado no registro local
+        let vendored = manifest_dir.ancestors().flat_map(|dir| {
+            let vendor = self.root.join(dir).join("vendor");
+            let mut candidates = vec![vendor.join(name)];
+            candidates.extend(versioned.as_ref().map(|v| vendor.join(v)));
+            candidates
+        });
+        // Sem lockfile a versão não é conhecida: vale a mais recente já baixada
+        let registry = self
+            .cargo_registries
+            .iter()
+            .filter_map(|registry| match &versioned {
+                Some(versioned) => Some(registry.join(versioned)),
+                None => latest_cached_crate(registry, name),
+            });
+
+        vendored.chain(registry).find_map(|dir| {
+            let manifest = dir.join("Cargo.toml");
+            let data: TomlValue = toml::from_str(&fs::read_to_string(&manifest).ok()?).ok()?;
+            let license = cargo_license(data.get("package")?, &dir)?;
+            Some((license, self.display(&manifest)))
+        })
+    }
+
+    fn go(&self, module: &str, version: Option<&str>) -> Option<(String, String)> {
+        let mut dirs = vec![self.root.join("vendor").join(module)];
+        if let (Some(cache), Some(version)) = (&self.go_module_cache, version) {
+            dirs.push(cache.join(format!("{}@{}", escape_go_module(module), version)));
+        }
+        dirs.into_iter().find_map(|dir| {
+            let license_file =
+                fs::read_dir(&dir)
+                    .ok()?
+                    .flatten()
+                    .map(|e| e.path())
+                    .find(|path| {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().to_lowercase())
+                            .is_some_and(|name| {
+                                LICENSE_FILE_PREFIXES.iter().any(|p| name.starts_with(p))
+                            })
+                    })?;
+            let license = identify_text(&fs::read_to_string(&license_file).ok()?)?;
+            Some((license, self.display(&license_file)))
+        })
+    }
+
+    // METADATA dos pacotes instalados num virtualenv do projeto
+    fn pypi(&mut self, name: &str) -> Option<(String, String)> {
+        let root = self.root.clone();
+        let site_packages = self
+            .site_packages
+            .get_or_insert_with(|| find_site_packages(&root));
+        let wanted = dependencies::normalize_python_name(name);
+        let metadata = site_packages.iter().find_map(|dir| {
+            fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .map(|e| e.path())
+                .find(|path| {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    file_name.ends_with(".dist-info")
+                        && file_name.split_once('-').is_some_and(|(package, _)| {
+                            dependencies::normalize_python_name(package) == wanted
+                        })
+                })
+        })?;
+        let metadata = metadata.join("METADATA");
+        let license = python_metadata_license(&fs::read_to_string(&metadata).ok()?)?;
+        Some((license, self.display(&metadata)))
+    }
+
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+fn latest_cached_crate(registry: &Path, name: &str) -> Option<PathBuf> {
+    let prefix = format!("{}-", name);
+    fs::read_dir(registry)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let version = file_name.strip_prefix(&prefix)?;
+            // `tree-sitter-` também casa com `tree-sitter-rust-0.23.0`
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| (dependencies::version_numbers(version), entry.path()))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, path)| path)
+}
+
+fn find_site_packages(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for venv in VIRTUALENV_DIRS {
+        let venv = root.join(venv);
+        // Windows: Lib/site-packages; demais: lib/pythonX.Y/site-packages
+        let windows = venv.join("Lib").join("site-packages");
+        if windows.is_dir() {
+            found.push(windows);
+        }
+        for entry in fs::read_dir(venv.join("lib"))
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let site_packages = entry.path().join("site-packages");
+            if site_packages.is_dir() {
+                found.push(site_packages);
+            }
+        }
+    }
+    found
+}
+
+// License-Expression (PEP 639), o campo License curto ou o classifier de licença
+fn python_metadata_license(metadata: &str) -> Option<String> {
+    let headers = metadata.split("\n\n").next().unwrap_or(metadata);
+    let field = |name: &str| {
+        headers.lines().find_map(|line| {
+            line.strip_prefix(name)
+                .map(str::trim)
+                .filter(|value| !value.is_empty() && *value != "UNKNOWN" && value.len() < 80)
+        })
+    };
+    field("License-Expression:")
+        .or_else(|| field("License:"))
+        .map(normalize_id)
+        .or_else(|| {
+            headers
+                .lines()
+                .filter_map(|line| line.strip_prefix("Classifier: License ::"))
+                .filter_map(|classifier| classifier.rsplit("::").next())
+                .map(|name| normalize_id(name.trim()))
+                .find(|id| !id.is_empty() && id != "OSI Approved")
+        })
+}
+
+// O cache de módulos do Go troca maiúsculas por `!` + minúscula
+fn escape_go_module(module: &str) -> String {
+    module
+        .chars()
+        .flat_map(|c| {
+            if c.is_ascii_uppercase() {
+                vec!['!', c.to_ascii_lowercase()]
+            } else {
+                vec![c]
+            }
+        })
+        .collect()
+}
+
+pub fn to_markdown(report: &LicenseReport) -> String {
+    let mut markdown = String::from("# Relatório de licenças\n\n");
+    markdown.push_str(&format!(
+        "**Licença do projeto:** {}\n\n",
+        report
+            .project
+            .license
+            .as_deref()
+            .unwrap_or("não identificada")
+    ));
+
+    if !report.project.evidence.is_empty() {
+        markdown.push_str("## Evidências\n\n");
+        for evidence in &report.project.evidence {
+            let kind = match evidence.kind {
+                LicenseEvidenceKind::File => "arquivo de licença",
+                LicenseEvidenceKind::Manifest => "manifesto",
+                LicenseEvidenceKind::Header => "cabeçalho SPDX",
+            };
+            if evidence.occurrences > 1 {
+                markdown.push_str(&format!(
+                    "- `{}` e mais {} arquivo(s) ({}): {}\n",
+                    evidence.path,
+                    evidence.occurrences - 1,
+                    kind,
+                    evidence.license
+                ));
+            } else {
+                markdown.push_str(&format!(
+                    "- `{}` ({}): {}\n",
+                    evidence.path, kind, evidence.license
+                ));
+            }
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str("## Política\n\n");
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "—".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+    markdown.push_str(&format!("- Permitidas: {}\n", list(&report.policy.allow)));
+    markdown.push_str(&format!("- Proibidas: {}\n", list(&report.policy.deny)));
+    markdown.push_str(&format!(
+        "- Exceções: {}\n\n",
+        list(&report.policy.exceptions)
+    ));
+
+    let mut by_license: BTreeMap<&str, usize> = BTreeMap::new();
+    for dependency in &report.dependencies {
+        *by_license
+            .entry(dependency.license.as_deref().unwrap_or("desconhecida"))
+            .or_default() += 1;
+    }
+    if !by_license.is_empty() {
+        markdown
+            .push_str("## Licenças das dependências\n\n| Licença | Dependências |\n|---|---|\n");
+        for (license, count) in by_license {
+            markdown.push_str(&format!("| {} | {} |\n", license, count));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str(&format!("## Problemas ({})\n\n", report.issues));
+    let issues: Vec<&DependencyLicense> = report
+        .dependencies
+        .iter()
+        .filter(|d| d.status != LicenseStatus::Allowed)
+        .collect();
+    if issues.is_empty() {
+        markdown.push_str("Nenhuma dependência em desacordo com a política.\n\n");
+    } else {
+        markdown.push_str("| Dependência | Versão | Ecossistema | Licença | Situação | Motivo |\n");
+        markdown.push_str("|---|---|---|---|---|---|\n");
+        for dependency in issues {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                dependency.name,
+                dependency.version.as_deref().unwrap_or("—"),
+                dependency.ecosystem,
+                dependency.license.as_deref().unwrap_or("—"),
+                status_label(dependency.status),
+                dependency.reason.as_deref().unwrap_or("")
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str("## Dependências\n\n");
+    markdown.push_str("| Dependência | Versão | Ecossistema | Licença | Situação | Origem |\n");
+    markdown.push_str("|---|---|---|---|---|---|\n");
+    for dependency in &report.dependencies {
+        markdown.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            dependency.name,
+            dependency.version.as_deref().unwrap_or("—"),
+            dependency.ecosystem,
+            dependency.license.as_deref().unwrap_or("—"),
+            status_label(dependency.status),
+            dependency.source.as_deref().unwrap_or("—")
+        ));
+    }
+    markdown
+}
+
+fn status_label(status: LicenseStatus) -> &'static str {
+    match status {
+        LicenseStatus::Allowed => "permitida",
+        LicenseStatus::Unknown => "desconhecida",
+        LicenseStatus::Unlisted => "fora da lista",
+        LicenseStatus::Incompatible => "incompatível",
+        LicenseStatus::Denied => "proibida",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(expression: &str) -> Vec<Vec<String>> {
+        alternatives(expression)
+    }
+
+    fn dependency(license: Option<&str>, kind: DependencyKind) -> DependencyLicense {
+        DependencyLicense {
+            name: "pacote".to_string(),
+            version: Some("1.0.0".to_string()),
+            ecosystem: dependencies::NPM.to_string(),
+            kind,
+            manifest: "package.json".to_string(),
+            license: license.map(str::to_string),
+            source: None,
+            status: LicenseStatus::Unknown,
+            reason: None,
+        }
+    }
+
+    fn policy(allow: &[&str], deny: &[&str]) -> LicensePolicy {
+        LicensePolicy {
+            allow: allow.iter().map(|id| id.to_string()).collect(),
+            deny: deny.iter().map(|id| id.to_string()).collect(),
+            exceptions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn alternatives_split_or_and_and_drop_exceptions() {
+        assert_eq!(ids("MIT OR Apache-2.0"), [["MIT"], ["Apache-2.0"]]);
+        assert_eq!(ids("MIT/Apache-2.0"), [["MIT"], ["Apache-2.0"]]);
+        assert_eq!(
+            ids("(MIT and BSD-3-Clause) or GPL-3.0"),
+            [vec!["MIT", "BSD-3-Clause"], vec!["GPL-3.0"]]
+        );
+        assert_eq!(ids("GPL-2.0 WITH Classpath-exception-2.0"), [["GPL-2.0"]]);
+        assert_eq!(
+            ids("GPL-2.0 with Classpath-exception-2.0 OR MIT"),
+            [["GPL-2.0"], ["MIT"]]
+        );
+        assert_eq!(ids("The MIT License"), [["MIT"]]);
+        assert!(ids("  ").is_empty());
+    }
+
+    #[test]
+    fn evaluate_takes_best_alternative_and_worst_combined_license() {
+        let none = LicensePolicy::default();
+        let runtime = DependencyKind::Runtime;
+
+        // Licença dupla: basta uma alternativa aceitável
+        let (status, _) = evaluate(&dependency(Some("GPL-3.0 OR MIT"), runtime), &none, false);
+        assert_eq!(status, LicenseStatus::Allowed);
+        // AND: vale a pior
+        let (status, reason) =
+            evaluate(&dependency(Some("MIT AND GPL-3.0"), runtime), &none, false);
+        assert_eq!(status, LicenseStatus::Incompatible);
+        assert!(reason.unwrap().contains("GPL-3.0"));
+
+        let (status, _) = evaluate(&dependency(None, runtime), &none, false);
+        assert_eq!(status, LicenseStatus::Unknown);
+
+        let mut exception = policy(&[], &["MIT"]);
+        exception.exceptions.push("pacote".to_string());
+        let (status, _) = evaluate(&dependency(Some("MIT"), runtime), &exception, false);
+        assert_eq!(status, LicenseStatus::Allowed);
+    }
+
+    #[test]
+    fn check_id_deny_beats_allow_and_copyleft_only_when_distributed() {
+        let runtime = DependencyKind::Runtime;
+        let both = policy(&["GPL-*", "MIT"], &["GPL-3.0"]);
+        assert_eq!(
+            check_id("GPL-3.0", runtime, &both, false).0,
+            LicenseStatus::Denied
+        );
+        // Permitida explicitamente, o copyleft não é apontado
+        assert_eq!(
+            check_id("GPL-2.0", runtime, &both, false).0,
+            LicenseStatus::Allowed
+        );
+        assert_eq!(
+            check_id("ISC", runtime, &both, false).0,
+            LicenseStatus::Unlisted
+        );
+        assert_eq!(
+            check_id("mit", runtime, &both, false).0,
+            LicenseStatus::Allowed
+        );
+        // A exceção em minúsculas não pode esconder a licença da política
+        let (status, _) = evaluate(
+            &dependency(Some("GPL-3.0 with GCC-exception-3.1"), runtime),
+            &both,
+            false,
+        );
+        assert_eq!(status, LicenseStatus::Denied);
+
+        let none = LicensePolicy::default();
+        assert_eq!(
+            check_id("AGPL-3.0", runtime, &none, false).0,
+            LicenseStatus::Incompatible
+        );
+        // Copyleft em dependência de desenvolvimento ou num projeto já copyleft
+        assert_eq!(
+            check_id("AGPL-3.0", DependencyKind::Dev, &none, false).0,
+            LicenseStatus::Allowed
+        );
+        assert_eq!(
+            check_id("GPL-3.0", runtime, &none, true).0,
+            LicenseStatus::Allowed
+        );
+        assert_eq!(
+            check_id("LGPL-3.0", runtime, &none, false).0,
+            LicenseStatus::Allowed
+        );
+    }
+
+    #[test]
+    fn python_metadata_license_fields_in_order() {
+        let metadata =
+            "Metadata-Version: 2.4\nName: pacote\nLicense-Expression: MIT OR Apache-2.0\n\
+                        License: BSD\nClassifier: License :: OSI Approved :: MIT License\n\n\
+                        License: corpo da descrição";
+        assert_eq!(
+            python_metadata_license(metadata).as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            python_metadata_license(
+                "Name: pacote\nLicense: UNKNOWN\n\
+                                     Classifier: License :: OSI Approved :: MIT License\n"
+            )
+            .as_deref(),
+            Some("MIT")
+        );
+        assert_eq!(
+            python_metadata_license("Name: pacote\nClassifier: License :: OSI Approved\n"),
+            None
+        );
+        // Cabeçalhos acabam na primeira linha em branco
+        assert_eq!(
+            python_metadata_license("Name: pacote\n\nLicense: MIT\n"),
+            None
+        );
+    }
+}
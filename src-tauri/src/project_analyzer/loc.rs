@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::languages::Language;
+use crate::types::{DirectoryStats, LanguageStats};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+}
+
+impl LineCounts {
+    pub fn lines(&self) -> usize {
+        self.code + self.comments + self.blanks
+    }
+
+    pub fn add(&mut self, other: &LineCounts) {
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+    }
+}
+
+// Conta linhas de código, comentário e em branco. Uma linha com código e comentário
+// conta como código; strings e comentários de bloco podem atravessar várias linhas.
+pub fn count_lines(language: &Language, content: &str) -> LineCounts {
+    let mut counts = LineCounts::default();
+    let mut block_close: Option<&str> = None;
+    let mut block_depth = 0usize;
+    let mut string_close: Option<&str> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() && string_close.is_none() {
+            counts.blanks += 1;
+            continue;
+        }
+
+        let mut has_code = string_close.is_some();
+        let mut has_comment = block_close.is_some();
+        let mut index = 0;
+
+        while index < line.len() {
+            let rest = &line[index..];
+
+            if let Some(close) = string_close {
+                if let Some(escaped) = rest.strip_prefix('\\') {
+                    index += 1 + next_char_len(escaped);
+                    continue;
+                }
+                if rest.starts_with(close) {
+                    string_close = None;
+                    index += close.len();
+                    continue;
+                }
+            } else if let Some(close) = block_close {
+                has_comment = true;
+                if language.nested_comments {
+                    if let Some((open, _)) = language
+                        .block_comments
+                        .iter()
+                        .find(|(open, c)| *c == close && rest.starts_with(open))
+                    {
+                        block_depth += 1;
+                        index += open.len();
+                        continue;
+                    }
+                }
+                if rest.starts_with(close) {
+                    block_depth -= 1;
+                    if block_depth == 0 {
+                        block_close = None;
+                    }
+                    index += close.len();
+                    continue;
+                }
+            } else if rest.starts_with(char::is_whitespace) {
+                // espaço fora de string/comentário não conta
+            } else if let Some((open, close)) = language
+                .block_comments
+                .iter()
+                .find(|(open, _)| rest.starts_with(open))
+            {
+                has_comment = true;
+                block_close = Some(close);
+                block_depth = 1;
+                index += open.len();
+                continue;
+            } else if language.line_comments.iter().any(|c| rest.starts_with(c)) {
+                has_comment = true;
+                break;
+            } else if let Some((open, close)) = language
+                .quotes
+                .iter()
+                .find(|(open, _)| rest.starts_with(open))
+            {
+                has_code = true;
+                string_close = Some(close);
+                index += open.len();
+                continue;
+            } else {
+                has_code = true;
+            }
+
+            index += next_char_len(rest);
+        }
+
+        // Strings simples não atravessam linhas na maioria das linguagens
+        if let Some(close) = string_close {
+            if !language.multiline_strings && (close == "\"" || close == "'") {
+                string_close = None;
+            }
+        }
+
+        if has_code {
+            counts.code += 1;
+        } else if has_comment {
+            counts.comments += 1;
+        } else {
+            counts.blanks += 1;
+        }
+    }
+
+    counts
+}
+
+fn next_char_len(text: &str) -> usize {
+    text.chars().next().map(|c| c.len_utf8()).unwrap_or(1)
+}
+
+pub struct FileLines {
+    pub relative_path: PathBuf,
+    pub language: &'static Language,
+    pub counts: LineCounts,
+}
+
+pub fn language_stats<'a>(files: impl IntoIterator<Item = &'a FileLines>) -> Vec<LanguageStats> {
+    let mut by_language: HashMap<&str, (usize, LineCounts)> = HashMap::new();
+    for file in files {
+        let entry = by_language.entry(file.language.name).or_default();
+        entry.0 += 1;
+        entry.1.add(&file.counts);
+    }
+
+    let mut stats: Vec<LanguageStats> = by_language
+        .into_iter()
+        .map(|(language, (files, counts))| LanguageStats {
+            language: language.to_string(),
+            files,
+            code: counts.code,
+            comments: counts.comments,
+            blanks: counts.blanks,
+            lines: counts.lines(),
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.code
+            .cmp(&a.code)
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    stats
+}
+
+// Soma cada arquivo em todos os diretórios acima dele, até a raiz
+pub fn directory_stats(files: &[FileLines]) -> Vec<DirectoryStats> {
+    let mut by_directory: HashMap<String, (usize, LineCounts)> = HashMap::new();
+    for file in files {
+        for directory in file.relative_path.ancestors().skip(1) {
+            let key = if directory.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                directory.to_string_lossy().replace('\\', "/")
+            };
+            let entry = by_directory.entry(key).or_default();
+            entry.0 += 1;
+            entry.1.add(&file.counts);
+        }
+    }
+
+    let mut stats: Vec<DirectoryStats> = by_directory
+        .into_iter()
+        .map(|(path, (files, counts))| DirectoryStats {
+            path,
+            files,
+            code: counts.code,
+            comments: counts.comments,
+            blanks: counts.blanks,
+            lines: counts.lines(),
+        })
+        .collect();
+    stats.sort_by(|a, b| a.path.cmp(&b.path));
+    stats
+}
@@ -0,0 +1,413 @@
+use chrono::DateTime;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::git_history;
+use super::languages::{self, Language};
+use crate::types::{BlameInfo, DebtMarker, MarkerCount, MarkerFilter, MarkerKind, TechDebtReport};
+
+const MAX_FILE_SIZE: u64 = 500_000;
+// Linhas mostradas antes e depois do marcador
+const CONTEXT_LINES: usize = 2;
+const COMMENT_MARKERS: &[(&str, MarkerKind)] = &[
+    ("TODO", MarkerKind::Todo),
+    ("FIXME", MarkerKind::Fixme),
+    ("HACK", MarkerKind::Hack),
+    ("XXX", MarkerKind::Xxx),
+];
+// Macros do Rust que marcam código ainda não implementado
+const PLACEHOLDER_MACROS: &[&str] = &["todo", "unimplemented"];
+// O que pode vir antes do marcador no início do comentário (`// `, ` * `, `# `, `<!-- `)
+const COMMENT_PREFIX: &[char] = &[' ', '\t', '/', '*', '#', '-', '!', '<', ';'];
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+// Marcadores de dívida técnica em comentários (não em strings nem em palavras como
+// `TODOS`) e macros de código pendente do Rust, com autor e data do git blame
+pub fn collect(root: &Path, files: &[(PathBuf, u64)], filter: &MarkerFilter) -> TechDebtReport {
+    let path_filter = filter.path.as_ref().map(|p| p.replace('\\', "/"));
+    let mut markers: Vec<DebtMarker> = files
+        .par_iter()
+        .filter(|(_, size)| *size < MAX_FILE_SIZE)
+        .filter_map(|(path, _)| {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if path_filter
+                .as_ref()
+                .is_some_and(|filter| !relative.contains(filter.as_str()))
+            {
+                return None;
+            }
+            let content = fs::read_to_string(path).ok()?;
+            let language = languages::detect(Path::new(&relative), content.lines().next())?;
+            let mut found: Vec<DebtMarker> = scan(language, &content)
+                .into_iter()
+                .filter(|(_, kind, _)| filter.kinds.is_empty() || filter.kinds.contains(kind))
+                .map(|(line, kind, text)| marker(&relative, &content, line, kind, text))
+                .collect();
+            if found.is_empty() {
+                return None;
+            }
+
+            let blame = blame(root, &relative);
+            for marker in &mut found {
+                marker.blame = blame.get(&marker.line).cloned();
+            }
+            Some(found)
+        })
+        .flatten()
+        .collect();
+
+    if let Some(author) = filter.author.as_ref().map(|a| a.to_lowercase()) {
+        markers.retain(|marker| {
+            marker.blame.as_ref().is_some_and(|blame| {
+                blame.author.to_lowercase().contains(&author)
+                    || blame.email.to_lowercase().contains(&author)
+            })
+        });
+    }
+    markers.sort_by(|a, b| a.file.cmp(&b.file).then_with(|| a.line.cmp(&b.line)));
+
+    TechDebtReport {
+        total: markers.len(),
+        by_kind: count_by(&markers, |m| Some(kind_label(m.kind).to_string())),
+        by_file: count_by(&markers, |m| Some(m.file.clone())),
+        by_author: count_by(&markers, |m| m.blame.as_ref().map(|b| b.author.clone())),
+        markers,
+    }
+}
+
+// Marcadores do arquivo como (linha, tipo, texto), percorrendo comentários e strings
+// com a sintaxe da linguagem
+fn scan(language: &Language, content: &str) -> Vec<(usize, MarkerKind, String)> {
+    let rust = language.name == "Rust";
+    let mut found = Vec::new();
+    let mut line = 1;
+    let mut index = 0;
+
+    while index < content.len() {
+        let rest = &content[index..];
+        let c = rest.chars().next().unwrap_or(' ');
+
+        if let Some((open, close)) = language
+            .block_comments
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            let end = languages::block_comment_end(language, rest, open, close);
+            for (offset, comment_line) in rest[..end].lines().enumerate() {
+                if let Some((kind, text)) = comment_marker(comment_line) {
+                    found.push((line + offset, kind, text));
+                }
+            }
+            line += rest[..end].matches('\n').count();
+            index += end;
+            continue;
+        }
+
+        if language.line_comments.iter().any(|c| rest.starts_with(c)) {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            if let Some((kind, text)) = comment_marker(&rest[..end]) {
+                found.push((line, kind, text));
+            }
+            index += end;
+            continue;
+        }
+
+        if let Some((open, close)) = language
+            .quotes
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            let end = languages::string_end(language, rest, open, close);
+            line += rest[..end].matches('\n').count();
+            index += end;
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            if rust && PLACEHOLDER_MACROS.contains(&word) && rest[end..].starts_with('!') {
+                let text = rest[end + 1..].lines().next().unwrap_or_default();
+                found.push((line, MarkerKind::Unimplemented, macro_message(word, text)));
+            }
+            index += end;
+            continue;
+        }
+
+        if c == '\n' {
+            line += 1;
+        }
+        index += c.len_utf8();
+    }
+
+    found
+}
+
+// Primeiro marcador da linha de comentário: palavra inteira em maiúsculas no início do
+// comentário ou seguida de `:`/`(`, para não pegar menções no meio do texto
+fn comment_marker(comment: &str) -> Option<(MarkerKind, String)> {
+    COMMENT_MARKERS
+        .iter()
+        .filter_map(|(word, kind)| {
+            let position = comment.match_indices(word).map(|(i, _)| i).find(|&i| {
+                let before = &comment[..i];
+                let after = &comment[i + word.len()..];
+                if before.chars().next_back().is_some_and(is_word_char)
+                    || after.chars().next().is_some_and(is_word_char)
+                {
+                    return false;
+                }
+                before.trim_start_matches(COMMENT_PREFIX).is_empty()
+                    || after.starts_with([':', '('])
+            })?;
+            Some((position, *kind, &comment[position + word.len()..]))
+        })
+        .min_by_key(|(position, _, _)| *position)
+        .map(|(_, kind, text)| (kind, clean_text(text)))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Texto depois do marcador, sem a pontuação inicial nem o fechamento do comentário
+fn clean_text(text: &str) -> String {
+    let text = text.trim();
+    let text = text
+        .strip_suffix("*/")
+        .or_else(|| text.strip_suffix("-->"))
+        .unwrap_or(text);
+    text.trim_start_matches([':', '-']).trim().to_string()
+}
+
+// `todo!("texto")` -> `todo!: texto`; sem mensagem fica só o nome da macro
+fn macro_message(name: &str, arguments: &str) -> String {
+    let message = arguments
+        .split('"')
+        .nth(1)
+        .filter(|message| !message.is_empty());
+    match message {
+        Some(message) => format!("{}!: {}", name, message),
+        None => format!("{}!", name),
+    }
+}
+
+fn marker(file: &str, content: &str, line: usize, kind: MarkerKind, text: String) -> DebtMarker {
+    let context_start = line.saturating_sub(CONTEXT_LINES).max(1);
+    let context = content
+        .lines()
+        .skip(context_start - 1)
+        .take(line + CONTEXT_LINES + 1 - context_start)
+        .map(|l| l.trim_end().to_string())
+        .collect();
+    DebtMarker {
+        kind,
+        file: file.to_string(),
+        line,
+        text,
+        context_start,
+        context,
+        blame: None,
+    }
+}
+
+// Autor e data de cada linha commitada do arquivo; vazio fora de um repositório git
+fn blame(root: &Path, relative: &str) -> HashMap<usize, BlameInfo> {
+    let Ok(output) = git_history::git(root, &["blame", "--line-porcelain", "--", relative]) else {
+        return HashMap::new();
+    };
+
+    let mut lines = HashMap::new();
+    let mut commit = "";
+    let mut final_line = 0;
+    let mut author = "";
+    let mut email = "";
+    let mut time = 0;
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("author ") {
+            author = name;
+        } else if let Some(mail) = line.strip_prefix("author-mail ") {
+            email = mail.trim_start_matches('<').trim_end_matches('>');
+        } else if let Some(timestamp) = line.strip_prefix("author-time ") {
+            time = timestamp.parse().unwrap_or_default();
+        } else if line.starts_with('\t') {
+            if commit != UNCOMMITTED {
+                lines.insert(
+                    final_line,
+                    BlameInfo {
+                        author: author.to_string(),
+                        email: email.to_string(),
+                        date: DateTime::from_timestamp(time, 0)
+                            .map(|date| date.to_rfc3339())
+                            .unwrap_or_default(),
+                        commit: commit.to_string(),
+                    },
+                );
+            }
+        } else {
+            // Cabeçalho: <commit> <linha original> <linha final> [<linhas no grupo>]
+            let mut parts = line.split(' ');
+            if let (Some(hash), Some(_), Some(number)) = (parts.next(), parts.next(), parts.next())
+            {
+                if hash.len() == UNCOMMITTED.len() && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    commit = hash;
+                    final_line = number.parse().unwrap_or_default();
+                }
+            }
+        }
+    }
+    lines
+}
+
+fn count_by(
+    markers: &[DebtMarker],
+    key: impl Fn(&DebtMarker) -> Option<String>,
+) -> Vec<MarkerCount> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for marker in markers {
+        if let Some(key) = key(marker) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<MarkerCount> = counts
+        .into_iter()
+        .map(|(key, count)| MarkerCount { key, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    counts
+}
+
+fn kind_label(kind: MarkerKind) -> &'static str {
+    match kind {
+        MarkerKind::Todo => "todo",
+        MarkerKind::Fixme => "fixme",
+        MarkerKind::Hack => "hack",
+        MarkerKind::Xxx => "xxx",
+        MarkerKind::Unimplemented => "unimplemented",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Placeholder no estilo do connect_github de commands.rs, com menções que não são
+    // marcadores (string, palavra maior) e as macros de código pendente
+    const COMMANDS: &str = r#"#[tauri::command]
+pub async fn connect_github(token: String) -> Result<bool, String> {
+    // TODO: Implement GitHub API integration
+    // For now, just validate the token format
+    if token.starts_with("ghp_") || token.starts_with("github_pat_") {
+        Ok(true)
+    } else {
+        Err("TODO: token inválido".to_string())
+    }
+}
+
+/* FIXME(ana) - tratar paginação */
+pub fn repositories() -> Vec<String> {
+    // Lista de TODOS os repositórios
+    todo!("listar repositórios")
+}
+
+pub fn issues() {
+    unimplemented!()
+}
+"#;
+
+    fn rust() -> &'static Language {
+        languages::by_name("Rust").unwrap()
+    }
+
+    #[test]
+    fn finds_connect_github_todo_and_placeholder_macros() {
+        let found = scan(rust(), COMMANDS);
+        assert_eq!(
+            found,
+            [
+                (
+                    3,
+                    MarkerKind::Todo,
+                    "Implement GitHub API integration".to_string()
+                ),
+                (
+                    12,
+                    MarkerKind::Fixme,
+                    "(ana) - tratar paginação".to_string()
+                ),
+                (
+                    15,
+                    MarkerKind::Unimplemented,
+                    "todo!: listar repositórios".to_string()
+                ),
+                (19, MarkerKind::Unimplemented, "unimplemented!".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn marker_keeps_surrounding_context() {
+        let marker = marker(
+            "src/commands.rs",
+            COMMANDS,
+            3,
+            MarkerKind::Todo,
+            "Implement GitHub API integration".to_string(),
+        );
+        assert_eq!(marker.context_start, 1);
+        assert_eq!(
+            marker.context,
+            [
+                "#[tauri::command]",
+                "pub async fn connect_github(token: String) -> Result<bool, String> {",
+                "    // TODO: Implement GitHub API integration",
+                "    // For now, just validate the token format",
+                "    if token.starts_with(\"ghp_\") || token.starts_with(\"github_pat_\") {",
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_filters_by_kind_and_path() {
+        let root = std::env::temp_dir().join(format!(
+            "rzonehub-markers-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(root.join("src")).unwrap();
+        let commands = root.join("src").join("commands.rs");
+        let script = root.join("build.py");
+        fs::write(&commands, COMMANDS).unwrap();
+        fs::write(&script, "# XXX: remover antes do release\nprint('TODO')\n").unwrap();
+        let files = vec![(commands, COMMANDS.len() as u64), (script, 40)];
+
+        let all = collect(&root, &files, &MarkerFilter::default());
+        assert_eq!(all.total, 5);
+        assert_eq!(all.markers[0].file, "build.py");
+        assert_eq!(all.markers[0].kind, MarkerKind::Xxx);
+
+        let todos = collect(
+            &root,
+            &files,
+            &MarkerFilter {
+                kinds: vec![MarkerKind::Todo],
+                path: Some("src/".to_string()),
+                author: None,
+            },
+        );
+        assert_eq!(todos.total, 1);
+        assert_eq!(todos.markers[0].line, 3);
+        // Fora de um repositório git não há blame
+        assert!(todos.markers[0].blame.is_none());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,1261 @@
+use rayon::prelude::*;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tree_sitter::Node;
+
+use super::syntax::Grammar;
+use super::MAX_FILE_SIZE;
+use crate::types::{ModuleEdge, ModuleGraph, ModuleNode};
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+const VITE_CONFIGS: &[&str] = &[
+    "vite.config.ts",
+    "vite.config.js",
+    "vite.config.mts",
+    "vite.config.mjs",
+    "vite.config.cts",
+    "vite.config.cjs",
+];
+const RUST_STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+enum Import {
+    RustMod(String),
+    RustPath(Vec<String>),
+    Script(String),
+    Python {
+        level: usize,
+        module: Vec<String>,
+        names: Vec<String>,
+    },
+    Go(String),
+}
+
+struct SourceFile {
+    relative: String,
+    grammar: Grammar,
+    imports: Vec<Import>,
+}
+
+// Onde um arquivo Rust fica na árvore de módulos do crate
+struct RustModule {
+    tree: String,
+    segments: Vec<String>,
+    children_dir: String,
+}
+
+// Resolução de imports não relativos de TS/JS: `paths`/`baseUrl` do tsconfig e `resolve.alias` do Vite
+#[derive(Default)]
+struct ScriptConfig {
+    dir: String,
+    base_url: Option<String>,
+    paths: Vec<(String, Vec<String>)>,
+    aliases: Vec<(String, String)>,
+}
+
+struct Workspace<'a> {
+    root: &'a Path,
+    files: HashSet<&'a str>,
+    rust_crates: HashMap<String, String>,
+    rust_crate_dirs: Vec<String>,
+    script_configs: Vec<ScriptConfig>,
+    python_roots: Vec<String>,
+    go_modules: Vec<(String, String)>,
+}
+
+pub fn build(root: &Path, files: &[(PathBuf, u64)]) -> ModuleGraph {
+    let relative_paths: Vec<String> = files
+        .iter()
+        .filter_map(|(path, _)| path.strip_prefix(root).ok())
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .collect();
+
+    let sources: Vec<SourceFile> = files
+        .par_iter()
+        .filter(|(_, size)| *size < MAX_FILE_SIZE)
+        .filter_map(|(path, _)| {
+            let relative = path.strip_prefix(root).ok()?;
+            let grammar = Grammar::detect(relative)?;
+            let content = fs::read_to_string(path).ok()?;
+            let tree = grammar.parse(&content)?;
+            Some(SourceFile {
+                relative: relative.to_string_lossy().replace('\\', "/"),
+                grammar,
+                imports: extract_imports(grammar, tree.root_node(), content.as_bytes()),
+            })
+        })
+        .collect();
+
+    let workspace = Workspace::new(root, &relative_paths);
+
+    // Go é agrupado por pacote (diretório); as outras linguagens por arquivo
+    let node_id = |relative: &str, grammar: Grammar| match grammar {
+        Grammar::Go => package_id(parent(relative)),
+        _ => relative.to_string(),
+    };
+    let nodes: BTreeMap<String, &str> = sources
+        .iter()
+        .map(|source| {
+            (
+                node_id(&source.relative, source.grammar),
+                source.grammar.name(),
+            )
+        })
+        .collect();
+
+    let mut edges: BTreeSet<(String, String)> = BTreeSet::new();
+    for source in &sources {
+        let from = node_id(&source.relative, source.grammar);
+        for import in &source.imports {
+            for target in workspace.resolve(source, import) {
+                if target != from && nodes.contains_key(&target) {
+                    edges.insert((from.clone(), target));
+                }
+            }
+        }
+    }
+
+    let mut fan_in: HashMap<&str, usize> = HashMap::new();
+    let mut fan_out: HashMap<&str, usize> = HashMap::new();
+    for (from, to) in &edges {
+        *fan_out.entry(from).or_default() += 1;
+        *fan_in.entry(to).or_default() += 1;
+    }
+
+    let ids: Vec<&String> = nodes.keys().collect();
+    let positions: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (id.as_str(), index))
+        .collect();
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    for (from, to) in &edges {
+        adjacency[positions[from.as_str()]].push(positions[to.as_str()]);
+    }
+    let mut cycles: Vec<Vec<String>> = strongly_connected(&adjacency)
+        .into_iter()
+        .map(|component| {
+            let mut members: Vec<String> = component.into_iter().map(|i| ids[i].clone()).collect();
+            members.sort();
+            members
+        })
+        .collect();
+    cycles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    ModuleGraph {
+        nodes: nodes
+            .iter()
+            .map(|(id, language)| ModuleNode {
+                id: id.clone(),
+                language: language.to_string(),
+                fan_in: fan_in.get(id.as_str()).copied().unwrap_or(0),
+                fan_out: fan_out.get(id.as_str()).copied().unwrap_or(0),
+            })
+            .collect(),
+        edges: edges
+            .into_iter()
+            .map(|(from, to)| ModuleEdge { from, to })
+            .collect(),
+        cycles,
+    }
+}
+
+fn extract_imports(grammar: Grammar, root: Node, source: &[u8]) -> Vec<Import> {
+    let text = |node: Node| node.utf8_text(source).unwrap_or_default().to_string();
+    let mut imports = Vec::new();
+    let mut stack = vec![root];
+
+    while let Some(node) = stack.pop() {
+        let mut descend = true;
+
+        match (grammar, node.kind()) {
+            (Grammar::Rust, "mod_item") if node.child_by_field_name("body").is_none() => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    imports.push(Import::RustMod(text(name)));
+                }
+            }
+            (Grammar::Rust, "use_declaration") => {
+                if let Some(argument) = node.child_by_field_name("argument") {
+                    imports.extend(
+                        expand_use_tree(&text(argument))
+                            .into_iter()
+                            .map(Import::RustPath),
+                    );
+                }
+                descend = false;
+            }
+            // Caminhos absolutos usados direto no código (`crate::types::Foo`)
+            (Grammar::Rust, "scoped_identifier" | "scoped_type_identifier") => {
+                let path: String = text(node)
+                    .split('<')
+                    .next()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect();
+                if ["crate::", "super::", "self::"]
+                    .iter()
+                    .any(|prefix| path.starts_with(prefix))
+                {
+                    imports.push(Import::RustPath(
+                        path.split("::").map(str::to_string).collect(),
+                    ));
+                }
+                descend = false;
+            }
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "import_statement" | "export_statement" | "import_require_clause",
+            ) => {
+                if let Some(specifier) = node
+                    .child_by_field_name("source")
+                    .and_then(|s| string_value(s, source))
+                {
+                    imports.push(Import::Script(specifier));
+                }
+            }
+            // require('x') e import('x')
+            (Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript, "call_expression") => {
+                let is_import = node
+                    .child_by_field_name("function")
+                    .is_some_and(|function| {
+                        function.kind() == "import"
+                            || (function.kind() == "identifier" && text(function) == "require")
+                    });
+                let specifier = node
+                    .child_by_field_name("arguments")
+                    .and_then(|arguments| arguments.named_child(0))
+                    .filter(|argument| argument.kind() == "string")
+                    .and_then(|argument| string_value(argument, source));
+                if let (true, Some(specifier)) = (is_import, specifier) {
+                    imports.push(Import::Script(specifier));
+                }
+            }
+            (Grammar::Python, "import_statement") => {
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    let module = name.child_by_field_name("name").unwrap_or(name);
+                    imports.push(Import::Python {
+                        level: 0,
+                        module: text(module).split('.').map(str::to_string).collect(),
+                        names: Vec::new(),
+                    });
+                }
+                descend = false;
+            }
+            (Grammar::Python, "import_from_statement") => {
+                if let Some(module_name) = node.child_by_field_name("module_name") {
+                    let module_text = text(module_name);
+                    let level = module_text.chars().take_while(|c| *c == '.').count();
+                    let module = module_text[level..]
+                        .split('.')
+                        .filter(|segment| !segment.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    let mut cursor = node.walk();
+                    let names = node
+                        .children_by_field_name("name", &mut cursor)
+                        .map(|name| text(name.child_by_field_name("name").unwrap_or(name)))
+                        .collect();
+                    imports.push(Import::Python {
+                        level,
+                        module,
+                        names,
+                    });
+                }
+                descend = false;
+            }
+            (Grammar::Go, "import_spec") => {
+                if let Some(path) = node.child_by_field_name("path") {
+                    imports.push(Import::Go(
+                        text(path)
+                            .trim_matches(|c| c == '"' || c == '`')
+                            .to_string(),
+                    ));
+                }
+                descend = false;
+            }
+            _ => {}
+        }
+
+        if descend {
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+    }
+
+    imports
+}
+
+fn string_value(node: Node, source: &[u8]) -> Option<String> {
+    let text = node.utf8_text(source).ok()?;
+    let value = text.get(1..text.len().checked_sub(1)?)?;
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// `a::{b, c::{d as e, self}, f::*}` vira [a, b], [a, c, d], [a, c], [a, f]
+fn expand_use_tree(tree: &str) -> Vec<Vec<String>> {
+    static ALIAS: OnceLock<Regex> = OnceLock::new();
+    let alias = ALIAS.get_or_init(|| Regex::new(r"\s+as\s+[A-Za-z_][A-Za-z0-9_]*").unwrap());
+    let compact: String = alias.replace_all(tree, "").split_whitespace().collect();
+
+    let mut paths = Vec::new();
+    expand_use_part(compact.trim_start_matches("::"), &[], &mut paths);
+    paths
+}
+
+fn expand_use_part(part: &str, prefix: &[String], paths: &mut Vec<Vec<String>>) {
+    // Vírgula no fim do grupo (`{a, b,}`) não importa o próprio prefixo
+    if part.is_empty() {
+        return;
+    }
+    let mut segments = prefix.to_vec();
+
+    if let (Some(open), Some(close)) = (part.find('{'), part.rfind('}')) {
+        segments.extend(path_segments(&part[..open]));
+        let mut depth = 0;
+        let mut start = open + 1;
+        for (index, c) in part[..close].char_indices().skip_while(|(i, _)| *i <= open) {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    expand_use_part(&part[start..index], &segments, paths);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        expand_use_part(&part[start..close], &segments, paths);
+        return;
+    }
+
+    segments.extend(path_segments(part));
+    if !segments.is_empty() {
+        paths.push(segments);
+    }
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = String> + '_ {
+    path.split("::")
+        .filter(|segment| !segment.is_empty() && *segment != "*" && *segment != "self")
+        .map(str::to_string)
+}
+
+impl<'a> Workspace<'a> {
+    fn new(root: &'a Path, relative_paths: &'a [String]) -> Self {
+        let mut workspace = Workspace {
+            root,
+            files: relative_paths.iter().map(String::as_str).collect(),
+            rust_crates: HashMap::new(),
+            rust_crate_dirs: Vec::new(),
+            script_configs: Vec::new(),
+            python_roots: vec![String::new(), "src".to_string()],
+            go_modules: Vec::new(),
+        };
+
+        for relative in relative_paths {
+            let dir = parent(relative).to_string();
+            match file_name(relative) {
+                "Cargo.toml" => workspace.add_rust_crate(relative, dir),
+                "go.mod" => {
+                    let module = workspace.read(relative).and_then(|content| {
+                        content.lines().find_map(|line| {
+                            line.trim()
+                                .strip_prefix("module ")
+                                .map(|m| m.trim().trim_matches('"').to_string())
+                        })
+                    });
+                    if let Some(module) = module {
+                        workspace.go_modules.push((module, dir));
+                    }
+                }
+                "pyproject.toml" | "setup.py" | "setup.cfg" => {
+                    workspace.python_roots.push(join(&dir, "src"));
+                    workspace.python_roots.push(dir);
+                }
+                name if (name.starts_with("tsconfig") && name.ends_with(".json"))
+                    || name == "jsconfig.json" =>
+                {
+                    let config = workspace.load_tsconfig(relative, 0);
+                    let scope = workspace.script_config(&dir);
+                    scope.base_url = scope.base_url.take().or(config.base_url);
+                    scope.paths.extend(config.paths);
+                }
+                name if VITE_CONFIGS.contains(&name) => {
+                    let aliases = workspace
+                        .read(relative)
+                        .map(|content| vite_aliases(&content, &dir))
+                        .unwrap_or_default();
+                    workspace.script_config(&dir).aliases.extend(aliases);
+                }
+                _ => {}
+            }
+        }
+
+        workspace.python_roots.sort();
+        workspace.python_roots.dedup();
+        // Configurações mais específicas (diretório mais fundo) primeiro
+        workspace
+            .script_configs
+            .sort_by_key(|config| Reverse(config.dir.len()));
+        workspace
+            .rust_crate_dirs
+            .sort_by_key(|dir| Reverse(dir.len()));
+        workspace
+            .go_modules
+            .sort_by_key(|(module, _)| Reverse(module.len()));
+        workspace
+    }
+
+    fn read(&self, relative: &str) -> Option<String> {
+        fs::read_to_string(self.root.join(relative)).ok()
+    }
+
+    fn exists(&self, relative: &str) -> bool {
+        self.files.contains(relative)
+    }
+
+    fn resolve(&self, source: &SourceFile, import: &Import) -> Vec<String> {
+        match import {
+            Import::RustMod(name) => {
+                let module = self.rust_module(&source.relative);
+                let base = join(&module.children_dir, name);
+                [format!("{}.rs", base), format!("{}/mod.rs", base)]
+                    .into_iter()
+                    .filter(|candidate| self.exists(candidate))
+                    .take(1)
+                    .collect()
+            }
+            Import::RustPath(path) => self
+                .resolve_rust_path(&source.relative, path)
+                .into_iter()
+                .collect(),
+            Import::Script(specifier) => self
+                .resolve_script(&source.relative, specifier)
+                .into_iter()
+                .collect(),
+            Import::Python {
+                level,
+                module,
+                names,
+            } => self.resolve_python(&source.relative, *level, module, names),
+            Import::Go(path) => self.resolve_go(path).into_iter().collect(),
+        }
+    }
+
+    fn add_rust_crate(&mut self, manifest: &str, dir: String) {
+        let Some(value) = self
+            .read(manifest)
+            .and_then(|content| content.parse::<toml::Value>().ok())
+        else {
+            return;
+        };
+        let Some(package) = value
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        else {
+            return;
+        };
+        let lib_name = value
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(|name| name.as_str())
+            .unwrap_or(package)
+            .replace('-', "_");
+        self.rust_crates.insert(lib_name, join(&dir, "src"));
+        self.rust_crate_dirs.push(dir);
+    }
+
+    fn rust_module(&self, relative: &str) -> RustModule {
+        let crate_dir = self
+            .rust_crate_dirs
+            .iter()
+            .find(|dir| is_within(relative, dir))
+            .map(String::as_str)
+            .unwrap_or_else(|| parent(relative));
+        let inside = relative
+            .strip_prefix(crate_dir)
+            .unwrap_or(relative)
+            .trim_start_matches('/');
+        let parts: Vec<&str> = inside.split('/').collect();
+
+        // src/ é uma árvore só (lib.rs/main.rs na raiz); src/bin, tests, examples e benches
+        // têm um crate por arquivo (ou por subpasta com main.rs)
+        let tree_depth = match parts.as_slice() {
+            ["src", "bin", _, _, ..] => 3,
+            ["src", "bin", ..] => 2,
+            ["src", ..] => 1,
+            ["tests" | "examples" | "benches", _, _, ..] => 2,
+            ["tests" | "examples" | "benches", ..] => 1,
+            _ => parts.len() - 1,
+        };
+        let tree = join(crate_dir, &parts[..tree_depth].join("/"));
+        let mut segments: Vec<String> = parts[tree_depth..].iter().map(|s| s.to_string()).collect();
+        let stem = segments.pop().unwrap_or_default();
+        let stem = stem.trim_end_matches(".rs");
+
+        let is_crate_root = segments.is_empty()
+            && (tree_depth != 1 || parts[0] != "src" || stem == "lib" || stem == "main");
+        let is_mod_root = is_crate_root || stem == "mod";
+        if !is_mod_root {
+            segments.push(stem.to_string());
+        }
+
+        RustModule {
+            children_dir: if is_mod_root {
+                parent(relative).to_string()
+            } else {
+                relative.trim_end_matches(".rs").to_string()
+            },
+            tree,
+            segments,
+        }
+    }
+
+    fn resolve_rust_path(&self, from: &str, path: &[String]) -> Option<String> {
+        let (first, rest) = path.split_first()?;
+        let module = self.rust_module(from);
+
+        match first.as_str() {
+            "crate" => self.rust_module_file(&module.tree, rest, 0),
+            "self" => self.rust_module_file(
+                &module.tree,
+                &[module.segments.as_slice(), rest].concat(),
+                0,
+            ),
+            "super" => {
+                let supers = path
+                    .iter()
+                    .take_while(|segment| *segment == "super")
+                    .count();
+                let base = &module.segments[..module.segments.len().saturating_sub(supers)];
+                self.rust_module_file(&module.tree, &[base, &path[supers..]].concat(), 0)
+            }
+            name if RUST_STD_CRATES.contains(&name) => None,
+            name if self.rust_crates.contains_key(name) => {
+                self.rust_module_file(&self.rust_crates[name], rest, 0)
+            }
+            // `use foo::Bar` com `mod foo;` declarado no módulo atual
+            _ => {
+                let segments = [module.segments.as_slice(), path].concat();
+                self.rust_module_file(&module.tree, &segments, module.segments.len() + 1)
+            }
+        }
+    }
+
+    // O caminho pode terminar em itens (`crate::types::Foo`): vale o prefixo mais longo que é módulo
+    fn rust_module_file(
+        &self,
+        tree: &str,
+        segments: &[String],
+        min_depth: usize,
+    ) -> Option<String> {
+        for depth in (min_depth.max(1)..=segments.len()).rev() {
+            let base = join(tree, &segments[..depth].join("/"));
+            for candidate in [format!("{}.rs", base), format!("{}/mod.rs", base)] {
+                if self.exists(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+        if min_depth > 0 {
+            return None;
+        }
+        ["lib.rs", "main.rs"]
+            .iter()
+            .map(|root| join(tree, root))
+            .find(|candidate| self.exists(candidate))
+    }
+
+    fn script_config(&mut self, dir: &str) -> &mut ScriptConfig {
+        let index = match self
+            .script_configs
+            .iter()
+            .position(|config| config.dir == dir)
+        {
+            Some(index) => index,
+            None => {
+                self.script_configs.push(ScriptConfig {
+                    dir: dir.to_string(),
+                    ..Default::default()
+                });
+                self.script_configs.len() - 1
+            }
+        };
+        &mut self.script_configs[index]
+    }
+
+    // Lê `baseUrl` e `paths` seguindo `extends` relativos; os caminhos já saem relativos à raiz
+    fn load_tsconfig(&self, relative: &str, depth: usize) -> ScriptConfig {
+        let dir = parent(relative);
+        let Some(json) = self
+            .read(relative)
+            .and_then(|content| parse_jsonc(&content))
+        else {
+            return ScriptConfig::default();
+        };
+
+        let mut config = ScriptConfig::default();
+        let extends: Vec<&str> = match json.get("extends") {
+            Some(serde_json::Value::String(base)) => vec![base.as_str()],
+            Some(serde_json::Value::Array(bases)) => {
+                bases.iter().filter_map(|b| b.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        for base in extends {
+            if depth < 5 && base.starts_with('.') {
+                let path = normalize(&join(dir, base))
+                    .filter(|path| self.exists(path))
+                    .or_else(|| normalize(&format!("{}.json", join(dir, base))));
+                if let Some(path) = path {
+                    let inherited = self.load_tsconfig(&path, depth + 1);
+                    config.base_url = inherited.base_url.or(config.base_url);
+                    if !inherited.paths.is_empty() {
+                        config.paths = inherited.paths;
+                    }
+                }
+            }
+        }
+
+        let options = json.get("compilerOptions");
+        if let Some(base_url) = options
+            .and_then(|o| o.get("baseUrl"))
+            .and_then(|b| b.as_str())
+        {
+            config.base_url = normalize(&join(dir, base_url));
+        }
+        if let Some(paths) = options
+            .and_then(|o| o.get("paths"))
+            .and_then(|p| p.as_object())
+        {
+            // Sem baseUrl, os destinos são relativos ao próprio tsconfig
+            let base = config.base_url.clone().unwrap_or_else(|| dir.to_string());
+            config.paths = paths
+                .iter()
+                .map(|(pattern, targets)| {
+                    let targets = targets
+                        .as_array()
+                        .map(|targets| {
+                            targets
+                                .iter()
+                                .filter_map(|t| t.as_str())
+                                .filter_map(|t| normalize(&join(&base, t)))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    (pattern.clone(), targets)
+                })
+                .collect();
+        }
+        config
+    }
+
+    fn resolve_script(&self, from: &str, specifier: &str) -> Option<String> {
+        if specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || specifier == "."
+            || specifier == ".."
+        {
+            return normalize(&join(parent(from), specifier))
+                .and_then(|path| self.probe_script(&path));
+        }
+
+        for config in self
+            .script_configs
+            .iter()
+            .filter(|config| is_within(from, &config.dir))
+        {
+            for (find, replacement) in &config.aliases {
+                if let Some(rest) = specifier.strip_prefix(find.as_str()) {
+                    if rest.is_empty() || rest.starts_with('/') {
+                        let target = normalize(&format!("{}{}", replacement, rest));
+                        if let Some(found) = target.and_then(|t| self.probe_script(&t)) {
+                            return Some(found);
+                        }
+                    }
+                }
+            }
+            for (pattern, targets) in &config.paths {
+                let Some(star) = match_path_pattern(pattern, specifier) else {
+                    continue;
+                };
+                for target in targets {
+                    if let Some(found) = self.probe_script(&target.replacen('*', star, 1)) {
+                        return Some(found);
+                    }
+                }
+            }
+            if let Some(found) = config
+                .base_url
+                .as_deref()
+                .and_then(|base| normalize(&join(base, specifier)))
+                .and_then(|path| self.probe_script(&path))
+            {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    fn probe_script(&self, path: &str) -> Option<String> {
+        let path = path.trim_end_matches('/');
+        if self.exists(path) {
+            return Some(path.to_string());
+        }
+        // import './foo.js' apontando para foo.ts (ESM com TypeScript)
+        if let Some((stem, extension)) = path.rsplit_once('.') {
+            if ["js", "jsx", "mjs", "cjs"].contains(&extension) {
+                let found = ["ts", "tsx", "mts", "cts"]
+                    .iter()
+                    .map(|e| format!("{}.{}", stem, e))
+                    .find(|candidate| self.exists(candidate));
+                if found.is_some() {
+                    return found;
+                }
+            }
+        }
+        SCRIPT_EXTENSIONS
+            .iter()
+            .map(|e| format!("{}.{}", path, e))
+            .chain(
+                SCRIPT_EXTENSIONS
+                    .iter()
+                    .map(|e| join(path, &format!("index.{}", e))),
+            )
+            .find(|candidate| self.exists(candidate))
+    }
+
+    // `from pkg import a, b` liga aos submódulos a/b quando existem; senão ao próprio pkg
+    fn resolve_python(
+        &self,
+        from: &str,
+        level: usize,
+        module: &[String],
+        names: &[String],
+    ) -> Vec<String> {
+        let bases: Vec<String> = if level > 0 {
+            let mut dir = parent(from);
+            for _ in 1..level {
+                dir = parent(dir);
+            }
+            vec![dir.to_string()]
+        } else {
+            self.python_roots.clone()
+        };
+
+        for base in &bases {
+            let submodules: Vec<String> = names
+                .iter()
+                .filter_map(|name| {
+                    self.probe_python(base, &[module, std::slice::from_ref(name)].concat())
+                })
+                .collect();
+            if !submodules.is_empty() {
+                return submodules;
+            }
+            let min_depth = if level > 0 { 0 } else { 1 };
+            for depth in (min_depth..=module.len()).rev() {
+                if let Some(found) = self.probe_python(base, &module[..depth]) {
+                    return vec![found];
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn probe_python(&self, base: &str, segments: &[String]) -> Option<String> {
+        let path = join(base, &segments.join("/"));
+        let candidates = if segments.is_empty() {
+            vec![join(&path, "__init__.py")]
+        } else {
+            vec![
+                format!("{}.py", path),
+                format!("{}.pyi", path),
+                join(&path, "__init__.py"),
+            ]
+        };
+        candidates
+            .into_iter()
+            .find(|candidate| self.exists(candidate))
+    }
+
+    fn resolve_go(&self, import: &str) -> Option<String> {
+        let (module, dir) = self
+            .go_modules
+            .iter()
+            .find(|(module, _)| import == module || import.starts_with(&format!("{}/", module)))?;
+        let package = join(dir, import[module.len()..].trim_start_matches('/'));
+        Some(package_id(&package))
+    }
+}
+
+// `@/*` casa com `@/components/Button` e devolve `components/Button`
+fn match_path_pattern<'s>(pattern: &str, specifier: &'s str) -> Option<&'s str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix)),
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+// Aliases em `resolve.alias` do Vite, nas formas de objeto e de lista ({ find, replacement })
+fn vite_aliases(content: &str, dir: &str) -> Vec<(String, String)> {
+    static OBJECT: OnceLock<Regex> = OnceLock::new();
+    static LIST: OnceLock<Regex> = OnceLock::new();
+    let object = OBJECT.get_or_init(|| {
+        Regex::new(r#"['"]?([@~$\w./-]+)['"]?\s*:\s*(?:(?:path\.)?(?:resolve|join)\(\s*(?:__dirname|process\.cwd\(\))\s*,\s*|fileURLToPath\(\s*new\s+URL\(\s*)?['"]([^'"]+)['"]"#).unwrap()
+    });
+    let list = LIST.get_or_init(|| {
+        Regex::new(r#"find\s*:\s*['"]([^'"]+)['"]\s*,\s*replacement\s*:\s*(?:(?:path\.)?(?:resolve|join)\(\s*(?:__dirname|process\.cwd\(\))\s*,\s*|fileURLToPath\(\s*new\s+URL\(\s*)?['"]([^'"]+)['"]"#).unwrap()
+    });
+
+    let Some(block) = alias_block(content) else {
+        return Vec::new();
+    };
+    let regex = if block.starts_with('[') { list } else { object };
+    regex
+        .captures_iter(block)
+        .filter_map(|captures| {
+            let replacement = captures[2].trim_start_matches('/');
+            let target = normalize(&join(dir, replacement))?;
+            Some((captures[1].to_string(), target))
+        })
+        .collect()
+}
+
+// O objeto/lista de `alias:` dentro de `resolve: { ... }`. Um `alias` solto (import de
+// plugin, comentário) não conta: o bloco seguinte seria o `defineConfig({`
+fn alias_block(content: &str) -> Option<&str> {
+    static RESOLVE: OnceLock<Regex> = OnceLock::new();
+    static ALIAS: OnceLock<Regex> = OnceLock::new();
+    let resolve = RESOLVE.get_or_init(|| Regex::new(r"\bresolve\s*:\s*\{").unwrap());
+    let alias = ALIAS.get_or_init(|| Regex::new(r"\balias\s*:\s*[\{\[]").unwrap());
+
+    resolve.find_iter(content).find_map(|found| {
+        let resolve_block = balanced_block(content, found.end() - 1)?;
+        let open = alias.find(resolve_block)?.end() - 1;
+        balanced_block(resolve_block, open)
+    })
+}
+
+// De `open` (um `{` ou `[`) até o fechamento correspondente
+fn balanced_block(content: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    for (index, c) in content[open..].char_indices() {
+        match c {
+            '{' | '[' | '(' => depth += 1,
+            '}' | ']' | ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open..=open + index]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// tsconfig aceita comentários e vírgulas sobrando
+fn parse_jsonc(content: &str) -> Option<serde_json::Value> {
+    let mut json = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|next| *next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+
+    static TRAILING_COMMA: OnceLock<Regex> = OnceLock::new();
+    let trailing = TRAILING_COMMA.get_or_init(|| Regex::new(r",(\s*[}\]])").unwrap());
+    serde_json::from_str(&trailing.replace_all(&json, "$1")).ok()
+}
+
+// Tarjan iterativo; componentes com mais de um nó são os ciclos de dependência
+fn strongly_connected(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let mut index = vec![UNVISITED; adjacency.len()];
+    let mut low = vec![0; adjacency.len()];
+    let mut on_stack = vec![false; adjacency.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut components = Vec::new();
+    let mut counter = 0;
+
+    for start in 0..adjacency.len() {
+        if index[start] != UNVISITED {
+            continue;
+        }
+        index[start] = counter;
+        low[start] = counter;
+        counter += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        let mut work: Vec<(usize, usize)> = vec![(start, 0)];
+
+        while let Some(&(node, position)) = work.last() {
+            if let Some(&next) = adjacency[node].get(position) {
+                if let Some(top) = work.last_mut() {
+                    top.1 += 1;
+                }
+                if index[next] == UNVISITED {
+                    index[next] = counter;
+                    low[next] = counter;
+                    counter += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    work.push((next, 0));
+                } else if on_stack[next] {
+                    low[node] = low[node].min(index[next]);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    components.push(component);
+                }
+            }
+        }
+    }
+
+    components
+}
+
+fn cycle_members(graph: &ModuleGraph) -> HashMap<&str, usize> {
+    graph
+        .cycles
+        .iter()
+        .enumerate()
+        .flat_map(|(index, cycle)| cycle.iter().map(move |id| (id.as_str(), index)))
+        .collect()
+}
+
+fn in_same_cycle(members: &HashMap<&str, usize>, edge: &ModuleEdge) -> bool {
+    members
+        .get(edge.from.as_str())
+        .is_some_and(|cycle| members.get(edge.to.as_str()) == Some(cycle))
+}
+
+// Graphviz; nós e arestas de ciclos em vermelho
+pub fn to_dot(graph: &ModuleGraph) -> String {
+    let members = cycle_members(graph);
+    let quote = |id: &str| format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut dot = String::from(
+        "digraph modules {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n",
+    );
+    for node in &graph.nodes {
+        let color = if members.contains_key(node.id.as_str()) {
+            ", color=red"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "    {} [label={}, tooltip=\"{} | fan-in {} | fan-out {}\"{}];\n",
+            quote(&node.id),
+            quote(&node.id),
+            node.language,
+            node.fan_in,
+            node.fan_out,
+            color
+        ));
+    }
+    for edge in &graph.edges {
+        let color = if in_same_cycle(&members, edge) {
+            " [color=red]"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "    {} -> {}{};\n",
+            quote(&edge.from),
+            quote(&edge.to),
+            color
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Mermaid (flowchart); os ids viram n0, n1... e o caminho fica no rótulo
+pub fn to_mermaid(graph: &ModuleGraph) -> String {
+    let members = cycle_members(graph);
+    let ids: HashMap<&str, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id.as_str(), format!("n{}", index)))
+        .collect();
+
+    let mut mermaid = String::from("graph LR\n");
+    for node in &graph.nodes {
+        mermaid.push_str(&format!(
+            "    {}[\"{}\"]\n",
+            ids[node.id.as_str()],
+            node.id.replace('"', "#quot;")
+        ));
+    }
+
+    let mut cycle_links = Vec::new();
+    for (index, edge) in graph.edges.iter().enumerate() {
+        mermaid.push_str(&format!(
+            "    {} --> {}\n",
+            ids[edge.from.as_str()],
+            ids[edge.to.as_str()]
+        ));
+        if in_same_cycle(&members, edge) {
+            cycle_links.push(index.to_string());
+        }
+    }
+
+    if !members.is_empty() {
+        let mut cycle_nodes: Vec<&String> = members.keys().map(|id| &ids[id]).collect();
+        cycle_nodes.sort();
+        mermaid.push_str("    classDef cycle stroke:#e53e3e,stroke-width:2px\n");
+        mermaid.push_str(&format!(
+            "    class {} cycle\n",
+            cycle_nodes
+                .iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+        mermaid.push_str(&format!(
+            "    linkStyle {} stroke:#e53e3e\n",
+            cycle_links.join(",")
+        ));
+    }
+    mermaid
+}
+
+fn file_name(relative: &str) -> &str {
+    relative.rsplit('/').next().unwrap_or(relative)
+}
+
+fn parent(relative: &str) -> &str {
+    relative.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn join(dir: &str, path: &str) -> String {
+    match (dir.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (_, true) => dir.to_string(),
+        _ => format!("{}/{}", dir, path),
+    }
+}
+
+fn is_within(relative: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || relative
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn package_id(dir: &str) -> String {
+    if dir.is_empty() {
+        ".".to_string()
+    } else {
+        dir.to_string()
+    }
+}
+
+// Resolve `.` e `..`; None se o caminho sair da raiz do workspace
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_jsonc_accepts_comments_and_trailing_commas() {
+        let value = parse_jsonc(
+            r#"{
+  // comentário de linha
+  "compilerOptions": {
+    "baseUrl": "./src", /* bloco */
+    "paths": { "@/*": ["./*"], },
+    "outDir": "http://exemplo.com//dist",
+  },
+}"#,
+        )
+        .unwrap();
+        assert_eq!(value["compilerOptions"]["baseUrl"], "./src");
+        assert_eq!(value["compilerOptions"]["paths"]["@/*"][0], "./*");
+        // `//` dentro de string não é comentário
+        assert_eq!(
+            value["compilerOptions"]["outDir"],
+            "http://exemplo.com//dist"
+        );
+        assert!(parse_jsonc("{ \"a\": }").is_none());
+    }
+
+    #[test]
+    fn match_path_pattern_with_and_without_wildcard() {
+        assert_eq!(
+            match_path_pattern("@/*", "@/components/Button"),
+            Some("components/Button")
+        );
+        assert_eq!(match_path_pattern("*.vue", "App.vue"), Some("App"));
+        assert_eq!(match_path_pattern("@/*", "lodash"), None);
+        assert_eq!(match_path_pattern("config", "config"), Some(""));
+        assert_eq!(match_path_pattern("config", "config/dev"), None);
+    }
+
+    #[test]
+    fn vite_aliases_object_and_list_forms() {
+        let object = r#"
+import { defineConfig } from 'vite'
+import path from 'path'
+
+export default defineConfig({
+  resolve: {
+    alias: {
+      '@': path.resolve(__dirname, './src'),
+      '~utils': fileURLToPath(new URL('./src/utils', import.meta.url)),
+    },
+  },
+})"#;
+        assert_eq!(
+            vite_aliases(object, "web"),
+            [
+                ("@".to_string(), "web/src".to_string()),
+                ("~utils".to_string(), "web/src/utils".to_string())
+            ]
+        );
+
+        let list = r#"export default {
+  resolve: { alias: [{ find: '@lib', replacement: '/lib' }] },
+}"#;
+        assert_eq!(
+            vite_aliases(list, ""),
+            [("@lib".to_string(), "lib".to_string())]
+        );
+    }
+
+    #[test]
+    fn vite_aliases_ignore_alias_outside_resolve() {
+        // O plugin de alias e as opções de build não são aliases
+        let content = r#"
+import alias from '@rollup/plugin-alias'
+
+export default defineConfig({
+  base: '/app/',
+  plugins: [alias()],
+  build: { outDir: 'dist' },
+})"#;
+        assert!(vite_aliases(content, "").is_empty());
+
+        let with_resolve = format!(
+            "{}\nexport const extra = {{ resolve: {{ alias: {{ '@': './src' }} }} }}",
+            content
+        );
+        assert_eq!(
+            vite_aliases(&with_resolve, ""),
+            [("@".to_string(), "src".to_string())]
+        );
+    }
+
+    #[test]
+    fn expand_use_tree_flattens_groups() {
+        let paths = expand_use_tree("a::{b, c::{d as e, self}, f::*}");
+        let expected: Vec<Vec<String>> = [
+            vec!["a", "b"],
+            vec!["a", "c", "d"],
+            vec!["a", "c"],
+            vec!["a", "f"],
+        ]
+        .iter()
+        .map(|path| path.iter().map(|s| s.to_string()).collect())
+        .collect();
+        assert_eq!(paths, expected);
+        assert_eq!(
+            expand_use_tree("::std::collections::HashMap"),
+            [["std", "collections", "HashMap"]]
+        );
+        assert_eq!(
+            expand_use_tree("crate::{\n    types::Foo,\n}"),
+            [["crate", "types", "Foo"]]
+        );
+    }
+
+    #[test]
+    fn strongly_connected_finds_only_cycles() {
+        // 0 -> 1 -> 2 -> 0 é um ciclo; 3 -> 4 -> 3 é outro; 5 só aponta para o primeiro
+        let adjacency = vec![
+            vec![1],
+            vec![2],
+            vec![0, 3],
+            vec![4],
+            vec![3],
+            vec![0],
+            vec![6],
+        ];
+        let mut components: Vec<Vec<usize>> = strongly_connected(&adjacency)
+            .into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+        // O laço de 6 em si mesmo não é ciclo entre módulos
+        assert_eq!(components, [vec![0, 1, 2], vec![3, 4]]);
+        assert!(strongly_connected(&[vec![1], vec![2], vec![]]).is_empty());
+    }
+}
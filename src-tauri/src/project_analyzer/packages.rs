@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::dependencies::{self, ProjectManifest};
+use super::loc::{self, FileLines};
+use crate::types::{Dependency, PackageNode, PackageTotals};
+
+// Manifestos do mesmo diretório (ex.: package.json + pnpm-workspace.yaml) viram um pacote
+#[derive(Default)]
+struct Package {
+    name: Option<String>,
+    ecosystems: Vec<String>,
+    manifests: Vec<String>,
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+// Árvore de pacotes do repositório. A raiz é sempre o nó de topo, mesmo sem manifesto;
+// cada arquivo e dependência pertence ao pacote mais profundo que o contém. Os manifestos
+// vêm de `dependencies::collect_with_manifests`, que já os leu
+pub fn build(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    lines: &[FileLines],
+    manifests: Vec<ProjectManifest>,
+    dependencies: &[Dependency],
+) -> PackageNode {
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+    packages.insert(String::new(), Package::default());
+    for manifest in manifests {
+        let path = slash_path(&manifest.path);
+        let dir = path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        let package = packages.entry(dir.to_string()).or_default();
+        if package.name.is_none() {
+            package.name = manifest.name;
+        }
+        if !package.ecosystems.iter().any(|e| e == manifest.ecosystem) {
+            package.ecosystems.push(manifest.ecosystem.to_string());
+        }
+        package.manifests.push(path);
+        package.members.extend(manifest.members);
+        package.exclude.extend(manifest.exclude);
+    }
+    let dirs: Vec<&str> = packages.keys().map(|dir| dir.as_str()).collect();
+
+    let mut own: HashMap<&str, PackageTotals> = HashMap::new();
+    for (path, _) in files {
+        let relative = slash_path(path.strip_prefix(root).unwrap_or(path));
+        own.entry(owner(&dirs, &relative)).or_default().files += 1;
+    }
+    for file in lines {
+        let totals = own
+            .entry(owner(&dirs, &slash_path(&file.relative_path)))
+            .or_default();
+        totals.code += file.counts.code;
+        totals.comments += file.counts.comments;
+        totals.blanks += file.counts.blanks;
+        totals.lines += file.counts.lines();
+    }
+    for dependency in dependencies {
+        own.entry(owner(&dirs, &dependency.manifest))
+            .or_default()
+            .dependencies += 1;
+    }
+
+    node(root, "", &packages, &dirs, &own, lines)
+}
+
+fn node(
+    root: &Path,
+    dir: &str,
+    packages: &BTreeMap<String, Package>,
+    dirs: &[&str],
+    own: &HashMap<&str, PackageTotals>,
+    lines: &[FileLines],
+) -> PackageNode {
+    let package = &packages[dir];
+    let children: Vec<PackageNode> = dirs
+        .iter()
+        .filter(|child| **child != dir && parent(dirs, child) == Some(dir))
+        .map(|child| node(root, child, packages, dirs, own, lines))
+        .collect();
+
+    let own = own.get(dir).cloned().unwrap_or_default();
+    let mut totals = own.clone();
+    for child in &children {
+        totals.files += child.totals.files;
+        totals.code += child.totals.code;
+        totals.comments += child.totals.comments;
+        totals.blanks += child.totals.blanks;
+        totals.lines += child.totals.lines;
+        totals.dependencies += child.totals.dependencies;
+    }
+
+    // Listado como membro por algum workspace acima (padrões relativos ao workspace)
+    let workspace_member = !dir.is_empty()
+        && dirs
+            .iter()
+            .filter(|ancestor| **ancestor != dir && contains(ancestor, dir))
+            .any(|ancestor| {
+                let workspace = &packages[*ancestor];
+                let relative = dir
+                    .strip_prefix(*ancestor)
+                    .unwrap_or(dir)
+                    .trim_start_matches('/');
+                workspace
+                    .members
+                    .iter()
+                    .any(|pattern| dependencies::matches_pattern(pattern, relative))
+                    && !workspace
+                        .exclude
+                        .iter()
+                        .any(|pattern| dependencies::matches_pattern(pattern, relative))
+            });
+
+    let dir_name = if dir.is_empty() {
+        root.canonicalize()
+            .ok()
+            .and_then(|root| root.file_name().map(|n| n.to_string_lossy().into_owned()))
+    } else {
+        dir.rsplit('/').next().map(|name| name.to_string())
+    };
+    PackageNode {
+        name: package
+            .name
+            .clone()
+            .or(dir_name)
+            .unwrap_or_else(|| ".".to_string()),
+        path: if dir.is_empty() { "." } else { dir }.to_string(),
+        ecosystems: package.ecosystems.clone(),
+        manifests: package.manifests.clone(),
+        workspace_root: !package.members.is_empty(),
+        workspace_member,
+        own,
+        totals,
+        languages: loc::language_stats(
+            lines
+                .iter()
+                .filter(|file| contains(dir, &slash_path(&file.relative_path))),
+        ),
+        children,
+    }
+}
+
+// Pacote mais profundo que contém o caminho (a raiz, "", contém tudo)
+fn owner<'a>(dirs: &[&'a str], path: &str) -> &'a str {
+    dirs.iter()
+        .copied()
+        .filter(|dir| contains(dir, path))
+        .max_by_key(|dir| dir.len())
+        .unwrap_or("")
+}
+
+fn parent<'a>(dirs: &[&'a str], dir: &str) -> Option<&'a str> {
+    dirs.iter()
+        .copied()
+        .filter(|candidate| *candidate != dir && contains(candidate, dir))
+        .max_by_key(|candidate| candidate.len())
+}
+
+fn contains(dir: &str, path: &str) -> bool {
+    dir.is_empty()
+        || path == dir
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+// Seção do resumo para repositórios com mais de um projeto
+pub fn summary(tree: &PackageNode) -> String {
+    let mut lines = Vec::new();
+    push_summary_lines(tree, 0, &mut lines);
+    if lines.len() < 2 {
+        return String::new();
+    }
+    format!("## Projetos no repositório:\n{}\n", lines.concat())
+}
+
+fn push_summary_lines(node: &PackageNode, depth: usize, lines: &mut Vec<String>) {
+    let depth = if node.manifests.is_empty() {
+        depth
+    } else {
+        lines.push(format!(
+            "{}- `{}` ({}, {}): {} linhas de código em {} arquivo(s)\n",
+            "  ".repeat(depth),
+            node.path,
+            node.name,
+            node.ecosystems.join(", "),
+            node.own.code,
+            node.own.files
+        ));
+        depth + 1
+    };
+    for child in &node.children {
+        push_summary_lines(child, depth, lines);
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::types::AnalysisProgress;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Análise cancelada")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+// Controle compartilhado entre as threads da análise: flag de cancelamento,
+// contadores de progresso e o callback que recebe os eventos (limitados a 10/s).
+pub struct AnalysisControl<'a> {
+    cancel: &'a AtomicBool,
+    on_progress: &'a (dyn Fn(&AnalysisProgress) + Sync),
+    phase: Mutex<String>,
+    current_directory: Mutex<String>,
+    files_scanned: AtomicUsize,
+    directories_scanned: AtomicUsize,
+    bytes_read: AtomicU64,
+    last_report: Mutex<Instant>,
+}
+
+impl<'a> AnalysisControl<'a> {
+    pub fn new(
+        cancel: &'a AtomicBool,
+        on_progress: &'a (dyn Fn(&AnalysisProgress) + Sync),
+    ) -> Self {
+        AnalysisControl {
+            cancel,
+            on_progress,
+            phase: Mutex::new(String::new()),
+            current_directory: Mutex::new(String::new()),
+            files_scanned: AtomicUsize::new(0),
+            directories_scanned: AtomicUsize::new(0),
+            bytes_read: AtomicU64::new(0),
+            last_report: Mutex::new(
+                Instant::now()
+                    .checked_sub(PROGRESS_INTERVAL)
+                    .unwrap_or_else(Instant::now),
+            ),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_phase(&self, phase: &str) {
+        *self.phase.lock().unwrap() = phase.to_string();
+        self.report(true);
+    }
+
+    pub fn directory_scanned(&self, path: &Path) {
+        self.directories_scanned.fetch_add(1, Ordering::Relaxed);
+        *self.current_directory.lock().unwrap() = path.to_string_lossy().to_string();
+        self.report(false);
+    }
+
+    pub fn file_scanned(&self) {
+        self.files_scanned.fetch_add(1, Ordering::Relaxed);
+        self.report(false);
+    }
+
+    pub fn bytes_read(&self, bytes: u64) {
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+        self.report(false);
+    }
+
+    pub fn report(&self, force: bool) {
+        {
+            let mut last = self.last_report.lock().unwrap();
+            if !force && last.elapsed() < PROGRESS_INTERVAL {
+                return;
+            }
+            *last = Instant::now();
+        }
+
+        (self.on_progress)(&AnalysisProgress {
+            job_id: None,
+            phase: self.phase.lock().unwrap().clone(),
+            files_scanned: self.files_scanned.load(Ordering::Relaxed),
+            directories_scanned: self.directories_scanned.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            current_directory: self.current_directory.lock().unwrap().clone(),
+        });
+    }
+}
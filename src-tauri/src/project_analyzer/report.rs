@@ -0,0 +1,590 @@
+use anyhow::Result;
+use serde_json::{json, Value as JsonValue};
+use std::path::Path;
+
+use crate::types::{CodeMetrics, FunctionMetrics, ProjectAnalysis, SuggestionSeverity};
+
+// Funções listadas na seção de complexidade do relatório
+const REPORT_FUNCTIONS: usize = 20;
+const REPORT_ROWS: usize = 20;
+// Acima disso a função vira achado no SARIF (limites usuais de SonarQube e McCabe)
+const COGNITIVE_THRESHOLD: usize = 15;
+const CYCLOMATIC_THRESHOLD: usize = 10;
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "RZOneHub";
+
+// Bloco do relatório, montado uma vez e renderizado em Markdown ou HTML
+enum Block {
+    Paragraph(String),
+    List(Vec<String>),
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+}
+
+struct Section {
+    title: String,
+    blocks: Vec<Block>,
+}
+
+fn sections(
+    root: &Path,
+    analysis: &ProjectAnalysis,
+    metrics: Option<&CodeMetrics>,
+) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    let total_lines: usize = analysis.languages.iter().map(|l| l.lines).sum();
+    let code_lines: usize = analysis.languages.iter().map(|l| l.code).sum();
+    sections.push(Section {
+        title: "Visão geral".to_string(),
+        blocks: vec![Block::List(vec![
+            format!("Localização: {}", root.display()),
+            format!("Arquivos: {}", analysis.total_files),
+            format!("Diretórios: {}", analysis.total_directories),
+            format!("Linhas: {} ({} de código)", total_lines, code_lines),
+            format!("Dependências declaradas: {}", analysis.dependencies.len()),
+        ])],
+    });
+
+    if !analysis.languages.is_empty() {
+        sections.push(Section {
+            title: "Linguagens".to_string(),
+            blocks: vec![Block::Table(
+                vec![
+                    "Linguagem",
+                    "Arquivos",
+                    "Código",
+                    "Comentários",
+                    "Em branco",
+                ],
+                analysis
+                    .languages
+                    .iter()
+                    .map(|l| {
+                        vec![
+                            l.language.clone(),
+                            l.files.to_string(),
+                            l.code.to_string(),
+                            l.comments.to_string(),
+                            l.blanks.to_string(),
+                        ]
+                    })
+                    .collect(),
+            )],
+        });
+    }
+
+    if !analysis.stack.is_empty() {
+        sections.push(Section {
+            title: "Stack".to_string(),
+            blocks: vec![Block::Table(
+                vec!["Tecnologia", "Categoria", "Confiança", "Evidências"],
+                analysis
+                    .stack
+                    .iter()
+                    .map(|t| {
+                        vec![
+                            t.name.clone(),
+                            super::category_label(t.category).to_string(),
+                            format!("{:.0}%", t.confidence * 100.0),
+                            t.evidence.join(", "),
+                        ]
+                    })
+                    .collect(),
+            )],
+        });
+    }
+
+    let suggestions = if analysis.suggestion_details.is_empty() {
+        vec![Block::Paragraph("Nenhuma sugestão.".to_string())]
+    } else {
+        analysis
+            .suggestion_details
+            .iter()
+            .flat_map(|s| {
+                let mut blocks = vec![Block::Paragraph(format!(
+                    "[{}] {} — {}",
+                    severity_label(s.severity),
+                    s.message,
+                    s.rationale
+                ))];
+                if !s.evidence.is_empty() {
+                    blocks.push(Block::List(s.evidence.clone()));
+                }
+                blocks
+            })
+            .collect()
+    };
+    sections.push(Section {
+        title: format!("Sugestões ({})", analysis.suggestion_details.len()),
+        blocks: suggestions,
+    });
+
+    sections.push(Section {
+        title: format!("Possíveis segredos ({})", analysis.secrets.len()),
+        blocks: vec![if analysis.secrets.is_empty() {
+            Block::Paragraph("Nenhum segredo encontrado.".to_string())
+        } else {
+            Block::Table(
+                vec!["Arquivo", "Linha", "Regra", "Trecho"],
+                analysis
+                    .secrets
+                    .iter()
+                    .map(|s| {
+                        vec![
+                            s.file.clone(),
+                            s.line.to_string(),
+                            s.description.clone(),
+                            s.preview.clone(),
+                        ]
+                    })
+                    .collect(),
+            )
+        }],
+    });
+
+    if let Some(audit) = &analysis.vulnerability_audit {
+        sections.push(Section {
+            title: format!("Vulnerabilidades ({})", audit.vulnerabilities.len()),
+            blocks: vec![if audit.vulnerabilities.is_empty() {
+                Block::Paragraph(format!(
+                    "Nenhuma vulnerabilidade conhecida em {} pacote(s).",
+                    audit.packages_checked
+                ))
+            } else {
+                Block::Table(
+                    vec!["Pacote", "Versão", "Advisory", "Severidade", "Corrigida em"],
+                    audit
+                        .vulnerabilities
+                        .iter()
+                        .map(|v| {
+                            vec![
+                                v.package.clone(),
+                                v.version.clone(),
+                                v.id.clone(),
+                                format!("{:?}", v.severity).to_lowercase(),
+                                v.fixed_versions.join(", "),
+                            ]
+                        })
+                        .collect(),
+                )
+            }],
+        });
+    }
+
+    if let Some(metrics) = metrics.filter(|m| !m.worst_functions.is_empty()) {
+        sections.push(Section {
+            title: "Complexidade".to_string(),
+            blocks: vec![
+                Block::Paragraph(format!(
+                    "{} funções em {} arquivos, complexidade ciclomática média {}.",
+                    metrics.functions_analyzed, metrics.files_analyzed, metrics.average_cyclomatic
+                )),
+                Block::Table(
+                    vec!["Função", "Arquivo", "Linhas", "Ciclomática", "Cognitiva"],
+                    metrics
+                        .worst_functions
+                        .iter()
+                        .take(REPORT_FUNCTIONS)
+                        .map(|f| {
+                            vec![
+                                f.name.clone(),
+                                format!("{}:{}", f.file, f.line),
+                                f.lines.to_string(),
+                                f.cyclomatic.to_string(),
+                                f.cognitive.to_string(),
+                            ]
+                        })
+                        .collect(),
+                ),
+            ],
+        });
+    }
+
+    if let Some(git) = &analysis.git {
+        let mut blocks = vec![Block::Paragraph(format!(
+            "{} commits nos últimos {} dias ({} no total), bus factor {}.",
+            git.commits_in_window, git.window_days, git.total_commits, git.bus_factor
+        ))];
+        if !git.hotspots.is_empty() {
+            blocks.push(Block::Table(
+                vec!["Hotspot", "Commits", "Complexidade", "Pontuação"],
+                git.hotspots
+                    .iter()
+                    .take(REPORT_ROWS)
+                    .map(|h| {
+                        vec![
+                            h.file.clone(),
+                            h.commits.to_string(),
+                            h.complexity.to_string(),
+                            h.score.to_string(),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+        sections.push(Section {
+            title: "Histórico do git".to_string(),
+            blocks,
+        });
+    }
+
+    if !analysis.largest_files.is_empty() {
+        sections.push(Section {
+            title: "Maiores arquivos".to_string(),
+            blocks: vec![Block::Table(
+                vec!["Arquivo", "Tamanho"],
+                analysis
+                    .largest_files
+                    .iter()
+                    .take(REPORT_ROWS)
+                    .map(|f| vec![f.path.clone(), format_size(f.size)])
+                    .collect(),
+            )],
+        });
+    }
+
+    sections
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn title(root: &Path) -> String {
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.display().to_string());
+    format!("Análise de {}", name)
+}
+
+fn generated_at() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+}
+
+pub fn to_markdown(
+    root: &Path,
+    analysis: &ProjectAnalysis,
+    metrics: Option<&CodeMetrics>,
+) -> String {
+    let mut markdown = format!("# {}\n\nGerado em {}.\n", title(root), generated_at());
+    for section in sections(root, analysis, metrics) {
+        markdown.push_str(&format!("\n## {}\n", section.title));
+        for block in section.blocks {
+            markdown.push('\n');
+            match block {
+                Block::Paragraph(text) => markdown.push_str(&format!("{}\n", text)),
+                Block::List(items) => {
+                    for item in items {
+                        markdown.push_str(&format!("- {}\n", item));
+                    }
+                }
+                Block::Table(headers, rows) => {
+                    markdown.push_str(&format!("| {} |\n", headers.join(" | ")));
+                    markdown.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+                    for row in rows {
+                        let cells: Vec<String> = row.iter().map(|c| markdown_cell(c)).collect();
+                        markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    }
+                }
+            }
+        }
+    }
+    markdown
+}
+
+// `|` e quebras de linha desmontariam a tabela
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+// Arquivo único, com o CSS embutido, para abrir no navegador ou anexar a um PR
+pub fn to_html(root: &Path, analysis: &ProjectAnalysis, metrics: Option<&CodeMetrics>) -> String {
+    let title = escape_html(&title(root));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"pt-BR\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p class=\"muted\">Gerado em {}.</p>\n",
+        HTML_STYLE,
+        generated_at()
+    );
+    for section in sections(root, analysis, metrics) {
+        html.push_str(&format!(
+            "<section>\n<h2>{}</h2>\n",
+            escape_html(&section.title)
+        ));
+        for block in section.blocks {
+            match block {
+                Block::Paragraph(text) => {
+                    html.push_str(&format!("<p>{}</p>\n", escape_html(&text)))
+                }
+                Block::List(items) => {
+                    html.push_str("<ul>\n");
+                    for item in items {
+                        html.push_str(&format!("<li>{}</li>\n", escape_html(&item)));
+                    }
+                    html.push_str("</ul>\n");
+                }
+                Block::Table(headers, rows) => {
+                    html.push_str("<table>\n<thead><tr>");
+                    for header in headers {
+                        html.push_str(&format!("<th>{}</th>", escape_html(header)));
+                    }
+                    html.push_str("</tr></thead>\n<tbody>\n");
+                    for row in rows {
+                        html.push_str("<tr>");
+                        for cell in row {
+                            html.push_str(&format!("<td>{}</td>", escape_html(&cell)));
+                        }
+                        html.push_str("</tr>\n");
+                    }
+                    html.push_str("</tbody>\n</table>\n");
+                }
+            }
+        }
+        html.push_str("</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+const HTML_STYLE: &str = "body { font-family: system-ui, sans-serif; margin: 2rem auto; \
+max-width: 1100px; padding: 0 1rem; color: #1f2328; }
+h1 { border-bottom: 1px solid #d0d7de; padding-bottom: .5rem; }
+h2 { margin-top: 2rem; }
+.muted { color: #656d76; }
+table { border-collapse: collapse; width: 100%; font-size: .9rem; }
+th, td { border: 1px solid #d0d7de; padding: .35rem .6rem; text-align: left; \
+vertical-align: top; }
+th { background: #f6f8fa; }
+tr:nth-child(even) td { background: #fbfcfd; }
+";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// SARIF 2.1.0 com sugestões, possíveis segredos e funções complexas demais, para
+// ferramentas de code scanning; os caminhos são relativos a SRCROOT (a raiz analisada)
+pub fn to_sarif(
+    root: &Path,
+    analysis: &ProjectAnalysis,
+    metrics: Option<&CodeMetrics>,
+) -> Result<String> {
+    let mut rules: Vec<JsonValue> = Vec::new();
+    let mut results: Vec<JsonValue> = Vec::new();
+
+    for suggestion in &analysis.suggestion_details {
+        let id = format!("suggestion/{}", suggestion.id);
+        let index = rule_index(&mut rules, &id, &suggestion.message);
+        let locations: Vec<JsonValue> = suggestion
+            .evidence
+            .iter()
+            .filter_map(|evidence| evidence_location(root, evidence))
+            .collect();
+        let mut result = json!({
+            "ruleId": id,
+            "ruleIndex": index,
+            "level": match suggestion.severity {
+                SuggestionSeverity::Info => "note",
+                SuggestionSeverity::Warning => "warning",
+                SuggestionSeverity::Critical => "error",
+            },
+            "message": { "text": format!("{} {}", suggestion.message, suggestion.rationale) },
+        });
+        if !locations.is_empty() {
+            result["locations"] = json!(locations);
+        }
+        results.push(result);
+    }
+
+    for secret in &analysis.secrets {
+        let id = format!("secret/{}", secret.rule);
+        let index = rule_index(&mut rules, &id, &secret.description);
+        results.push(json!({
+            "ruleId": id,
+            "ruleIndex": index,
+            "level": "error",
+            "message": { "text": format!("Possível segredo: {}", secret.description) },
+            "locations": [location(&secret.file, secret.line, Some(secret.column), None)],
+        }));
+    }
+
+    for function in metrics.iter().flat_map(|m| &m.worst_functions) {
+        for (id, description, value, threshold) in complexity_findings(function) {
+            let index = rule_index(&mut rules, id, description);
+            results.push(json!({
+                "ruleId": id,
+                "ruleIndex": index,
+                "level": "warning",
+                "message": {
+                    "text": format!(
+                        "{} tem {} {} (limite {})",
+                        function.name, description.to_lowercase(), value, threshold
+                    )
+                },
+                "locations": [
+                    location(&function.file, function.line, None, Some(function.end_line))
+                ],
+            }));
+        }
+    }
+
+    // file:///home/... e, no Windows, file:///C:/...
+    let root_path = root.to_string_lossy().replace('\\', "/");
+    let root_uri = format!(
+        "file:///{}/",
+        encode_uri_path(
+            root_path.trim_start_matches('/').trim_end_matches('/'),
+            b"/:"
+        )
+    );
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "originalUriBaseIds": { "SRCROOT": { "uri": root_uri } },
+            "results": results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&sarif)?)
+}
+
+// Índice da regra em `rules`, registrando-a na primeira ocorrência
+fn rule_index(rules: &mut Vec<JsonValue>, id: &str, description: &str) -> usize {
+    rules
+        .iter()
+        .position(|rule| rule["id"] == id)
+        .unwrap_or_else(|| {
+            rules.push(json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            }));
+            rules.len() - 1
+        })
+}
+
+fn complexity_findings(
+    function: &FunctionMetrics,
+) -> Vec<(&'static str, &'static str, usize, usize)> {
+    let mut findings = Vec::new();
+    if function.cognitive > COGNITIVE_THRESHOLD {
+        findings.push((
+            "complexity/cognitive",
+            "Complexidade cognitiva",
+            function.cognitive,
+            COGNITIVE_THRESHOLD,
+        ));
+    }
+    if function.cyclomatic > CYCLOMATIC_THRESHOLD {
+        findings.push((
+            "complexity/cyclomatic",
+            "Complexidade ciclomática",
+            function.cyclomatic,
+            CYCLOMATIC_THRESHOLD,
+        ));
+    }
+    findings
+}
+
+fn location(file: &str, line: usize, column: Option<usize>, end_line: Option<usize>) -> JsonValue {
+    let mut region = json!({ "startLine": line.max(1) });
+    if let Some(column) = column {
+        region["startColumn"] = json!(column.max(1));
+    }
+    if let Some(end_line) = end_line {
+        region["endLine"] = json!(end_line.max(line));
+    }
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": relative_uri(file), "uriBaseId": "SRCROOT" },
+            "region": region,
+        }
+    })
+}
+
+// Evidências das regras são texto livre; viram localização só quando começam com um
+// arquivo do projeto (`arquivo` ou `arquivo:linha ...`)
+fn evidence_location(root: &Path, evidence: &str) -> Option<JsonValue> {
+    let first = evidence.split_whitespace().next()?;
+    let (file, line) = match first.rsplit_once(':') {
+        Some((file, line)) => (file, line.parse::<usize>().ok()),
+        None => (first, None),
+    };
+    if file.is_empty() || !root.join(file).is_file() {
+        return None;
+    }
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": relative_uri(file), "uriBaseId": "SRCROOT" },
+        }
+    });
+    if let Some(line) = line {
+        location["physicalLocation"]["region"] = json!({ "startLine": line });
+    }
+    Some(location)
+}
+
+// SARIF exige referências URI: espaços, `#`, `%` e não-ASCII viram %XX. O `:` também,
+// senão `a:b.rs` seria lido como esquema
+fn relative_uri(file: &str) -> String {
+    encode_uri_path(&file.replace('\\', "/"), b"/")
+}
+
+fn encode_uri_path(path: &str, keep: &[u8]) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn severity_label(severity: SuggestionSeverity) -> &'static str {
+    match severity {
+        SuggestionSeverity::Info => "info",
+        SuggestionSeverity::Warning => "aviso",
+        SuggestionSeverity::Critical => "crítico",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_locations_use_percent_encoded_uris() {
+        let location = location("docs/notas de versão #2 (100%).md", 3, None, None);
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "docs/notas%20de%20vers%C3%A3o%20%232%20%28100%25%29.md"
+        );
+        assert_eq!(relative_uri("src\\a:b.rs"), "src/a%3Ab.rs");
+        assert_eq!(relative_uri("src-tauri/src/lib.rs"), "src-tauri/src/lib.rs");
+    }
+
+    #[test]
+    fn sarif_root_uri_keeps_drive_letter() {
+        assert_eq!(
+            encode_uri_path("C:/Meus Projetos/app", b"/:"),
+            "C:/Meus%20Projetos/app"
+        );
+    }
+}
@@ -0,0 +1,702 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::dependencies;
+use super::syntax::Grammar;
+use crate::types::{
+    Dependency, DetectedTechnology, LanguageStats, SecretFinding, Suggestion, SuggestionRuleInfo,
+    SuggestionSeverity, TechnologyCategory, VulnerabilityAudit,
+};
+
+// Configuração do projeto: regras desativadas, severidades e regras próprias
+pub const PROJECT_RULES_FILE: &str = ".rzonehub/rules.toml";
+const RULES_EXTENSION: &str = "toml";
+const MAX_EVIDENCE: usize = 10;
+
+// O que as regras enxergam da análise
+pub struct RuleInput<'a> {
+    pub root: &'a Path,
+    // Caminhos relativos com `/`
+    pub files: &'a [String],
+    pub dependencies: &'a [Dependency],
+    pub stack: &'a [DetectedTechnology],
+    pub languages: &'a [LanguageStats],
+    pub secrets: &'a [SecretFinding],
+    pub vulnerability_audit: Option<&'a VulnerabilityAudit>,
+}
+
+pub struct Finding {
+    pub message: String,
+    pub rationale: String,
+    pub evidence: Vec<String>,
+}
+
+pub trait Rule: Sync {
+    fn id(&self) -> &str;
+    fn description(&self) -> &str;
+    fn severity(&self) -> SuggestionSeverity;
+    fn check(&self, input: &RuleInput) -> Option<Finding>;
+}
+
+struct BuiltinRule {
+    id: &'static str,
+    description: &'static str,
+    severity: SuggestionSeverity,
+    check: fn(&RuleInput) -> Option<Finding>,
+}
+
+impl Rule for BuiltinRule {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn severity(&self) -> SuggestionSeverity {
+        self.severity
+    }
+
+    fn check(&self, input: &RuleInput) -> Option<Finding> {
+        (self.check)(input)
+    }
+}
+
+static BUILTIN_RULES: &[BuiltinRule] = &[
+    BuiltinRule {
+        id: "committed-secrets",
+        description: "Segredos encontrados em arquivos do projeto",
+        severity: SuggestionSeverity::Critical,
+        check: committed_secrets,
+    },
+    BuiltinRule {
+        id: "vulnerable-dependencies",
+        description: "Dependências com vulnerabilidades conhecidas",
+        severity: SuggestionSeverity::Critical,
+        check: vulnerable_dependencies,
+    },
+    BuiltinRule {
+        id: "missing-tests",
+        description: "Projeto sem testes automatizados",
+        severity: SuggestionSeverity::Warning,
+        check: missing_tests,
+    },
+    BuiltinRule {
+        id: "missing-readme",
+        description: "Projeto sem README na raiz",
+        severity: SuggestionSeverity::Warning,
+        check: missing_readme,
+    },
+    BuiltinRule {
+        id: "missing-gitignore",
+        description: "Projeto sem .gitignore na raiz",
+        severity: SuggestionSeverity::Warning,
+        check: missing_gitignore,
+    },
+    BuiltinRule {
+        id: "missing-lockfile",
+        description: "Manifesto sem lockfile correspondente",
+        severity: SuggestionSeverity::Warning,
+        check: missing_lockfile,
+    },
+    BuiltinRule {
+        id: "missing-linter",
+        description: "Código JavaScript/TypeScript ou Python sem linter configurado",
+        severity: SuggestionSeverity::Info,
+        check: missing_linter,
+    },
+    BuiltinRule {
+        id: "typescript-migration",
+        description: "Projeto JavaScript grande sem TypeScript",
+        severity: SuggestionSeverity::Info,
+        check: typescript_migration,
+    },
+    BuiltinRule {
+        id: "missing-ci",
+        description: "Projeto sem integração contínua",
+        severity: SuggestionSeverity::Info,
+        check: missing_ci,
+    },
+    BuiltinRule {
+        id: "missing-license",
+        description: "Projeto sem arquivo de licença",
+        severity: SuggestionSeverity::Info,
+        check: missing_license,
+    },
+    BuiltinRule {
+        id: "large-project",
+        description: "Muitos arquivos em um único pacote",
+        severity: SuggestionSeverity::Info,
+        check: large_project,
+    },
+];
+
+// Regra declarada em TOML; todas as condições informadas precisam valer
+#[derive(Debug, Clone, Deserialize)]
+struct UserRuleSpec {
+    id: String,
+    message: String,
+    #[serde(default)]
+    rationale: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "default_severity")]
+    severity: SuggestionSeverity,
+    // Globs: pelo menos um arquivo precisa casar
+    #[serde(default)]
+    present: Vec<String>,
+    // Globs: nenhum arquivo pode casar
+    #[serde(default)]
+    missing: Vec<String>,
+    // Tecnologias que precisam (ou não podem) ter sido detectadas
+    #[serde(default)]
+    stack: Vec<String>,
+    #[serde(default)]
+    not_stack: Vec<String>,
+    // Pelo menos uma dessas dependências declarada
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
+    min_files: Option<usize>,
+}
+
+fn default_severity() -> SuggestionSeverity {
+    SuggestionSeverity::Info
+}
+
+struct UserRule {
+    spec: UserRuleSpec,
+    present: Option<GlobSet>,
+    missing: Option<GlobSet>,
+}
+
+impl UserRule {
+    fn new(spec: UserRuleSpec) -> Result<UserRule> {
+        Ok(UserRule {
+            present: glob_set(&spec.present)?,
+            missing: glob_set(&spec.missing)?,
+            spec,
+        })
+    }
+}
+
+impl Rule for UserRule {
+    fn id(&self) -> &str {
+        &self.spec.id
+    }
+
+    fn description(&self) -> &str {
+        self.spec
+            .description
+            .as_deref()
+            .unwrap_or(&self.spec.message)
+    }
+
+    fn severity(&self) -> SuggestionSeverity {
+        self.spec.severity
+    }
+
+    fn check(&self, input: &RuleInput) -> Option<Finding> {
+        let spec = &self.spec;
+        let mut evidence: Vec<String> = Vec::new();
+
+        if spec.min_files.is_some_and(|min| input.files.len() < min) {
+            return None;
+        }
+        if let Some(present) = &self.present {
+            let matched: Vec<String> = input
+                .files
+                .iter()
+                .filter(|file| present.is_match(file.as_str()))
+                .take(MAX_EVIDENCE)
+                .cloned()
+                .collect();
+            if matched.is_empty() {
+                return None;
+            }
+            evidence.extend(matched);
+        }
+        if let Some(missing) = &self.missing {
+            if input
+                .files
+                .iter()
+                .any(|file| missing.is_match(file.as_str()))
+            {
+                return None;
+            }
+        }
+        for name in &spec.stack {
+            let technology = detected(input, name)?;
+            evidence.extend(technology.evidence.iter().take(MAX_EVIDENCE).cloned());
+        }
+        if spec
+            .not_stack
+            .iter()
+            .any(|name| detected(input, name).is_some())
+        {
+            return None;
+        }
+        if !spec.dependencies.is_empty() {
+            let manifests: Vec<String> = input
+                .dependencies
+                .iter()
+                .filter(|d| spec.dependencies.contains(&d.name))
+                .map(|d| format!("{} ({})", d.manifest, d.name))
+                .collect();
+            if manifests.is_empty() {
+                return None;
+            }
+            evidence.extend(manifests);
+        }
+
+        evidence.dedup();
+        Some(Finding {
+            message: spec.message.clone(),
+            rationale: spec.rationale.clone(),
+            evidence,
+        })
+    }
+}
+
+// Configuração já combinada: regras globais do usuário e depois as do projeto
+#[derive(Default)]
+struct RulesConfig {
+    disabled: HashSet<String>,
+    severity: HashMap<String, SuggestionSeverity>,
+    rules: Vec<UserRule>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    disabled: Vec<String>,
+    #[serde(default)]
+    severity: HashMap<String, SuggestionSeverity>,
+    #[serde(default, rename = "rule")]
+    rules: Vec<UserRuleSpec>,
+}
+
+// Regras do usuário ficam em <config>/RZOneHub/rules/*.toml
+pub fn rules_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("RZOneHub").join("rules"))
+}
+
+impl RulesConfig {
+    // Sem projeto, só as regras globais do usuário
+    fn load(root: Option<&Path>) -> RulesConfig {
+        let mut paths: Vec<PathBuf> = rules_dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().and_then(|e| e.to_str()) == Some(RULES_EXTENSION)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        if let Some(project_file) = root
+            .map(|root| root.join(PROJECT_RULES_FILE))
+            .filter(|path| path.is_file())
+        {
+            paths.push(project_file);
+        }
+
+        let mut config = RulesConfig::default();
+        for path in paths {
+            match load_rules_file(&path) {
+                Ok(file) => config.merge(file, &path),
+                Err(e) => log::warn!("Ignorando arquivo de regras inválido {:?}: {:#}", path, e),
+            }
+        }
+        config
+    }
+
+    fn merge(&mut self, file: RulesFile, path: &Path) {
+        self.disabled.extend(file.disabled);
+        self.severity.extend(file.severity);
+        for spec in file.rules {
+            let id = spec.id.clone();
+            match UserRule::new(spec) {
+                // Arquivos carregados depois (o do projeto) substituem regras com o mesmo id
+                Ok(rule) => {
+                    self.rules.retain(|existing| existing.id() != id);
+                    self.rules.push(rule);
+                }
+                Err(e) => log::warn!("Ignorando regra '{}' de {:?}: {:#}", id, path, e),
+            }
+        }
+    }
+
+    // Regras do usuário com o id de uma embutida a substituem
+    fn rules(&self) -> Vec<(&dyn Rule, bool)> {
+        let overridden: HashSet<&str> = self.rules.iter().map(|rule| rule.id()).collect();
+        BUILTIN_RULES
+            .iter()
+            .filter(|rule| !overridden.contains(rule.id))
+            .map(|rule| (rule as &dyn Rule, true))
+            .chain(self.rules.iter().map(|rule| (rule as &dyn Rule, false)))
+            .collect()
+    }
+
+    fn severity_of(&self, rule: &dyn Rule) -> SuggestionSeverity {
+        self.severity
+            .get(rule.id())
+            .copied()
+            .unwrap_or_else(|| rule.severity())
+    }
+}
+
+fn load_rules_file(path: &Path) -> Result<RulesFile> {
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content).context("TOML inválido")
+}
+
+// Roda as regras ativas do projeto; mais graves primeiro
+pub fn evaluate(input: &RuleInput) -> Vec<Suggestion> {
+    let config = RulesConfig::load(Some(input.root));
+
+    let mut suggestions: Vec<Suggestion> = config
+        .rules()
+        .into_iter()
+        .filter(|(rule, _)| !config.disabled.contains(rule.id()))
+        .filter_map(|(rule, builtin)| {
+            let finding = rule.check(input)?;
+            Some(Suggestion {
+                id: rule.id().to_string(),
+                severity: config.severity_of(rule),
+                message: finding.message,
+                rationale: finding.rationale,
+                evidence: finding.evidence,
+                builtin,
+            })
+        })
+        .collect();
+    suggestions.sort_by_key(|suggestion| Reverse(suggestion.severity));
+    suggestions
+}
+
+pub fn list_rules(root: Option<&Path>) -> Vec<SuggestionRuleInfo> {
+    let config = RulesConfig::load(root);
+    config
+        .rules()
+        .into_iter()
+        .map(|(rule, builtin)| SuggestionRuleInfo {
+            id: rule.id().to_string(),
+            description: rule.description().to_string(),
+            severity: config.severity_of(rule),
+            enabled: !config.disabled.contains(rule.id()),
+            builtin,
+        })
+        .collect()
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Glob inválido: {}", pattern))?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+fn detected<'a>(input: &'a RuleInput, name: &str) -> Option<&'a DetectedTechnology> {
+    input
+        .stack
+        .iter()
+        .find(|technology| technology.name.eq_ignore_ascii_case(name))
+}
+
+fn has_category(input: &RuleInput, category: TechnologyCategory) -> bool {
+    input.stack.iter().any(|t| t.category == category)
+}
+
+fn file_name(relative: &str) -> &str {
+    relative.rsplit('/').next().unwrap_or(relative)
+}
+
+fn root_files<'a>(input: &'a RuleInput) -> impl Iterator<Item = &'a str> {
+    input
+        .files
+        .iter()
+        .map(String::as_str)
+        .filter(|file| !file.contains('/'))
+}
+
+fn code_lines(input: &RuleInput, languages: &[&str]) -> usize {
+    input
+        .languages
+        .iter()
+        .filter(|l| languages.contains(&l.language.as_str()))
+        .map(|l| l.code)
+        .sum()
+}
+
+// Convenções de teste das linguagens suportadas
+fn is_test_path(relative: &str) -> bool {
+    let name = file_name(relative).to_lowercase();
+    let in_test_dir = relative
+        .split('/')
+        .rev()
+        .skip(1)
+        .any(|dir| matches!(dir, "tests" | "test" | "__tests__" | "spec"));
+    in_test_dir
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.ends_with("_test.go")
+        || name.ends_with("_test.py")
+        || (name.starts_with("test_") && name.ends_with(".py"))
+        || name.ends_with("_spec.rb")
+        || name.ends_with("test.java")
+}
+
+fn committed_secrets(input: &RuleInput) -> Option<Finding> {
+    if input.secrets.is_empty() {
+        return None;
+    }
+    Some(Finding {
+        message: format!(
+            "Remova {} possível(is) segredo(s) do código e revogue as credenciais expostas.",
+            input.secrets.len()
+        ),
+        rationale: "Credenciais versionadas ficam no histórico do git mesmo depois de apagadas \
+                    e podem ser usadas por qualquer pessoa com acesso ao repositório."
+            .to_string(),
+        evidence: input
+            .secrets
+            .iter()
+            .take(MAX_EVIDENCE)
+            .map(|s| format!("{}:{} ({})", s.file, s.line, s.rule))
+            .collect(),
+    })
+}
+
+fn vulnerable_dependencies(input: &RuleInput) -> Option<Finding> {
+    let audit = input.vulnerability_audit?;
+    if audit.vulnerabilities.is_empty() {
+        return None;
+    }
+    Some(Finding {
+        message: format!(
+            "Atualize as dependências com {} vulnerabilidade(s) conhecida(s).",
+            audit.vulnerabilities.len()
+        ),
+        rationale: "Versões com advisories publicados são alvo fácil; a maioria já tem versão \
+                    corrigida disponível."
+            .to_string(),
+        evidence: audit
+            .vulnerabilities
+            .iter()
+            .take(MAX_EVIDENCE)
+            .map(|v| format!("{} {} ({}) em {}", v.package, v.version, v.id, v.lockfile))
+            .collect(),
+    })
+}
+
+fn missing_tests(input: &RuleInput) -> Option<Finding> {
+    let code_files = input
+        .files
+        .iter()
+        .filter(|file| Grammar::detect(Path::new(file.as_str())).is_some())
+        .count();
+    if code_files <= 20
+        || has_category(input, TechnologyCategory::TestRunner)
+        || input.files.iter().any(|file| is_test_path(file))
+    {
+        return None;
+    }
+    Some(Finding {
+        message: "Considere adicionar testes automatizados para garantir qualidade do código."
+            .to_string(),
+        rationale: format!(
+            "Nenhum arquivo de teste ou test runner encontrado em {} arquivos de código.",
+            code_files
+        ),
+        evidence: Vec::new(),
+    })
+}
+
+fn missing_readme(input: &RuleInput) -> Option<Finding> {
+    if root_files(input).any(|file| file.to_lowercase().starts_with("readme")) {
+        return None;
+    }
+    Some(Finding {
+        message: "Adicione um README.md para documentar o projeto e facilitar onboarding."
+            .to_string(),
+        rationale: "Não há README na raiz do projeto.".to_string(),
+        evidence: Vec::new(),
+    })
+}
+
+fn missing_gitignore(input: &RuleInput) -> Option<Finding> {
+    if input.root.join(".gitignore").is_file() {
+        return None;
+    }
+    Some(Finding {
+        message:
+            "Adicione um .gitignore para não versionar builds, dependências e arquivos locais."
+                .to_string(),
+        rationale: "Não há .gitignore na raiz do projeto.".to_string(),
+        evidence: Vec::new(),
+    })
+}
+
+fn missing_lockfile(input: &RuleInput) -> Option<Finding> {
+    let lockfiles: HashSet<&str> = input.files.iter().map(|f| file_name(f)).collect();
+    let mut manifests: Vec<&str> = input
+        .dependencies
+        .iter()
+        .filter(|d| {
+            !dependencies::lockfile_names(&d.ecosystem)
+                .iter()
+                .any(|name| lockfiles.contains(name))
+        })
+        .map(|d| d.manifest.as_str())
+        .collect();
+    manifests.sort();
+    manifests.dedup();
+    if manifests.is_empty() {
+        return None;
+    }
+    Some(Finding {
+        message: "Versione o lockfile para que builds e auditorias usem as mesmas versões."
+            .to_string(),
+        rationale: "Sem lockfile cada instalação pode resolver versões diferentes das \
+                    dependências e a auditoria de vulnerabilidades não sabe o que está em uso."
+            .to_string(),
+        evidence: manifests
+            .into_iter()
+            .take(MAX_EVIDENCE)
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+fn missing_linter(input: &RuleInput) -> Option<Finding> {
+    let mut evidence: Vec<String> = Vec::new();
+    let has_file = |names: &[&str]| {
+        input
+            .files
+            .iter()
+            .any(|file| names.contains(&file_name(file)))
+    };
+
+    let js_lines = code_lines(input, &["JavaScript", "TypeScript", "TSX", "JSX"]);
+    if js_lines > 0
+        && detected(input, "ESLint").is_none()
+        && !has_file(&["biome.json", "biome.jsonc", ".jshintrc"])
+    {
+        evidence.push(format!(
+            "{} linhas de JavaScript/TypeScript sem ESLint ou Biome",
+            js_lines
+        ));
+    }
+
+    let python_lines = code_lines(input, &["Python"]);
+    let python_linter = detected(input, "Ruff").is_some()
+        || has_file(&[".flake8", ".pylintrc", "pylintrc"])
+        || input
+            .dependencies
+            .iter()
+            .any(|d| matches!(d.name.as_str(), "flake8" | "pylint" | "ruff"));
+    if python_lines > 0 && !python_linter {
+        evidence.push(format!(
+            "{} linhas de Python sem Ruff, Flake8 ou Pylint",
+            python_lines
+        ));
+    }
+
+    if evidence.is_empty() {
+        return None;
+    }
+    Some(Finding {
+        message: "Configure um linter para manter consistência de código e identificar problemas."
+            .to_string(),
+        rationale: "Linters pegam bugs comuns e divergências de estilo antes do code review."
+            .to_string(),
+        evidence,
+    })
+}
+
+fn typescript_migration(input: &RuleInput) -> Option<Finding> {
+    let js_files: Vec<&String> = input
+        .files
+        .iter()
+        .filter(|file| {
+            let name = file_name(file);
+            (name.ends_with(".js") || name.ends_with(".jsx")) && !name.contains(".config.")
+        })
+        .collect();
+    let has_typescript = code_lines(input, &["TypeScript", "TSX"]) > 0
+        || detected(input, "TypeScript compiler").is_some();
+    if js_files.len() <= 30 || has_typescript {
+        return None;
+    }
+    Some(Finding {
+        message: "Migrar para TypeScript pode melhorar a manutenibilidade e prevenir bugs."
+            .to_string(),
+        rationale: format!(
+            "{} arquivos JavaScript e nenhum arquivo TypeScript.",
+            js_files.len()
+        ),
+        evidence: js_files.into_iter().take(MAX_EVIDENCE).cloned().collect(),
+    })
+}
+
+fn missing_ci(input: &RuleInput) -> Option<Finding> {
+    if input.files.len() <= 50 || has_category(input, TechnologyCategory::Ci) {
+        return None;
+    }
+    Some(Finding {
+        message: "Configure CI/CD (GitHub Actions, GitLab CI) para automatizar testes e deploys."
+            .to_string(),
+        rationale: "Nenhuma configuração de integração contínua encontrada.".to_string(),
+        evidence: Vec::new(),
+    })
+}
+
+fn missing_license(input: &RuleInput) -> Option<Finding> {
+    let has_license = root_files(input).any(|file| {
+        let name = file.to_lowercase();
+        name.starts_with("license") || name.starts_with("licence") || name.starts_with("copying")
+    });
+    if has_license {
+        return None;
+    }
+    Some(Finding {
+        message: "Adicione um arquivo LICENSE definindo como o código pode ser usado.".to_string(),
+        rationale: "Sem licença explícita, ninguém tem permissão legal para reutilizar o código."
+            .to_string(),
+        evidence: Vec::new(),
+    })
+}
+
+fn large_project(input: &RuleInput) -> Option<Finding> {
+    // Workspaces (Cargo, npm, go.work) já são modularizados
+    let is_workspace = input
+        .dependencies
+        .iter()
+        .any(|d| d.workspace_member.is_some());
+    if input.files.len() <= 500 || is_workspace {
+        return None;
+    }
+    Some(Finding {
+        message: "Projeto grande detectado. Considere modularizar o código em pacotes menores."
+            .to_string(),
+        rationale: format!(
+            "{} arquivos em um único pacote, sem workspace configurado.",
+            input.files.len()
+        ),
+        evidence: Vec::new(),
+    })
+}
@@ -0,0 +1,384 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::types::SecretFinding;
+
+struct SecretRule {
+    id: &'static str,
+    description: &'static str,
+    // O grupo 1 é o trecho sensível que vai ser mascarado/removido
+    pattern: &'static str,
+}
+
+// Regras específicas primeiro: em trechos sobrepostos vale a primeira que casar
+const RULES: &[SecretRule] = &[
+    SecretRule {
+        id: "private_key",
+        description: "Chave privada",
+        pattern: r"(?s)(-----BEGIN[ A-Z0-9]*PRIVATE KEY(?: BLOCK)?-----.*?(?:-----END[ A-Z0-9]*PRIVATE KEY(?: BLOCK)?-----|\z))",
+    },
+    SecretRule {
+        id: "aws_access_key",
+        description: "AWS Access Key ID",
+        pattern: r"\b((?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16})\b",
+    },
+    SecretRule {
+        id: "aws_secret_key",
+        description: "AWS Secret Access Key",
+        pattern: r#"(?i)aws[_\-.]?secret[_\-.]?(?:access[_\-.]?)?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+]{40})"#,
+    },
+    SecretRule {
+        id: "github_token",
+        description: "Token do GitHub",
+        pattern: r"\b((?:ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{22,255})\b",
+    },
+    SecretRule {
+        id: "gitlab_token",
+        description: "Token do GitLab",
+        pattern: r"\b(glpat-[A-Za-z0-9_\-]{20,})",
+    },
+    SecretRule {
+        id: "slack_token",
+        description: "Token do Slack",
+        pattern: r"\b(xox[abposr]-[A-Za-z0-9\-]{10,})",
+    },
+    SecretRule {
+        id: "anthropic_key",
+        description: "API key da Anthropic",
+        pattern: r"\b(sk-ant-[A-Za-z0-9_\-]{20,})",
+    },
+    SecretRule {
+        id: "openai_key",
+        description: "API key da OpenAI",
+        pattern: r"\b(sk-(?:proj-|svcacct-|admin-)?[A-Za-z0-9_\-]{20,})",
+    },
+    SecretRule {
+        id: "google_api_key",
+        description: "API key do Google",
+        pattern: r"\b(AIza[0-9A-Za-z_\-]{35})",
+    },
+    SecretRule {
+        id: "stripe_key",
+        description: "Chave do Stripe",
+        pattern: r"\b((?:sk|rk)_(?:live|test)_[0-9A-Za-z]{20,})",
+    },
+    SecretRule {
+        id: "generic_secret",
+        description: "Senha ou token atribuído no código",
+        pattern: r#"(?i)(?:password|passwd|pwd|secret|token|api[_\-]?key|access[_\-]?key|auth[_\-]?key|private[_\-]?key)\w*["']?\s*[:=]\s*["'`]?([^\s"'`,;]{8,})"#,
+    },
+    SecretRule {
+        id: "high_entropy",
+        description: "String de alta entropia",
+        pattern: r#"["'`]([A-Za-z0-9+/_\-]{32,}={0,2})["'`]"#,
+    },
+];
+
+const ENV_SECRET_KEYWORDS: &[&str] = &[
+    "SECRET",
+    "TOKEN",
+    "KEY",
+    "PASSWORD",
+    "PASSWD",
+    "PWD",
+    "CREDENTIAL",
+    "PRIVATE",
+    "AUTH",
+    "DSN",
+];
+
+const PLACEHOLDER_HINTS: &[&str] = &[
+    "your",
+    "sua",
+    "seu",
+    "aqui",
+    "here",
+    "example",
+    "exemplo",
+    "placeholder",
+    "changeme",
+    "change_me",
+    "xxx",
+    "***",
+    "dummy",
+    "sample",
+    "todo",
+    "redacted",
+];
+
+// Arquivos com hashes de integridade, minificados ou binários em texto
+const HIGH_ENTROPY_SKIP: &[&str] = &[
+    ".lock",
+    "lock.json",
+    "lock.yaml",
+    "go.sum",
+    ".min.js",
+    ".min.css",
+    ".map",
+    ".svg",
+];
+
+const HIGH_ENTROPY_THRESHOLD: f64 = 4.5;
+const GENERIC_ENTROPY_THRESHOLD: f64 = 3.0;
+
+struct SecretMatch {
+    rule: &'static str,
+    description: &'static str,
+    start: usize,
+    end: usize,
+}
+
+fn compiled_rules() -> &'static [(&'static SecretRule, Regex)] {
+    static COMPILED: OnceLock<Vec<(&'static SecretRule, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        RULES
+            .iter()
+            .map(|rule| (rule, Regex::new(rule.pattern).unwrap()))
+            .collect()
+    })
+}
+
+pub fn is_env_file(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .is_some_and(|name| name == ".env" || name.starts_with(".env.") || name.ends_with(".env"))
+}
+
+fn is_env_template(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .is_some_and(|name| {
+            ["example", "sample", "template", "dist"]
+                .iter()
+                .any(|s| name.contains(s))
+        })
+}
+
+fn find_secrets(path: &Path, content: &str) -> Vec<SecretMatch> {
+    let file_name = path.to_string_lossy().to_lowercase();
+    let skip_entropy = HIGH_ENTROPY_SKIP
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix));
+    let mut matches: Vec<SecretMatch> = Vec::new();
+
+    for (rule, regex) in compiled_rules() {
+        if rule.id == "high_entropy" && skip_entropy {
+            continue;
+        }
+
+        for captures in regex.captures_iter(content) {
+            let Some(secret) = captures.get(1) else {
+                continue;
+            };
+            let accepted = match rule.id {
+                "generic_secret" => looks_like_secret_value(secret.as_str()),
+                "high_entropy" => looks_random(secret.as_str(), &content[..secret.start()]),
+                _ => true,
+            };
+            let overlaps = matches
+                .iter()
+                .any(|m| secret.start() < m.end && m.start < secret.end());
+
+            if accepted && !overlaps {
+                matches.push(SecretMatch {
+                    rule: rule.id,
+                    description: rule.description,
+                    start: secret.start(),
+                    end: secret.end(),
+                });
+            }
+        }
+    }
+
+    if is_env_file(path) {
+        find_env_values(path, content, &mut matches);
+    }
+
+    matches.sort_by_key(|m| m.start);
+    matches
+}
+
+// Em arquivos .env qualquer valor de chave sensível conta; nos modelos (.env.example)
+// só valores que não parecem placeholder
+fn find_env_values(path: &Path, content: &str, matches: &mut Vec<SecretMatch>) {
+    let template = is_env_template(path);
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let key = key
+            .trim()
+            .trim_start_matches("export ")
+            .trim()
+            .to_uppercase();
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        if value.is_empty()
+            || !ENV_SECRET_KEYWORDS.iter().any(|k| key.contains(k))
+            || (template && is_placeholder(value))
+        {
+            continue;
+        }
+
+        let Some(relative_start) = line
+            .find('=')
+            .and_then(|equals| Some(equals + 1 + line[equals + 1..].find(value)?))
+        else {
+            continue;
+        };
+        let start = line_start + relative_start;
+        let end = start + value.len();
+        if matches.iter().any(|m| start < m.end && m.start < end) {
+            continue;
+        }
+        matches.push(SecretMatch {
+            rule: "env_value",
+            description: "Valor sensível em arquivo .env",
+            start,
+            end,
+        });
+    }
+}
+
+fn is_placeholder(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    lower.starts_with('<')
+        || lower.starts_with('$')
+        || PLACEHOLDER_HINTS.iter().any(|hint| lower.contains(hint))
+        || value
+            .chars()
+            .all(|c| c == value.chars().next().unwrap_or(c))
+}
+
+// Descarta tipos e expressões (`Option<String>`, `config.token`, `get_token()`)
+fn looks_like_secret_value(value: &str) -> bool {
+    if value
+        .chars()
+        .any(|c| matches!(c, '(' | ')' | '<' | '>' | '[' | ']' | '{' | '}' | '&' | '|'))
+    {
+        return false;
+    }
+    let is_path_expression = value.contains('.')
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    // Variáveis em snake_case (`secrets: found_secrets`, `token = access_token`)
+    let is_identifier =
+        value.contains('_') && value.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+    if is_path_expression || is_identifier || is_placeholder(value) {
+        return false;
+    }
+
+    let classes = [
+        value.chars().any(|c| c.is_ascii_lowercase()),
+        value.chars().any(|c| c.is_ascii_uppercase()),
+        value.chars().any(|c| c.is_ascii_digit()),
+        value.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ];
+    classes.iter().filter(|c| **c).count() >= 2
+        && shannon_entropy(value) >= GENERIC_ENTROPY_THRESHOLD
+}
+
+fn looks_random(value: &str, before: &str) -> bool {
+    // data:image/png;base64,... e hashes de integridade (sha512-...)
+    if before.ends_with("base64,") || (value.starts_with("sha") && value.contains('-')) {
+        return false;
+    }
+    value.chars().any(|c| c.is_ascii_lowercase())
+        && value.chars().any(|c| c.is_ascii_uppercase())
+        && value.chars().any(|c| c.is_ascii_digit())
+        && shannon_entropy(value) >= HIGH_ENTROPY_THRESHOLD
+}
+
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let length = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// Mostra só o começo do segredo, para o usuário reconhecer sem expor o valor
+fn mask(secret: &str) -> String {
+    let first_line = secret.lines().next().unwrap_or_default();
+    if first_line.starts_with("-----BEGIN") {
+        return first_line.to_string();
+    }
+    let visible: String = secret.chars().take(4).collect();
+    format!(
+        "{}{}",
+        visible,
+        "*".repeat(secret.chars().count().saturating_sub(4).min(12))
+    )
+}
+
+pub fn scan(relative: &Path, content: &str) -> Vec<SecretFinding> {
+    let file = relative.to_string_lossy().replace('\\', "/");
+    let mut findings: Vec<SecretFinding> = find_secrets(relative, content)
+        .into_iter()
+        .map(|m| {
+            let before = &content[..m.start];
+            let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            SecretFinding {
+                rule: m.rule.to_string(),
+                description: m.description.to_string(),
+                file: file.clone(),
+                line: before.matches('\n').count() + 1,
+                column: before[line_start..].chars().count() + 1,
+                preview: mask(&content[m.start..m.end]),
+            }
+        })
+        .collect();
+
+    // Um .env real dentro do workspace (não ignorado pelo git) já é um problema por si só
+    if is_env_file(relative) && !is_env_template(relative) {
+        findings.insert(
+            0,
+            SecretFinding {
+                rule: "env_file".to_string(),
+                description: "Arquivo .env não ignorado pelo git".to_string(),
+                file,
+                line: 1,
+                column: 1,
+                preview: String::new(),
+            },
+        );
+    }
+
+    findings
+}
+
+// Substitui cada segredo encontrado por [REDACTED:<regra>] antes de o conteúdo sair da máquina
+pub fn redact(relative: &Path, content: &str) -> String {
+    let matches = find_secrets(relative, content);
+    if matches.is_empty() {
+        return content.to_string();
+    }
+
+    let mut redacted = String::with_capacity(content.len());
+    let mut position = 0;
+    for m in matches {
+        redacted.push_str(&content[position..m.start]);
+        redacted.push_str(&format!("[REDACTED:{}]", m.rule));
+        position = m.end;
+    }
+    redacted.push_str(&content[position..]);
+    redacted
+}
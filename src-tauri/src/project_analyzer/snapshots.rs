@@ -0,0 +1,483 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::cache;
+use crate::types::{
+    AnalysisDiff, AnalysisFinding, AnalysisSnapshot, Dependency, DependencyChange, FindingKind,
+    LanguageDelta, ProjectAnalysis, SnapshotFile, SnapshotInfo,
+};
+
+pub const SNAPSHOTS_ENV: &str = "RZONEHUB_SNAPSHOTS";
+
+// Por workspace; os mais antigos são apagados a cada novo snapshot
+const MAX_SNAPSHOTS: usize = 200;
+// A partir daqui o arquivo conta como grande (mesmo limite em que a análise deixa de lê-lo)
+const LARGE_FILE_SIZE: u64 = super::MAX_FILE_SIZE;
+const INFO_EXTENSION: &str = "info";
+
+pub fn default_dir() -> Option<PathBuf> {
+    std::env::var_os(SNAPSHOTS_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("RZOneHub").join("snapshots")))
+}
+
+// Grava a análise em `<dir>/<workspace>/<id>.json`. O id começa com a data em UTC (a ordem
+// alfabética é a cronológica) e termina com o workspace, então basta ele para achar o
+// arquivo. O resumo e o conteúdo dos arquivos ficam de fora para o snapshot não crescer.
+// Ao lado vai `<id>.info` só com os metadados, para a listagem não abrir o snapshot inteiro
+pub fn save(root: &Path, analysis: &ProjectAnalysis) -> Result<()> {
+    match default_dir() {
+        Some(dir) => save_in(&dir, root, analysis),
+        None => Ok(()),
+    }
+}
+
+fn save_in(dir: &Path, root: &Path, analysis: &ProjectAnalysis) -> Result<()> {
+    let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let key = cache::workspace_key(&canonical);
+    let now = Utc::now();
+    let id = format!("{}-{}", now.format("%Y%m%dT%H%M%S%3fZ"), key);
+
+    let mut analysis = analysis.clone();
+    analysis.summary.clear();
+    for file in &mut analysis.largest_files {
+        file.content = None;
+    }
+    let files = std::mem::take(&mut analysis.scanned_files);
+
+    let snapshot = AnalysisSnapshot {
+        id: id.clone(),
+        root: canonical.to_string_lossy().to_string(),
+        created_at: now.to_rfc3339(),
+        files,
+        analysis,
+    };
+    let workspace_dir = dir.join(&key);
+    fs::create_dir_all(&workspace_dir)?;
+    let path = workspace_dir.join(format!("{}.json", id));
+    cache::write_atomic(&path, &serde_json::to_vec(&snapshot)?)?;
+    cache::write_atomic(
+        &path.with_extension(INFO_EXTENSION),
+        &serde_json::to_vec(&info(&snapshot))?,
+    )?;
+
+    prune(&workspace_dir)
+}
+
+// Arquivos da varredura com caminho relativo à raiz, em ordem alfabética
+pub fn snapshot_files(root: &Path, files: &[(PathBuf, u64)]) -> Vec<SnapshotFile> {
+    let mut files: Vec<SnapshotFile> = files
+        .iter()
+        .map(|(path, size)| SnapshotFile {
+            path: path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            size: *size,
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn prune(workspace_dir: &Path) -> Result<()> {
+    let mut paths = snapshot_paths(workspace_dir);
+    if paths.len() > MAX_SNAPSHOTS {
+        paths.sort();
+        for path in &paths[..paths.len() - MAX_SNAPSHOTS] {
+            fs::remove_file(path)?;
+            let _ = fs::remove_file(path.with_extension(INFO_EXTENSION));
+        }
+    }
+    Ok(())
+}
+
+fn snapshot_paths(workspace_dir: &Path) -> Vec<PathBuf> {
+    match fs::read_dir(workspace_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Snapshots do workspace, do mais recente para o mais antigo
+pub fn list(root: &Path) -> Vec<SnapshotInfo> {
+    default_dir()
+        .map(|dir| list_in(&dir, root))
+        .unwrap_or_default()
+}
+
+fn list_in(dir: &Path, root: &Path) -> Vec<SnapshotInfo> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut snapshots: Vec<SnapshotInfo> = snapshot_paths(&dir.join(cache::workspace_key(&root)))
+        .iter()
+        .filter_map(|path| {
+            // Snapshots antigos não têm o `.info`: lê o arquivo inteiro só nesse caso
+            read_info(path)
+                .or_else(|_| read(path).map(|snapshot| info(&snapshot)))
+                .map_err(|e| log::warn!("Ignorando snapshot inválido {:?}: {:#}", path, e))
+                .ok()
+        })
+        .collect();
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    snapshots
+}
+
+// Snapshot mais recente do workspace (o primeiro da listagem)
+pub fn latest(root: &Path) -> Result<AnalysisSnapshot> {
+    let latest = list(root)
+        .into_iter()
+        .next()
+        .context("Nenhuma análise salva para este workspace; analise o projeto primeiro")?;
+    load(&latest.id)
+}
+
+pub fn load(id: &str) -> Result<AnalysisSnapshot> {
+    let dir = default_dir().context("Diretório de dados do usuário não encontrado")?;
+    load_in(&dir, id)
+}
+
+fn load_in(dir: &Path, id: &str) -> Result<AnalysisSnapshot> {
+    // O id vira caminho: só `<data>-<workspace>` com letras e dígitos
+    let (_, key) = id
+        .split_once('-')
+        .filter(|_| id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .ok_or_else(|| anyhow::anyhow!("Snapshot inválido: {}", id))?;
+    let path = dir.join(key).join(format!("{}.json", id));
+    if !path.is_file() {
+        anyhow::bail!("Snapshot não encontrado: {}", id);
+    }
+    read(&path)
+}
+
+fn read(path: &Path) -> Result<AnalysisSnapshot> {
+    let content = fs::read_to_string(path).context("Erro ao ler snapshot")?;
+    serde_json::from_str(&content).context("Snapshot corrompido")
+}
+
+fn read_info(path: &Path) -> Result<SnapshotInfo> {
+    let content = fs::read_to_string(path.with_extension(INFO_EXTENSION))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn info(snapshot: &AnalysisSnapshot) -> SnapshotInfo {
+    SnapshotInfo {
+        id: snapshot.id.clone(),
+        root: snapshot.root.clone(),
+        created_at: snapshot.created_at.clone(),
+        total_files: snapshot.analysis.total_files,
+        code_lines: snapshot.analysis.languages.iter().map(|l| l.code).sum(),
+        findings: findings(&snapshot.analysis).len(),
+    }
+}
+
+pub fn diff(from: &AnalysisSnapshot, to: &AnalysisSnapshot) -> AnalysisDiff {
+    let before: HashMap<&str, u64> = from
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.size))
+        .collect();
+    let after: HashMap<&str, u64> = to.files.iter().map(|f| (f.path.as_str(), f.size)).collect();
+    let files_added: Vec<SnapshotFile> = to
+        .files
+        .iter()
+        .filter(|f| !before.contains_key(f.path.as_str()))
+        .cloned()
+        .collect();
+    let files_removed: Vec<SnapshotFile> = from
+        .files
+        .iter()
+        .filter(|f| !after.contains_key(f.path.as_str()))
+        .cloned()
+        .collect();
+    // Arquivos novos já grandes e arquivos que cresceram além do limite
+    let mut new_large_files: Vec<SnapshotFile> = to
+        .files
+        .iter()
+        .filter(|f| {
+            f.size >= LARGE_FILE_SIZE
+                && before
+                    .get(f.path.as_str())
+                    .map_or(true, |size| *size < LARGE_FILE_SIZE)
+        })
+        .cloned()
+        .collect();
+    new_large_files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let languages = language_deltas(&from.analysis, &to.analysis);
+    let code_delta = languages.iter().map(|l| l.code_delta).sum();
+
+    let (dependencies_added, dependencies_removed, dependencies_changed) =
+        dependency_changes(&from.analysis.dependencies, &to.analysis.dependencies);
+
+    let before_findings = findings(&from.analysis);
+    let after_findings = findings(&to.analysis);
+    let new_findings = after_findings
+        .iter()
+        .filter(|(key, _)| !before_findings.contains_key(*key))
+        .map(|(_, finding)| finding.clone())
+        .collect();
+    let fixed_findings = before_findings
+        .into_iter()
+        .filter(|(key, _)| !after_findings.contains_key(key))
+        .map(|(_, finding)| finding)
+        .collect();
+
+    AnalysisDiff {
+        from: info(from),
+        to: info(to),
+        files_added,
+        files_removed,
+        new_large_files,
+        languages,
+        code_delta,
+        dependencies_added,
+        dependencies_removed,
+        dependencies_changed,
+        new_findings,
+        fixed_findings,
+    }
+}
+
+// Só linguagens que mudaram, das maiores variações para as menores
+fn language_deltas(from: &ProjectAnalysis, to: &ProjectAnalysis) -> Vec<LanguageDelta> {
+    let mut totals: BTreeMap<&str, [usize; 4]> = BTreeMap::new();
+    for language in &from.languages {
+        let entry = totals.entry(language.language.as_str()).or_default();
+        entry[0] = language.files;
+        entry[2] = language.code;
+    }
+    for language in &to.languages {
+        let entry = totals.entry(language.language.as_str()).or_default();
+        entry[1] = language.files;
+        entry[3] = language.code;
+    }
+
+    let mut deltas: Vec<LanguageDelta> = totals
+        .into_iter()
+        .filter(
+            |(_, [files_before, files_after, code_before, code_after])| {
+                files_before != files_after || code_before != code_after
+            },
+        )
+        .map(
+            |(language, [files_before, files_after, code_before, code_after])| LanguageDelta {
+                language: language.to_string(),
+                files_before,
+                files_after,
+                code_before,
+                code_after,
+                code_delta: code_after as i64 - code_before as i64,
+            },
+        )
+        .collect();
+    deltas.sort_by(|a, b| {
+        b.code_delta
+            .abs()
+            .cmp(&a.code_delta.abs())
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    deltas
+}
+
+fn dependency_changes(
+    from: &[Dependency],
+    to: &[Dependency],
+) -> (Vec<Dependency>, Vec<Dependency>, Vec<DependencyChange>) {
+    let key = |d: &Dependency| (d.ecosystem.clone(), d.name.clone(), d.manifest.clone());
+    let version = |d: &Dependency| d.resolved_version.clone().or_else(|| d.requirement.clone());
+    let before: BTreeMap<_, &Dependency> = from.iter().map(|d| (key(d), d)).collect();
+    let after: BTreeMap<_, &Dependency> = to.iter().map(|d| (key(d), d)).collect();
+
+    let added = after
+        .iter()
+        .filter(|(key, _)| !before.contains_key(*key))
+        .map(|(_, d)| (*d).clone())
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|(key, _)| !after.contains_key(*key))
+        .map(|(_, d)| (*d).clone())
+        .collect();
+    let changed = after
+        .iter()
+        .filter_map(|(key, new)| {
+            let old = before.get(key)?;
+            let (old_version, new_version) = (version(old), version(new));
+            (old_version != new_version).then(|| DependencyChange {
+                name: new.name.clone(),
+                ecosystem: new.ecosystem.clone(),
+                manifest: new.manifest.clone(),
+                before: old_version,
+                after: new_version,
+            })
+        })
+        .collect();
+    (added, removed, changed)
+}
+
+// Achados indexados por uma chave estável entre análises. Segredos usam regra, arquivo e o
+// trecho mascarado, não a linha: código inserido acima não muda o achado. Trechos iguais
+// no mesmo arquivo se distinguem pela ordem em que aparecem
+fn findings(analysis: &ProjectAnalysis) -> BTreeMap<(FindingKind, String), AnalysisFinding> {
+    let mut findings = BTreeMap::new();
+    for suggestion in &analysis.suggestion_details {
+        findings.insert(
+            (FindingKind::Suggestion, suggestion.id.clone()),
+            AnalysisFinding {
+                kind: FindingKind::Suggestion,
+                id: suggestion.id.clone(),
+                message: suggestion.message.clone(),
+                location: None,
+            },
+        );
+    }
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    for secret in &analysis.secrets {
+        let key = format!("{} {} {}", secret.rule, secret.file, secret.preview);
+        let occurrence = occurrences.entry(key.clone()).or_default();
+        *occurrence += 1;
+        findings.insert(
+            (FindingKind::Secret, format!("{} #{}", key, occurrence)),
+            AnalysisFinding {
+                kind: FindingKind::Secret,
+                id: secret.rule.clone(),
+                message: secret.description.clone(),
+                location: Some(format!("{}:{}", secret.file, secret.line)),
+            },
+        );
+    }
+    // Mesma vulnerabilidade em outra versão do pacote continua sendo o mesmo achado
+    for vulnerability in analysis
+        .vulnerability_audit
+        .iter()
+        .flat_map(|audit| &audit.vulnerabilities)
+    {
+        findings.insert(
+            (
+                FindingKind::Vulnerability,
+                format!("{} {}", vulnerability.id, vulnerability.package),
+            ),
+            AnalysisFinding {
+                kind: FindingKind::Vulnerability,
+                id: vulnerability.id.clone(),
+                message: format!(
+                    "{} {}: {}",
+                    vulnerability.package,
+                    vulnerability.version,
+                    vulnerability
+                        .summary
+                        .as_deref()
+                        .unwrap_or(&vulnerability.id)
+                ),
+                location: Some(vulnerability.lockfile.clone()),
+            },
+        );
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretFinding;
+
+    fn snapshot(id: &str, secrets: Vec<SecretFinding>) -> AnalysisSnapshot {
+        let mut analysis: ProjectAnalysis = serde_json::from_value(serde_json::json!({
+            "total_files": 1,
+            "total_directories": 0,
+            "file_types": [],
+            "largest_files": [],
+            "suggestions": [],
+            "summary": "",
+        }))
+        .unwrap();
+        analysis.secrets = secrets;
+        AnalysisSnapshot {
+            id: id.to_string(),
+            root: "/projeto".to_string(),
+            created_at: String::new(),
+            files: Vec::new(),
+            analysis,
+        }
+    }
+
+    fn secret(line: usize, preview: &str) -> SecretFinding {
+        SecretFinding {
+            rule: "github_token".to_string(),
+            description: "Token do GitHub".to_string(),
+            file: "src/config.rs".to_string(),
+            line,
+            column: 17,
+            preview: preview.to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_keeps_secret_that_only_moved_down() {
+        let from = snapshot("a", vec![secret(3, "ghp_************")]);
+        let to = snapshot(
+            "b",
+            vec![secret(4, "ghp_************"), secret(9, "ghp_************")],
+        );
+
+        let diff = diff(&from, &to);
+        assert!(diff.fixed_findings.is_empty());
+        // O mesmo trecho repetido mais abaixo é um segredo novo
+        assert_eq!(diff.new_findings.len(), 1);
+        assert_eq!(
+            diff.new_findings[0].location.as_deref(),
+            Some("src/config.rs:9")
+        );
+    }
+
+    #[test]
+    fn list_reads_info_headers_and_falls_back_to_full_snapshot() {
+        let base = std::env::temp_dir().join(format!(
+            "rzonehub-snapshots-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let project = base.join("project");
+        fs::create_dir_all(&project).unwrap();
+        let dir = base.join("snapshots");
+
+        let mut analysis = snapshot("", vec![secret(3, "ghp_************")]).analysis;
+        analysis.scanned_files = snapshot_files(&project, &[(project.join("main.rs"), 42)]);
+        save_in(&dir, &project, &analysis).unwrap();
+
+        let listed = list_in(&dir, &project);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].findings, 1);
+        let snapshot = load_in(&dir, &listed[0].id).unwrap();
+        assert_eq!(
+            snapshot.files,
+            [SnapshotFile {
+                path: "main.rs".to_string(),
+                size: 42
+            }]
+        );
+        let header =
+            snapshot_paths(&dir.join(cache::workspace_key(&project.canonicalize().unwrap())))[0]
+                .with_extension(INFO_EXTENSION);
+        assert!(header.is_file());
+
+        // Sem o `.info` (snapshot de versões anteriores) a listagem lê o snapshot inteiro
+        fs::remove_file(&header).unwrap();
+        let fallback = list_in(&dir, &project);
+        assert_eq!(fallback.len(), 1);
+        assert_eq!(fallback[0].id, listed[0].id);
+        assert_eq!(fallback[0].findings, listed[0].findings);
+        assert_eq!(fallback[0].total_files, snapshot.analysis.total_files);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,586 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::complexity::round;
+use super::dependencies::{CARGO, GO, NPM, PYPI};
+use crate::types::{Dependency, DetectedTechnology, LanguageStats, TechnologyCategory};
+
+// Abaixo disso o sinal é fraco demais para aparecer na análise
+const MIN_CONFIDENCE: f64 = 0.5;
+const MAX_EVIDENCE_PER_SIGNAL: usize = 3;
+// Arquivos lidos para sinais `Contains` (pom.xml, Gemfile, ...)
+const MAX_CONTENT_FILE_SIZE: u64 = 200_000;
+
+// Linguagens de dados/markup que não definem a stack
+const NON_STACK_LANGUAGES: &[&str] = &[
+    "JSON",
+    "YAML",
+    "TOML",
+    "XML",
+    "SVG",
+    "Markdown",
+    "Plain Text",
+];
+
+enum Signal {
+    // Dependência declarada em manifesto; nome terminado em `*` casa por prefixo
+    Dependency(&'static str, &'static str),
+    // Nome de arquivo em qualquer pasta (sem diferenciar maiúsculas)
+    File(&'static str),
+    // Arquivo dentro de uma pasta com o nome dado e terminado no sufixo
+    Under(&'static str, &'static str),
+    // Arquivo com esse nome contendo o trecho
+    Contains(&'static str, &'static str),
+}
+
+use Signal::{Contains, Dependency as Dep, File, Under};
+
+struct Rule {
+    name: &'static str,
+    category: TechnologyCategory,
+    signals: &'static [(Signal, f64)],
+}
+
+const fn rule(
+    name: &'static str,
+    category: TechnologyCategory,
+    signals: &'static [(Signal, f64)],
+) -> Rule {
+    Rule {
+        name,
+        category,
+        signals,
+    }
+}
+
+use TechnologyCategory::{BuildTool, Ci, Framework, PackageManager, TestRunner, Tooling};
+
+static RULES: &[Rule] = &[
+    // Frameworks e plataformas
+    rule(
+        "Tauri",
+        Framework,
+        &[
+            (Dep(CARGO, "tauri"), 0.9),
+            (File("tauri.conf.json"), 0.9),
+            (Dep(NPM, "@tauri-apps/api"), 0.7),
+            (Dep(NPM, "@tauri-apps/cli"), 0.6),
+        ],
+    ),
+    rule(
+        "Electron",
+        Framework,
+        &[
+            (Dep(NPM, "electron"), 0.9),
+            (File("electron-builder.yml"), 0.7),
+        ],
+    ),
+    rule("React", Framework, &[(Dep(NPM, "react"), 0.9)]),
+    rule(
+        "Next.js",
+        Framework,
+        &[
+            (Dep(NPM, "next"), 0.9),
+            (File("next.config.js"), 0.8),
+            (File("next.config.mjs"), 0.8),
+            (File("next.config.ts"), 0.8),
+        ],
+    ),
+    rule("Vue", Framework, &[(Dep(NPM, "vue"), 0.9)]),
+    rule(
+        "Nuxt",
+        Framework,
+        &[
+            (Dep(NPM, "nuxt"), 0.9),
+            (File("nuxt.config.ts"), 0.8),
+            (File("nuxt.config.js"), 0.8),
+        ],
+    ),
+    rule("Svelte", Framework, &[(Dep(NPM, "svelte"), 0.9)]),
+    rule(
+        "SvelteKit",
+        Framework,
+        &[
+            (Dep(NPM, "@sveltejs/kit"), 0.9),
+            (File("svelte.config.js"), 0.5),
+        ],
+    ),
+    rule(
+        "Angular",
+        Framework,
+        &[
+            (Dep(NPM, "@angular/core"), 0.9),
+            (File("angular.json"), 0.8),
+        ],
+    ),
+    rule("Express", Framework, &[(Dep(NPM, "express"), 0.85)]),
+    rule("NestJS", Framework, &[(Dep(NPM, "@nestjs/core"), 0.9)]),
+    rule(
+        "Tailwind CSS",
+        Framework,
+        &[
+            (Dep(NPM, "tailwindcss"), 0.8),
+            (File("tailwind.config.js"), 0.8),
+            (File("tailwind.config.ts"), 0.8),
+            (File("tailwind.config.cjs"), 0.8),
+        ],
+    ),
+    rule(
+        "Django",
+        Framework,
+        &[
+            (Dep(PYPI, "django"), 0.9),
+            (Contains("manage.py", "django"), 0.8),
+        ],
+    ),
+    rule("Flask", Framework, &[(Dep(PYPI, "flask"), 0.9)]),
+    rule("FastAPI", Framework, &[(Dep(PYPI, "fastapi"), 0.9)]),
+    rule(
+        "Spring Boot",
+        Framework,
+        &[
+            (Contains("pom.xml", "spring-boot"), 0.9),
+            (Contains("build.gradle", "org.springframework.boot"), 0.9),
+            (
+                Contains("build.gradle.kts", "org.springframework.boot"),
+                0.9,
+            ),
+        ],
+    ),
+    rule("Actix Web", Framework, &[(Dep(CARGO, "actix-web"), 0.9)]),
+    rule("Axum", Framework, &[(Dep(CARGO, "axum"), 0.9)]),
+    rule("Rocket", Framework, &[(Dep(CARGO, "rocket"), 0.9)]),
+    rule(
+        "Gin",
+        Framework,
+        &[(Dep(GO, "github.com/gin-gonic/gin"), 0.9)],
+    ),
+    rule(
+        "Echo",
+        Framework,
+        &[(Dep(GO, "github.com/labstack/echo*"), 0.9)],
+    ),
+    rule(
+        "Ruby on Rails",
+        Framework,
+        &[
+            (Contains("Gemfile", "\"rails\""), 0.9),
+            (Contains("Gemfile", "'rails'"), 0.9),
+            (Under("config", "routes.rb"), 0.5),
+        ],
+    ),
+    rule(
+        "Laravel",
+        Framework,
+        &[
+            (Contains("composer.json", "laravel/framework"), 0.9),
+            (File("artisan"), 0.5),
+        ],
+    ),
+    rule(
+        "ASP.NET Core",
+        Framework,
+        &[(Contains("*.csproj", "Microsoft.AspNetCore"), 0.9)],
+    ),
+    // Build
+    rule(
+        "Vite",
+        BuildTool,
+        &[
+            (Dep(NPM, "vite"), 0.8),
+            (File("vite.config.ts"), 0.9),
+            (File("vite.config.js"), 0.9),
+            (File("vite.config.mjs"), 0.9),
+        ],
+    ),
+    rule(
+        "Webpack",
+        BuildTool,
+        &[(Dep(NPM, "webpack"), 0.8), (File("webpack.config.js"), 0.9)],
+    ),
+    rule("esbuild", BuildTool, &[(Dep(NPM, "esbuild"), 0.7)]),
+    rule(
+        "TypeScript compiler",
+        BuildTool,
+        &[(Dep(NPM, "typescript"), 0.7), (File("tsconfig.json"), 0.9)],
+    ),
+    rule(
+        "Maven",
+        BuildTool,
+        &[(File("pom.xml"), 0.95), (File("mvnw"), 0.8)],
+    ),
+    rule(
+        "Gradle",
+        BuildTool,
+        &[
+            (File("build.gradle"), 0.95),
+            (File("build.gradle.kts"), 0.95),
+            (File("gradlew"), 0.8),
+        ],
+    ),
+    rule("CMake", BuildTool, &[(File("CMakeLists.txt"), 0.95)]),
+    rule("Make", BuildTool, &[(File("Makefile"), 0.7)]),
+    // Gerenciadores de pacotes
+    rule(
+        "Cargo",
+        PackageManager,
+        &[(File("Cargo.toml"), 0.95), (File("Cargo.lock"), 0.9)],
+    ),
+    rule(
+        "npm",
+        PackageManager,
+        &[
+            (File("package-lock.json"), 0.95),
+            (File("npm-shrinkwrap.json"), 0.95),
+            // Sem lockfile o package.json sozinho não decide entre npm, Yarn e pnpm
+            (File("package.json"), 0.4),
+        ],
+    ),
+    rule(
+        "Yarn",
+        PackageManager,
+        &[(File("yarn.lock"), 0.95), (File(".yarnrc.yml"), 0.8)],
+    ),
+    rule(
+        "pnpm",
+        PackageManager,
+        &[
+            (File("pnpm-lock.yaml"), 0.95),
+            (File("pnpm-workspace.yaml"), 0.8),
+        ],
+    ),
+    rule(
+        "Bun",
+        PackageManager,
+        &[(File("bun.lockb"), 0.95), (File("bun.lock"), 0.95)],
+    ),
+    rule("Go modules", PackageManager, &[(File("go.mod"), 0.95)]),
+    rule(
+        "pip",
+        PackageManager,
+        &[
+            (File("requirements.txt"), 0.8),
+            (File("requirements-dev.txt"), 0.7),
+        ],
+    ),
+    rule(
+        "Poetry",
+        PackageManager,
+        &[
+            (File("poetry.lock"), 0.95),
+            (Contains("pyproject.toml", "[tool.poetry]"), 0.9),
+        ],
+    ),
+    rule("uv", PackageManager, &[(File("uv.lock"), 0.95)]),
+    rule(
+        "Pipenv",
+        PackageManager,
+        &[(File("Pipfile"), 0.9), (File("Pipfile.lock"), 0.95)],
+    ),
+    rule("Composer", PackageManager, &[(File("composer.json"), 0.9)]),
+    rule("Bundler", PackageManager, &[(File("Gemfile"), 0.9)]),
+    // Testes
+    rule(
+        "Jest",
+        TestRunner,
+        &[
+            (Dep(NPM, "jest"), 0.9),
+            (File("jest.config.js"), 0.9),
+            (File("jest.config.ts"), 0.9),
+        ],
+    ),
+    rule(
+        "Vitest",
+        TestRunner,
+        &[
+            (Dep(NPM, "vitest"), 0.9),
+            (File("vitest.config.ts"), 0.9),
+            (File("vitest.config.js"), 0.9),
+        ],
+    ),
+    rule(
+        "Mocha",
+        TestRunner,
+        &[(Dep(NPM, "mocha"), 0.9), (File(".mocharc.json"), 0.8)],
+    ),
+    rule(
+        "Playwright",
+        TestRunner,
+        &[
+            (Dep(NPM, "@playwright/test"), 0.9),
+            (File("playwright.config.ts"), 0.9),
+        ],
+    ),
+    rule(
+        "Cypress",
+        TestRunner,
+        &[
+            (Dep(NPM, "cypress"), 0.9),
+            (File("cypress.config.ts"), 0.9),
+            (File("cypress.config.js"), 0.9),
+        ],
+    ),
+    rule(
+        "pytest",
+        TestRunner,
+        &[
+            (Dep(PYPI, "pytest"), 0.9),
+            (File("pytest.ini"), 0.9),
+            (File("conftest.py"), 0.8),
+            (Contains("pyproject.toml", "[tool.pytest"), 0.9),
+        ],
+    ),
+    rule("cargo test", TestRunner, &[(Under("tests", ".rs"), 0.8)]),
+    rule("go test", TestRunner, &[(File("*_test.go"), 0.9)]),
+    rule(
+        "JUnit",
+        TestRunner,
+        &[
+            (Contains("pom.xml", "junit"), 0.9),
+            (Contains("build.gradle", "junit"), 0.9),
+            (Contains("build.gradle.kts", "junit"), 0.9),
+        ],
+    ),
+    rule(
+        "RSpec",
+        TestRunner,
+        &[(File(".rspec"), 0.9), (Contains("Gemfile", "rspec"), 0.8)],
+    ),
+    // CI
+    rule(
+        "GitHub Actions",
+        Ci,
+        &[
+            (Under(".github/workflows", ".yml"), 0.95),
+            (Under(".github/workflows", ".yaml"), 0.95),
+        ],
+    ),
+    rule("GitLab CI", Ci, &[(File(".gitlab-ci.yml"), 0.95)]),
+    rule("CircleCI", Ci, &[(Under(".circleci", "config.yml"), 0.95)]),
+    rule(
+        "Azure Pipelines",
+        Ci,
+        &[(File("azure-pipelines.yml"), 0.95)],
+    ),
+    rule("Jenkins", Ci, &[(File("Jenkinsfile"), 0.95)]),
+    rule("Travis CI", Ci, &[(File(".travis.yml"), 0.95)]),
+    rule(
+        "Bitbucket Pipelines",
+        Ci,
+        &[(File("bitbucket-pipelines.yml"), 0.95)],
+    ),
+    // Outras ferramentas
+    rule(
+        "Docker",
+        Tooling,
+        &[
+            (File("Dockerfile"), 0.9),
+            (File("docker-compose.yml"), 0.9),
+            (File("compose.yaml"), 0.9),
+        ],
+    ),
+    rule(
+        "ESLint",
+        Tooling,
+        &[
+            (Dep(NPM, "eslint"), 0.8),
+            (File("eslint.config.js"), 0.9),
+            (File(".eslintrc.json"), 0.9),
+            (File(".eslintrc.js"), 0.9),
+            (File(".eslintrc.cjs"), 0.9),
+        ],
+    ),
+    rule(
+        "Prettier",
+        Tooling,
+        &[
+            (Dep(NPM, "prettier"), 0.8),
+            (File(".prettierrc"), 0.9),
+            (File(".prettierrc.json"), 0.9),
+        ],
+    ),
+    rule(
+        "Ruff",
+        Tooling,
+        &[(Dep(PYPI, "ruff"), 0.8), (File("ruff.toml"), 0.9)],
+    ),
+];
+
+struct Workspace<'a> {
+    // Caminhos relativos com `/`, na mesma ordem da varredura
+    files: Vec<(String, &'a PathBuf, u64)>,
+    dependencies: &'a [Dependency],
+    contents: HashMap<&'a PathBuf, Option<String>>,
+}
+
+// Linguagens (pela contagem de linhas) e tecnologias encontradas em manifestos,
+// dependências e arquivos de configuração, cada uma com confiança e evidências
+pub fn detect(
+    root: &Path,
+    files: &[(PathBuf, u64)],
+    dependencies: &[Dependency],
+    languages: &[LanguageStats],
+) -> Vec<DetectedTechnology> {
+    let mut files: Vec<(String, &PathBuf, u64)> = files
+        .iter()
+        .filter_map(|(path, size)| {
+            let relative = path.strip_prefix(root).ok()?;
+            Some((relative.to_string_lossy().replace('\\', "/"), path, *size))
+        })
+        .collect();
+    // Arquivos mais rasos primeiro: a evidência mostra os da raiz
+    files.sort_by(|a, b| {
+        let depth = |path: &str| path.matches('/').count();
+        depth(&a.0).cmp(&depth(&b.0)).then_with(|| a.0.cmp(&b.0))
+    });
+    let mut workspace = Workspace {
+        files,
+        dependencies,
+        contents: HashMap::new(),
+    };
+
+    let mut detected = detect_languages(languages);
+    for rule in RULES {
+        let mut missing = 1.0;
+        let mut evidence: Vec<String> = Vec::new();
+        for (signal, weight) in rule.signals {
+            let found = workspace.evidence(signal);
+            if !found.is_empty() {
+                // Sinais independentes: cada um reduz a chance de ser falso positivo
+                missing *= 1.0 - weight;
+                evidence.extend(found);
+            }
+        }
+        let confidence = round(1.0 - missing);
+        if confidence >= MIN_CONFIDENCE {
+            detected.push(DetectedTechnology {
+                name: rule.name.to_string(),
+                category: rule.category,
+                confidence,
+                evidence,
+            });
+        }
+    }
+
+    detected.sort_by(|a, b| {
+        a.category
+            .cmp(&b.category)
+            .then_with(|| b.confidence.total_cmp(&a.confidence))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    detected
+}
+
+fn detect_languages(languages: &[LanguageStats]) -> Vec<DetectedTechnology> {
+    let languages: Vec<&LanguageStats> = languages
+        .iter()
+        .filter(|l| l.code > 0 && !NON_STACK_LANGUAGES.contains(&l.language.as_str()))
+        .collect();
+    let total: usize = languages.iter().map(|l| l.code).sum();
+
+    languages
+        .into_iter()
+        .filter_map(|language| {
+            let share = language.code as f64 / total as f64;
+            // Linguagem com poucas linhas costuma ser script solto, não parte da stack
+            let confidence = round((0.5 + share * 5.0).min(1.0));
+            (share >= 0.01).then(|| DetectedTechnology {
+                name: language.language.clone(),
+                category: TechnologyCategory::Language,
+                confidence,
+                evidence: vec![format!(
+                    "{} arquivo(s), {} linhas de código ({:.0}%)",
+                    language.files,
+                    language.code,
+                    share * 100.0
+                )],
+            })
+        })
+        .collect()
+}
+
+impl<'a> Workspace<'a> {
+    fn evidence(&mut self, signal: &Signal) -> Vec<String> {
+        match signal {
+            Signal::Dependency(ecosystem, name) => {
+                let mut manifests: Vec<&str> = self
+                    .dependencies
+                    .iter()
+                    .filter(|d| d.ecosystem == *ecosystem && name_matches(name, &d.name))
+                    .map(|d| d.manifest.as_str())
+                    .collect();
+                manifests.sort();
+                manifests.dedup();
+                manifests
+                    .into_iter()
+                    .take(MAX_EVIDENCE_PER_SIGNAL)
+                    .map(|manifest| {
+                        format!(
+                            "dependência `{}` em {}",
+                            name.trim_end_matches('*'),
+                            manifest
+                        )
+                    })
+                    .collect()
+            }
+            Signal::File(name) => self
+                .files
+                .iter()
+                .filter(|(relative, _, _)| file_name_matches(name, relative))
+                .take(MAX_EVIDENCE_PER_SIGNAL)
+                .map(|(relative, _, _)| format!("arquivo {}", relative))
+                .collect(),
+            Signal::Under(dir, suffix) => self
+                .files
+                .iter()
+                .filter(|(relative, _, _)| {
+                    let prefix = format!("{}/", dir);
+                    (relative.starts_with(&prefix) || relative.contains(&format!("/{}", prefix)))
+                        && relative.to_lowercase().ends_with(suffix)
+                })
+                .take(MAX_EVIDENCE_PER_SIGNAL)
+                .map(|(relative, _, _)| format!("arquivo {}", relative))
+                .collect(),
+            Signal::Contains(name, needle) => {
+                let candidates: Vec<(String, &'a PathBuf)> = self
+                    .files
+                    .iter()
+                    .filter(|(relative, _, size)| {
+                        *size <= MAX_CONTENT_FILE_SIZE && file_name_matches(name, relative)
+                    })
+                    .take(10)
+                    .map(|(relative, path, _)| (relative.clone(), *path))
+                    .collect();
+                candidates
+                    .into_iter()
+                    .filter(|(_, path)| {
+                        self.contents
+                            .entry(path)
+                            .or_insert_with(|| fs::read_to_string(path).ok())
+                            .as_deref()
+                            .is_some_and(|content| content.contains(needle))
+                    })
+                    .take(MAX_EVIDENCE_PER_SIGNAL)
+                    .map(|(relative, _)| format!("`{}` em {}", needle, relative))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name.eq_ignore_ascii_case(pattern),
+    }
+}
+
+// `*_test.go` e `*.csproj` casam por sufixo; o resto pelo nome exato
+fn file_name_matches(pattern: &str, relative: &str) -> bool {
+    let file_name = relative.rsplit('/').next().unwrap_or(relative);
+    match pattern.strip_prefix('*') {
+        Some(suffix) => file_name.to_lowercase().ends_with(&suffix.to_lowercase()),
+        None => file_name.eq_ignore_ascii_case(pattern),
+    }
+}
@@ -0,0 +1,301 @@
+use std::path::Path;
+use tree_sitter::Node;
+
+use super::syntax::{receiver_type, Grammar};
+use crate::types::{SymbolInfo, SymbolKind};
+
+struct Found {
+    name: String,
+    kind: SymbolKind,
+    container: Option<String>,
+    line: usize,
+    column: usize,
+    end_line: usize,
+}
+
+// Definições de nível de módulo/classe; o que é declarado dentro de funções fica de fora
+pub fn extract(root: &Path, path: &Path, source: &str) -> Option<Vec<SymbolInfo>> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let grammar = Grammar::detect(relative)?;
+    let tree = grammar.parse(source)?;
+    let bytes = source.as_bytes();
+
+    let mut found: Vec<Found> = Vec::new();
+    let mut stack: Vec<(Node, Option<String>)> = vec![(tree.root_node(), None)];
+
+    while let Some((node, container)) = stack.pop() {
+        let mut add = |name: Node, kind: SymbolKind, definition: Node| {
+            found.extend(symbol_at(source, name, kind, container.clone(), definition));
+        };
+        let name = node.child_by_field_name("name");
+        let in_container = container.is_some();
+        // Filhos herdam o container atual; classes/impls/traits/módulos trocam
+        let mut descend: Option<Option<String>> = Some(container.clone());
+
+        match (grammar, node.kind()) {
+            (Grammar::Rust, "function_item" | "function_signature_item") => {
+                let in_impl = node
+                    .parent()
+                    .and_then(|list| list.parent())
+                    .is_some_and(|item| matches!(item.kind(), "impl_item" | "trait_item"));
+                let kind = if in_impl {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                };
+                name.into_iter().for_each(|n| add(n, kind, node));
+                descend = None;
+            }
+            (Grammar::Rust, "struct_item" | "union_item") => name
+                .into_iter()
+                .for_each(|n| add(n, SymbolKind::Struct, node)),
+            (Grammar::Rust, "enum_item") => name
+                .into_iter()
+                .for_each(|n| add(n, SymbolKind::Enum, node)),
+            (Grammar::Rust, "type_item") => name
+                .into_iter()
+                .for_each(|n| add(n, SymbolKind::Type, node)),
+            (Grammar::Rust, "const_item" | "static_item") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Constant, node));
+                descend = None;
+            }
+            (Grammar::Rust, "macro_definition") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Macro, node));
+                descend = None;
+            }
+            (Grammar::Rust, "trait_item") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Trait, node));
+                descend = Some(text(name, bytes));
+            }
+            (Grammar::Rust, "mod_item") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Module, node));
+                descend = Some(text(name, bytes));
+            }
+            // `impl<T> Trait for Foo<T>` agrupa os métodos em `Foo`
+            (Grammar::Rust, "impl_item") => {
+                descend = Some(
+                    text(node.child_by_field_name("type"), bytes)
+                        .map(|t| t.split('<').next().unwrap_or_default().trim().to_string()),
+                );
+            }
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "function_declaration" | "generator_function_declaration",
+            ) => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Function, node));
+                descend = None;
+            }
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "class_declaration" | "abstract_class_declaration",
+            ) => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Class, node));
+                descend = Some(text(name, bytes));
+            }
+            (Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript, "method_definition") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Method, node));
+                descend = None;
+            }
+            // `handleClick = () => {...}` dentro de classe
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "public_field_definition" | "field_definition",
+            ) => {
+                let field = name.or_else(|| node.child_by_field_name("property"));
+                if node
+                    .child_by_field_name("value")
+                    .is_some_and(|value| is_function_value(value.kind()))
+                {
+                    field
+                        .into_iter()
+                        .for_each(|n| add(n, SymbolKind::Method, node));
+                }
+                descend = None;
+            }
+            (Grammar::TypeScript | Grammar::Tsx, "interface_declaration") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Interface, node));
+                descend = None;
+            }
+            (Grammar::TypeScript | Grammar::Tsx, "type_alias_declaration") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Type, node));
+                descend = None;
+            }
+            (Grammar::TypeScript | Grammar::Tsx, "enum_declaration") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Enum, node));
+                descend = None;
+            }
+            (Grammar::TypeScript | Grammar::Tsx, "internal_module" | "module") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Module, node));
+                descend = Some(text(name, bytes));
+            }
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "lexical_declaration" | "variable_declaration",
+            ) => {
+                let is_const = node
+                    .child_by_field_name("kind")
+                    .is_some_and(|kind| kind.kind() == "const");
+                let mut cursor = node.walk();
+                for declarator in node.named_children(&mut cursor) {
+                    let Some(declared) = declarator
+                        .child_by_field_name("name")
+                        .filter(|n| n.kind() == "identifier")
+                    else {
+                        continue;
+                    };
+                    let kind = match declarator.child_by_field_name("value") {
+                        Some(value) if is_function_value(value.kind()) => SymbolKind::Function,
+                        _ if is_const => SymbolKind::Constant,
+                        _ => SymbolKind::Variable,
+                    };
+                    add(declared, kind, declarator);
+                }
+                descend = None;
+            }
+            (
+                Grammar::TypeScript | Grammar::Tsx | Grammar::JavaScript,
+                "arrow_function" | "function_expression" | "generator_function" | "function",
+            ) => descend = None,
+            (Grammar::Python, "function_definition") => {
+                let kind = if in_container {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                };
+                name.into_iter().for_each(|n| add(n, kind, node));
+                descend = None;
+            }
+            (Grammar::Python, "class_definition") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Class, node));
+                descend = Some(text(name, bytes));
+            }
+            // Atribuições no nível do módulo/classe; MAIÚSCULAS são constantes por convenção
+            (Grammar::Python, "assignment") => {
+                if let Some(target) = node
+                    .child_by_field_name("left")
+                    .filter(|left| left.kind() == "identifier")
+                {
+                    let is_constant = target.utf8_text(bytes).is_ok_and(|t| {
+                        t.chars().any(|c| c.is_ascii_uppercase())
+                            && !t.chars().any(|c| c.is_ascii_lowercase())
+                    });
+                    let kind = if is_constant {
+                        SymbolKind::Constant
+                    } else {
+                        SymbolKind::Variable
+                    };
+                    add(target, kind, node);
+                }
+                descend = None;
+            }
+            (Grammar::Python, "lambda") => descend = None,
+            (Grammar::Go, "function_declaration") => {
+                name.into_iter()
+                    .for_each(|n| add(n, SymbolKind::Function, node));
+                descend = None;
+            }
+            (Grammar::Go, "method_declaration") => {
+                let receiver = node
+                    .child_by_field_name("receiver")
+                    .and_then(|receiver| receiver_type(receiver, bytes));
+                if let Some(name) = name {
+                    found.extend(symbol_at(source, name, SymbolKind::Method, receiver, node));
+                }
+                descend = None;
+            }
+            (Grammar::Go, "type_spec" | "type_alias") => {
+                let kind = match node.child_by_field_name("type").map(|t| t.kind()) {
+                    Some("struct_type") => SymbolKind::Struct,
+                    Some("interface_type") => SymbolKind::Interface,
+                    _ => SymbolKind::Type,
+                };
+                name.into_iter().for_each(|n| add(n, kind, node));
+                descend = None;
+            }
+            (Grammar::Go, "const_spec" | "var_spec") => {
+                let kind = if node.kind() == "const_spec" {
+                    SymbolKind::Constant
+                } else {
+                    SymbolKind::Variable
+                };
+                let mut cursor = node.walk();
+                for declared in node.children_by_field_name("name", &mut cursor) {
+                    add(declared, kind, node);
+                }
+                descend = None;
+            }
+            (Grammar::Go, "func_literal") => descend = None,
+            _ => {}
+        }
+
+        if let Some(child_container) = descend {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                stack.push((child, child_container.clone()));
+            }
+        }
+    }
+
+    let file = relative.to_string_lossy().replace('\\', "/");
+    let mut symbols: Vec<SymbolInfo> = found
+        .into_iter()
+        .filter(|f| !f.name.is_empty())
+        .map(|f| SymbolInfo {
+            name: f.name,
+            kind: f.kind,
+            container: f.container,
+            language: grammar.name().to_string(),
+            path: path.to_string_lossy().to_string(),
+            file: file.clone(),
+            line: f.line,
+            column: f.column,
+            end_line: f.end_line,
+        })
+        .collect();
+    symbols.sort_by_key(|symbol| (symbol.line, symbol.column));
+    Some(symbols)
+}
+
+fn symbol_at(
+    source: &str,
+    name: Node,
+    kind: SymbolKind,
+    container: Option<String>,
+    definition: Node,
+) -> Option<Found> {
+    let text = name.utf8_text(source.as_bytes()).ok()?;
+    let line_start = name.start_byte() - name.start_position().column;
+    Some(Found {
+        name: text.to_string(),
+        kind,
+        container,
+        line: name.start_position().row + 1,
+        // Coluna em caracteres, não em bytes
+        column: source[line_start..name.start_byte()].chars().count() + 1,
+        end_line: definition.end_position().row + 1,
+    })
+}
+
+fn text(node: Option<Node>, source: &[u8]) -> Option<String> {
+    node.and_then(|n| n.utf8_text(source).ok())
+        .map(str::to_string)
+}
+
+fn is_function_value(kind: &str) -> bool {
+    matches!(
+        kind,
+        "arrow_function" | "function_expression" | "generator_function" | "function"
+    )
+}
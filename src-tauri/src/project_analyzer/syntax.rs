@@ -0,0 +1,72 @@
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Tree};
+
+// Linguagens com gramática tree-sitter embutida
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grammar {
+    Rust,
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl Grammar {
+    pub fn detect(path: &Path) -> Option<Grammar> {
+        let file_name = path.file_name()?.to_str()?;
+        if file_name.ends_with(".d.ts") || file_name.contains(".min.") {
+            return None;
+        }
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Grammar::Rust),
+            "ts" | "mts" | "cts" => Some(Grammar::TypeScript),
+            "tsx" => Some(Grammar::Tsx),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Grammar::JavaScript),
+            "py" | "pyi" => Some(Grammar::Python),
+            "go" => Some(Grammar::Go),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Grammar::Rust => "Rust",
+            Grammar::TypeScript | Grammar::Tsx => "TypeScript",
+            Grammar::JavaScript => "JavaScript",
+            Grammar::Python => "Python",
+            Grammar::Go => "Go",
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Grammar::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Grammar::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Grammar::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    pub fn parse(self, source: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language()).ok()?;
+        parser.parse(source, None)
+    }
+}
+
+// `func (s *Server) Start()` vira `Server.Start`
+pub fn receiver_type(receiver: Node, source: &[u8]) -> Option<String> {
+    let mut stack = vec![receiver];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "type_identifier" {
+            return node.utf8_text(source).ok().map(str::to_string);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    None
+}
@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::types::PromptTemplate;
+
+const TEMPLATE_EXTENSION: &str = "json";
+
+// Templates embutidos: (id, nome, descrição, texto)
+const BUILTIN_TEMPLATES: &[(&str, &str, &str, &str)] = &[
+    (
+        "code_snippet",
+        "Snippet de código",
+        "Gera um snippet de código a partir de uma descrição",
+        "Gere um snippet de código em {{language}} para: {{description}}\n\nRetorne apenas o código, sem explicações.",
+    ),
+    (
+        "unit_tests",
+        "Testes unitários",
+        "Escreve testes unitários para o código selecionado",
+        "Escreva testes unitários em {{language}} para o código abaixo, extraído de `{{file}}`.\n\
+         Cubra casos normais, casos de borda e tratamento de erros, usando o framework de testes mais comum para a linguagem.\n\n\
+         ```{{language}}\n{{selection}}\n```\n\nRetorne apenas o código dos testes.",
+    ),
+    (
+        "explain_code",
+        "Explicar código",
+        "Explica o que o código selecionado faz",
+        "Explique de forma clara o que o código abaixo (arquivo `{{file}}`, linguagem {{language}}) faz, \
+         passo a passo, apontando possíveis problemas.\n\n```{{language}}\n{{selection}}\n```",
+    ),
+    (
+        "refactor_code",
+        "Refatorar código",
+        "Sugere uma versão refatorada do código selecionado",
+        "Refatore o código abaixo (arquivo `{{file}}`, linguagem {{language}}) para melhorar legibilidade e \
+         manutenibilidade, sem alterar o comportamento. Explique brevemente as mudanças.\n\n```{{language}}\n{{selection}}\n```",
+    ),
+    (
+        "document_code",
+        "Documentar código",
+        "Adiciona comentários de documentação ao código selecionado",
+        "Adicione comentários de documentação idiomáticos de {{language}} ao código abaixo (arquivo `{{file}}`). \
+         Retorne o código completo documentado.\n\n```{{language}}\n{{selection}}\n```",
+    ),
+    (
+        "project_question",
+        "Pergunta sobre o projeto",
+        "Responde uma pergunta usando o resumo do projeto como contexto",
+        "Use o contexto do projeto abaixo para responder à pergunta.\n\n{{project_summary}}\n\n## Pergunta\n{{question}}",
+    ),
+];
+
+fn variable_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("RZOneHub").join("templates"))
+}
+
+// Lista os nomes de variáveis usados no template, na ordem em que aparecem
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for capture in variable_regex().captures_iter(template) {
+        let name = capture[1].to_string();
+        if !variables.contains(&name) {
+            variables.push(name);
+        }
+    }
+    variables
+}
+
+pub fn list_templates() -> Vec<PromptTemplate> {
+    let mut templates: Vec<PromptTemplate> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, name, description, template)| PromptTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            template: template.to_string(),
+            variables: template_variables(template),
+            builtin: true,
+        })
+        .collect();
+
+    // Templates do usuário substituem os embutidos com o mesmo id
+    for user_template in load_user_templates() {
+        if let Some(existing) = templates.iter_mut().find(|t| t.id == user_template.id) {
+            *existing = user_template;
+        } else {
+            templates.push(user_template);
+        }
+    }
+
+    templates
+}
+
+pub fn find_template(id: &str) -> Result<PromptTemplate> {
+    list_templates()
+        .into_iter()
+        .find(|t| t.id == id)
+        .with_context(|| format!("Template de prompt não encontrado: {}", id))
+}
+
+pub fn render(template: &PromptTemplate, variables: &HashMap<String, String>) -> Result<String> {
+    let missing: Vec<&str> = template
+        .variables
+        .iter()
+        .filter(|name| !variables.contains_key(name.as_str()))
+        .map(|name| name.as_str())
+        .collect();
+
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Variáveis ausentes para o template '{}': {}",
+            template.id,
+            missing.join(", ")
+        );
+    }
+
+    let rendered = variable_regex().replace_all(&template.template, |caps: &regex::Captures| {
+        variables.get(&caps[1]).cloned().unwrap_or_default()
+    });
+
+    Ok(rendered.into_owned())
+}
+
+pub fn render_by_id(id: &str, variables: &HashMap<String, String>) -> Result<String> {
+    render(&find_template(id)?, variables)
+}
+
+// Templates do usuário ficam em <config>/RZOneHub/templates/*.json
+fn load_user_templates() -> Vec<PromptTemplate> {
+    let Some(dir) = templates_dir() else {
+        return Vec::new();
+    };
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(TEMPLATE_EXTENSION) {
+            continue;
+        }
+
+        match load_template_file(&path) {
+            Ok(template) => templates.push(template),
+            Err(e) => log::warn!("Ignorando template inválido {:?}: {}", path, e),
+        }
+    }
+
+    templates.sort_by(|a, b| a.id.cmp(&b.id));
+    templates
+}
+
+fn load_template_file(path: &Path) -> Result<PromptTemplate> {
+    #[derive(serde::Deserialize)]
+    struct TemplateFile {
+        id: Option<String>,
+        name: Option<String>,
+        #[serde(default)]
+        description: String,
+        template: String,
+    }
+
+    let content = fs::read_to_string(path)?;
+    let file: TemplateFile = serde_json::from_str(&content).context("JSON inválido")?;
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let id = file.id.unwrap_or(stem);
+
+    Ok(PromptTemplate {
+        name: file.name.unwrap_or_else(|| id.clone()),
+        id,
+        description: file.description,
+        variables: template_variables(&file.template),
+        template: file.template,
+        builtin: false,
+    })
+}
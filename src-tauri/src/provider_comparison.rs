@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::ai_client;
+use crate::types::{
+    ChatMessage, ChatRequest, ComparisonResult, ComparisonTarget, ProviderComparison,
+};
+
+// Envia o mesmo prompt para todos os alvos em paralelo. `on_delta` recebe os trechos
+// de cada alvo conforme chegam e `on_result` é chamado assim que um alvo termina.
+pub async fn compare_providers<D, R>(
+    id: String,
+    prompt: String,
+    history: Vec<ChatMessage>,
+    targets: Vec<ComparisonTarget>,
+    on_delta: D,
+    on_result: R,
+) -> ProviderComparison
+where
+    D: Fn(usize, &str) + Send + Sync + 'static,
+    R: Fn(&ComparisonResult) + Send + Sync + 'static,
+{
+    let on_delta = Arc::new(on_delta);
+    let on_result = Arc::new(on_result);
+
+    let handles: Vec<_> = targets
+        .into_iter()
+        .enumerate()
+        .map(|(index, target)| {
+            let request = ChatRequest {
+                provider: target.provider.clone(),
+                api_key: target.api_key,
+                prompt: prompt.clone(),
+                history: history.clone(),
+                model: target.model.clone(),
+            };
+            let model = target
+                .model
+                .or_else(|| ai_client::default_model(&target.provider).map(|m| m.to_string()));
+            let provider = target.provider;
+            let task_provider = provider.clone();
+            let task_model = model.clone();
+            let on_delta = Arc::clone(&on_delta);
+            let on_result = Arc::clone(&on_result);
+
+            let task = tokio::spawn(async move {
+                let provider = task_provider;
+                let model = task_model;
+                let started = Instant::now();
+                let first_token: Arc<Mutex<Option<u64>>> = Arc::new(Mutex::new(None));
+                let first_token_writer = Arc::clone(&first_token);
+
+                let response = ai_client::stream_chat_response(request, move |delta| {
+                    let mut first = first_token_writer.lock().unwrap();
+                    if first.is_none() {
+                        *first = Some(started.elapsed().as_millis() as u64);
+                    }
+                    drop(first);
+                    on_delta(index, delta);
+                })
+                .await;
+
+                let latency_ms = started.elapsed().as_millis() as u64;
+                let first_token_ms = *first_token.lock().unwrap();
+
+                let result = match response {
+                    Ok(response) => ComparisonResult {
+                        target_index: index,
+                        provider,
+                        model,
+                        content: Some(response.content),
+                        error: None,
+                        latency_ms,
+                        first_token_ms,
+                        usage: response.usage,
+                    },
+                    Err(e) => ComparisonResult {
+                        target_index: index,
+                        provider,
+                        model,
+                        content: None,
+                        error: Some(e.to_string()),
+                        latency_ms,
+                        first_token_ms,
+                        usage: None,
+                    },
+                };
+
+                on_result(&result);
+                result
+            });
+
+            (index, provider, model, task)
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for (index, provider, model, handle) in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => results.push(ComparisonResult {
+                target_index: index,
+                provider,
+                model,
+                content: None,
+                error: Some(format!("Tarefa de comparação falhou: {}", e)),
+                latency_ms: 0,
+                first_token_ms: None,
+                usage: None,
+            }),
+        }
+    }
+
+    ProviderComparison {
+        id,
+        prompt,
+        results,
+    }
+}
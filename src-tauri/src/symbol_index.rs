@@ -0,0 +1,514 @@
+use anyhow::Result;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
+
+use crate::ignore_rules::{IgnoreRules, PathMatcher};
+use crate::project_analyzer::{collect_symbols, file_symbols, source_language};
+use crate::types::{SymbolIndexStatus, SymbolInfo};
+
+// Espera os eventos de uma rajada (salvar, git checkout) antes de reindexar
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// Índice de símbolos por workspace, mantido atualizado por um watcher de arquivos
+#[derive(Default)]
+pub struct SymbolIndex {
+    workspaces: Mutex<HashMap<PathBuf, Arc<WorkspaceSymbols>>>,
+}
+
+struct WorkspaceSymbols {
+    root: PathBuf,
+    // Montadas uma vez por workspace, não a cada rajada de eventos
+    rules: IgnoreRules,
+    ignored: PathMatcher,
+    files: RwLock<HashMap<PathBuf, Vec<SymbolInfo>>>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    watched_dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl SymbolIndex {
+    // (Re)constrói o índice do workspace e passa a observar mudanças nele
+    pub fn index(&self, root: &Path) -> Result<SymbolIndexStatus> {
+        let root = root.canonicalize()?;
+        let rules = IgnoreRules::for_root(&root);
+        let workspace = Arc::new(WorkspaceSymbols {
+            root: root.clone(),
+            ignored: rules.matcher(),
+            rules,
+            files: RwLock::new(collect_symbols(&root)?.into_iter().collect()),
+            watcher: Mutex::new(None),
+            watched_dirs: Mutex::new(HashSet::new()),
+        });
+
+        match start_watcher(&workspace) {
+            Ok(watcher) => *workspace.watcher.lock().unwrap() = Some(watcher),
+            Err(e) => log::warn!("Não foi possível observar {:?}: {}", root, e),
+        }
+
+        let status = workspace.status();
+        // Substituir o workspace anterior derruba o watcher antigo junto
+        self.workspaces.lock().unwrap().insert(root, workspace);
+        Ok(status)
+    }
+
+    pub fn find_symbol(
+        &self,
+        query: &str,
+        root: Option<&Path>,
+        limit: usize,
+    ) -> Result<Vec<SymbolInfo>> {
+        let workspaces = match root {
+            Some(root) => vec![self.workspace_for(root, true)?],
+            None => self.workspaces.lock().unwrap().values().cloned().collect(),
+        };
+
+        let mut matches: Vec<(i64, SymbolInfo)> = Vec::new();
+        for workspace in workspaces {
+            let files = workspace.files.read().unwrap();
+            for symbol in files.values().flatten() {
+                if let Some(score) = symbol_score(query, symbol) {
+                    matches.push((score, symbol.clone()));
+                }
+            }
+        }
+
+        matches.sort_by(|(score_a, a), (score_b, b)| {
+            score_b
+                .cmp(score_a)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        matches.truncate(limit);
+        Ok(matches.into_iter().map(|(_, symbol)| symbol).collect())
+    }
+
+    // Definições de `name` (aceita `Tipo::metodo`/`Classe.metodo`) vistas a partir de `file`:
+    // mesma linguagem e arquivos mais próximos primeiro
+    pub fn find_definition(&self, name: &str, file: &Path) -> Result<Vec<SymbolInfo>> {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        let workspace = self.workspace_for(&file, false)?;
+        let (container, name) = split_qualified(name);
+        let language = source_language(&file);
+
+        let files = workspace.files.read().unwrap();
+        let candidates = |exact: bool| -> Vec<SymbolInfo> {
+            files
+                .values()
+                .flatten()
+                .filter(|symbol| {
+                    if exact {
+                        symbol.name == name
+                    } else {
+                        symbol.name.eq_ignore_ascii_case(name)
+                    }
+                })
+                .filter(|symbol| {
+                    container.map_or(true, |container| {
+                        symbol
+                            .container
+                            .as_deref()
+                            .is_some_and(|c| c == container || c.ends_with(container))
+                    })
+                })
+                .cloned()
+                .collect()
+        };
+        let mut definitions = candidates(true);
+        if definitions.is_empty() {
+            definitions = candidates(false);
+        }
+
+        definitions.sort_by_key(|symbol| {
+            let path = Path::new(&symbol.path);
+            (
+                language.is_some_and(|l| l != symbol.language),
+                path != file,
+                path.parent() != file.parent(),
+                Reverse(common_prefix(path, &file)),
+                symbol.file.clone(),
+                symbol.line,
+            )
+        });
+        Ok(definitions)
+    }
+
+    // Workspace indexado que contém `path`; se não houver, indexa a raiz do projeto
+    fn workspace_for(&self, path: &Path, is_root: bool) -> Result<Arc<WorkspaceSymbols>> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let existing = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|workspace| canonical.starts_with(&workspace.root))
+            .max_by_key(|workspace| workspace.root.as_os_str().len())
+            .cloned();
+        if let Some(workspace) = existing {
+            return Ok(workspace);
+        }
+
+        let root = if is_root {
+            canonical
+        } else {
+            let dir = canonical.parent().unwrap_or(&canonical);
+            IgnoreRules::for_path(dir).root().to_path_buf()
+        };
+        self.index(&root)?;
+        self.workspaces
+            .lock()
+            .unwrap()
+            .get(&root)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Workspace não indexado: {:?}", root))
+    }
+}
+
+impl WorkspaceSymbols {
+    fn status(&self) -> SymbolIndexStatus {
+        let files = self.files.read().unwrap();
+        SymbolIndexStatus {
+            root: self.root.to_string_lossy().to_string(),
+            files: files.len(),
+            symbols: files.values().map(Vec::len).sum(),
+            watching: self.watcher.lock().unwrap().is_some(),
+        }
+    }
+
+    // Observa só os diretórios que não são ignorados: um watch recursivo pegaria
+    // node_modules/target e estouraria o limite de watches do inotify
+    fn watch_tree(&self, watcher: &mut RecommendedWatcher, dir: &Path) {
+        let mut watched = self.watched_dirs.lock().unwrap();
+        for entry in self
+            .rules
+            .walker(dir)
+            .build()
+            .filter_map(|entry| entry.ok())
+        {
+            let path = entry.path();
+            if entry.file_type().is_some_and(|t| t.is_dir()) && !watched.contains(path) {
+                match watcher.watch(path, RecursiveMode::NonRecursive) {
+                    Ok(()) => {
+                        watched.insert(path.to_path_buf());
+                    }
+                    Err(e) => log::warn!("Não foi possível observar {:?}: {}", path, e),
+                }
+            }
+        }
+    }
+
+    fn apply_changes(&self, changed: HashSet<PathBuf>) {
+        for path in &changed {
+            self.ignored.refresh(path);
+        }
+
+        for path in changed {
+            if path.is_dir() {
+                if self.watched_dirs.lock().unwrap().contains(&path)
+                    || self.ignored.is_ignored(&path, true)
+                {
+                    continue;
+                }
+                // Pasta nova (ou renomeada): passa a observar e indexa tudo dentro dela
+                if let Some(watcher) = self.watcher.lock().unwrap().as_mut() {
+                    self.watch_tree(watcher, &path);
+                }
+                let files: Vec<(PathBuf, Vec<SymbolInfo>)> = self
+                    .rules
+                    .walker(&path)
+                    .build()
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| {
+                        let symbols = file_symbols(&self.root, entry.path())?;
+                        Some((entry.path().to_path_buf(), symbols))
+                    })
+                    .collect();
+                self.files.write().unwrap().extend(files);
+            } else if path.is_file() {
+                let known = self.files.read().unwrap().contains_key(&path);
+                if !known && self.ignored.is_ignored(&path, false) {
+                    continue;
+                }
+                match file_symbols(&self.root, &path) {
+                    Some(symbols) => {
+                        self.files.write().unwrap().insert(path, symbols);
+                    }
+                    None => {
+                        self.files.write().unwrap().remove(&path);
+                    }
+                }
+            } else {
+                // Removido: o caminho pode ser um arquivo ou uma pasta inteira
+                self.files
+                    .write()
+                    .unwrap()
+                    .retain(|file, _| !file.starts_with(&path));
+                self.watched_dirs
+                    .lock()
+                    .unwrap()
+                    .retain(|dir| !dir.starts_with(&path));
+            }
+        }
+    }
+}
+
+fn start_watcher(workspace: &Arc<WorkspaceSymbols>) -> notify::Result<RecommendedWatcher> {
+    let (sender, receiver) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    workspace.watch_tree(&mut watcher, &workspace.root);
+
+    // A thread termina quando o watcher (e com ele o sender) é descartado
+    let weak: Weak<WorkspaceSymbols> = Arc::downgrade(workspace);
+    std::thread::spawn(move || {
+        while let Ok(first) = receiver.recv() {
+            let mut changed: HashSet<PathBuf> = HashSet::new();
+            let mut collect = |event: notify::Result<notify::Event>| match event {
+                Ok(event) if !event.kind.is_access() => changed.extend(event.paths),
+                Ok(_) => {}
+                Err(e) => log::warn!("Erro no watcher de símbolos: {}", e),
+            };
+            collect(first);
+            while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+                collect(event);
+            }
+
+            let Some(workspace) = weak.upgrade() else {
+                break;
+            };
+            workspace.apply_changes(changed);
+        }
+    });
+
+    Ok(watcher)
+}
+
+fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    let name = name.trim();
+    name.rsplit_once("::")
+        .or_else(|| name.rsplit_once('.'))
+        .map(|(container, name)| (Some(container), name))
+        .unwrap_or((None, name))
+}
+
+fn common_prefix(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+// Com `Tipo::metodo`/`Classe.metodo` a busca considera o container junto com o nome
+fn symbol_score(query: &str, symbol: &SymbolInfo) -> Option<i64> {
+    let (container, name) = split_qualified(query);
+    let score = fuzzy_score(name, &symbol.name)?;
+    match container {
+        None => Some(score),
+        Some(container) => {
+            let symbol_container = symbol.container.as_deref()?;
+            Some(score + fuzzy_score(container, symbol_container)? / 2)
+        }
+    }
+}
+
+// Busca fuzzy no estilo "go to symbol": as letras da consulta precisam aparecer em ordem;
+// exato > prefixo > substring > subsequência, com bônus para início de palavra/camelCase
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let original: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = original
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let length_penalty = original.len() as i64;
+
+    if lower == query {
+        return Some(10_000 - length_penalty);
+    }
+    if lower.starts_with(&query) {
+        return Some(8_000 - length_penalty);
+    }
+    if let Some(position) = lower
+        .windows(query.len())
+        .position(|window| window == query.as_slice())
+    {
+        let boundary = if is_word_start(&original, position) {
+            1_000
+        } else {
+            0
+        };
+        return Some(6_000 + boundary - position as i64 - length_penalty);
+    }
+
+    let mut score = 1_000;
+    let mut previous: Option<usize> = None;
+    let mut index = 0;
+    for wanted in &query {
+        let found = (index..lower.len()).find(|&i| lower[i] == *wanted)?;
+        if is_word_start(&original, found) {
+            score += 30;
+        }
+        match previous {
+            Some(p) if p + 1 == found => score += 15,
+            Some(p) => score -= (found - p - 1) as i64,
+            None => score -= found as i64,
+        }
+        previous = Some(found);
+        index = found + 1;
+    }
+    Some(score - length_penalty)
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let (before, current) = (chars[index - 1], chars[index]);
+    matches!(before, '_' | '-' | '.' | '$')
+        || (before.is_lowercase() && current.is_uppercase())
+        || (!before.is_ascii_digit() && current.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SymbolKind;
+    use std::fs;
+
+    #[test]
+    fn fuzzy_score_tiers() {
+        let score = |candidate: &str| fuzzy_score("parse", candidate).unwrap();
+        let exact = score("parse");
+        let prefix = score("parseUsage");
+        let substring = score("try_parse");
+        let subsequence = score("prepare_sequence");
+        assert!(exact > prefix, "{} {}", exact, prefix);
+        assert!(prefix > substring, "{} {}", prefix, substring);
+        assert!(substring > subsequence, "{} {}", substring, subsequence);
+        assert_eq!(fuzzy_score("parse", "PARSE"), Some(exact));
+        assert_eq!(fuzzy_score("parse", "sparkle"), None);
+        assert_eq!(fuzzy_score("", "qualquer"), Some(0));
+
+        // Substring em início de palavra vale mais que no meio
+        assert!(score("try_parse") > score("reparsed"));
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_camel_case_and_word_starts() {
+        let camel = fuzzy_score("gu", "getUser").unwrap();
+        let plain = fuzzy_score("gu", "gaurd").unwrap();
+        assert!(camel > plain, "{} {}", camel, plain);
+
+        let snake = fuzzy_score("gd", "get_data").unwrap();
+        let middle = fuzzy_score("gd", "getdata").unwrap();
+        assert!(snake > middle, "{} {}", snake, middle);
+    }
+
+    #[test]
+    fn split_qualified_accepts_rust_and_dotted_names() {
+        assert_eq!(split_qualified("Parser::parse"), (Some("Parser"), "parse"));
+        assert_eq!(
+            split_qualified("crate::Parser::parse"),
+            (Some("crate::Parser"), "parse")
+        );
+        assert_eq!(
+            split_qualified("app.Parser.parse"),
+            (Some("app.Parser"), "parse")
+        );
+        assert_eq!(split_qualified("  parse "), (None, "parse"));
+    }
+
+    fn symbol(root: &Path, file: &str, name: &str, container: Option<&str>) -> SymbolInfo {
+        let path = root.join(file);
+        SymbolInfo {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            container: container.map(str::to_string),
+            language: source_language(&path).unwrap().to_string(),
+            path: path.to_string_lossy().to_string(),
+            file: file.to_string(),
+            line: 1,
+            column: 1,
+            end_line: 3,
+        }
+    }
+
+    #[test]
+    fn find_definition_resolves_type_method_and_orders_by_proximity() {
+        let root = std::env::temp_dir()
+            .join(format!(
+                "rzonehub-symbols-{}-{}",
+                std::process::id(),
+                chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ))
+            .join("app");
+        fs::create_dir_all(&root).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let symbols = [
+            symbol(&root, "src/parser.rs", "parse", Some("Parser")),
+            symbol(&root, "src/lexer.rs", "parse", Some("Lexer")),
+            symbol(&root, "src/main.rs", "parse", None),
+            symbol(&root, "tools/parser.py", "parse", Some("Parser")),
+            symbol(&root, "src/deep/config.rs", "Parse", None),
+        ];
+        let mut files: HashMap<PathBuf, Vec<SymbolInfo>> = HashMap::new();
+        for symbol in symbols {
+            files
+                .entry(PathBuf::from(&symbol.path))
+                .or_default()
+                .push(symbol);
+        }
+        let rules = IgnoreRules::for_root(&root);
+        let index = SymbolIndex::default();
+        index.workspaces.lock().unwrap().insert(
+            root.clone(),
+            Arc::new(WorkspaceSymbols {
+                root: root.clone(),
+                ignored: rules.matcher(),
+                rules,
+                files: RwLock::new(files),
+                watcher: Mutex::new(None),
+                watched_dirs: Mutex::new(HashSet::new()),
+            }),
+        );
+
+        let from = root.join("src").join("main.rs");
+        let files_of = |definitions: Vec<SymbolInfo>| -> Vec<String> {
+            definitions.into_iter().map(|symbol| symbol.file).collect()
+        };
+
+        // Só métodos de `Parser`, a mesma linguagem primeiro
+        assert_eq!(
+            files_of(index.find_definition("Parser::parse", &from).unwrap()),
+            ["src/parser.rs", "tools/parser.py"]
+        );
+        assert_eq!(
+            files_of(index.find_definition("Parser.parse", &from).unwrap()),
+            ["src/parser.rs", "tools/parser.py"]
+        );
+        // Sem container: o próprio arquivo, depois a mesma pasta, depois o resto
+        assert_eq!(
+            files_of(index.find_definition("parse", &from).unwrap()),
+            [
+                "src/main.rs",
+                "src/lexer.rs",
+                "src/parser.rs",
+                "tools/parser.py"
+            ]
+        );
+        // Sem nome exato, cai para a busca sem diferenciar maiúsculas
+        assert_eq!(
+            files_of(index.find_definition("PARSE", &from).unwrap()).len(),
+            5
+        );
+
+        fs::remove_dir_all(root.parent().unwrap()).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
@@ -18,6 +19,15 @@ pub struct ChatRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub content: String,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,4 +66,780 @@ pub struct ProjectAnalysis {
     pub largest_files: Vec<FileInfo>,
     pub suggestions: Vec<String>,
     pub summary: String,
+    #[serde(default)]
+    pub languages: Vec<LanguageStats>,
+    #[serde(default)]
+    pub directories: Vec<DirectoryStats>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    #[serde(default)]
+    pub vulnerability_audit: Option<VulnerabilityAudit>,
+    #[serde(default)]
+    pub secrets: Vec<SecretFinding>,
+    #[serde(default)]
+    pub context: Option<ContextReport>,
+    #[serde(default)]
+    pub stack: Vec<DetectedTechnology>,
+    #[serde(default)]
+    pub suggestion_details: Vec<Suggestion>,
+    #[serde(default)]
+    pub git: Option<GitAnalytics>,
+    #[serde(default)]
+    pub packages: Option<PackageNode>,
+    // Arquivos da varredura, guardados para o snapshot não percorrer a árvore de novo;
+    // não vão para o frontend nem para o JSON exportado
+    #[serde(skip)]
+    pub scanned_files: Vec<SnapshotFile>,
+}
+
+// Sugestão emitida por uma regra (embutida ou do usuário) com o porquê e onde
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub id: String,
+    pub severity: SuggestionSeverity,
+    pub message: String,
+    pub rationale: String,
+    pub evidence: Vec<String>,
+    pub builtin: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionRuleInfo {
+    pub id: String,
+    pub description: String,
+    pub severity: SuggestionSeverity,
+    pub enabled: bool,
+    pub builtin: bool,
+}
+
+// Tecnologia identificada no projeto; `confidence` vai de 0 a 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedTechnology {
+    pub name: String,
+    pub category: TechnologyCategory,
+    pub confidence: f64,
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TechnologyCategory {
+    Language,
+    Framework,
+    BuildTool,
+    PackageManager,
+    TestRunner,
+    Ci,
+    Tooling,
+}
+
+// Histórico local do git dentro da janela de `window_days` dias
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitAnalytics {
+    pub repository_root: String,
+    pub window_days: u32,
+    pub total_commits: usize,
+    pub commits_in_window: usize,
+    pub first_commit: Option<String>,
+    pub last_commit: Option<String>,
+    pub bus_factor: usize,
+    pub contributors: Vec<Contributor>,
+    pub files: Vec<FileChurn>,
+    pub hotspots: Vec<Hotspot>,
+    pub directories: Vec<DirectoryOwnership>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChurn {
+    pub file: String,
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    pub authors: usize,
+    pub last_changed: String,
+}
+
+// `score` = commits na janela × complexidade (ciclomática somada, ou linhas sem gramática)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub file: String,
+    pub commits: usize,
+    pub churn: usize,
+    pub complexity: usize,
+    pub lines: usize,
+    pub score: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryOwnership {
+    pub directory: String,
+    pub commits: usize,
+    pub contributors: Vec<Contributor>,
+    pub bus_factor: usize,
+}
+
+// O que entrou (e o que ficou de fora) do contexto do projeto enviado à IA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextReport {
+    pub token_budget: usize,
+    pub tokens_used: usize,
+    pub included: Vec<ContextFile>,
+    pub omitted: Vec<OmittedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextFile {
+    pub path: String,
+    pub tokens: usize,
+    pub lines_included: usize,
+    pub total_lines: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OmittedFile {
+    pub path: String,
+    pub reason: OmissionReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OmissionReason {
+    Budget,
+    Limit,
+    Size,
+    Unreadable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+    pub lines: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    Runtime,
+    Dev,
+    Build,
+    Peer,
+    Optional,
+    Indirect,
+}
+
+// Dependência declarada em um manifesto (Cargo.toml, package.json, go.mod,
+// requirements.txt, pyproject.toml), com a versão resolvida pelo lockfile quando houver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub requirement: Option<String>,
+    pub resolved_version: Option<String>,
+    pub kind: DependencyKind,
+    pub ecosystem: String,
+    pub manifest: String,
+    pub workspace_member: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VulnerabilitySeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vulnerability {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub package: String,
+    pub version: String,
+    pub ecosystem: String,
+    pub lockfile: String,
+    pub severity: VulnerabilitySeverity,
+    pub cvss_score: Option<f64>,
+    pub cvss_vector: Option<String>,
+    pub summary: Option<String>,
+    pub fixed_versions: Vec<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VulnerabilityAudit {
+    pub database_path: String,
+    pub packages_checked: usize,
+    pub advisories_checked: usize,
+    pub vulnerabilities: Vec<Vulnerability>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisorySourceStatus {
+    pub source: String,
+    pub available: bool,
+    pub advisories: usize,
+    pub updated_at: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisoryDbStatus {
+    pub path: String,
+    pub sources: Vec<AdvisorySourceStatus>,
+}
+
+// Possível segredo encontrado no workspace; `preview` traz só o início do valor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretFinding {
+    pub rule: String,
+    pub description: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub preview: String,
+}
+
+// Totais acumulados do diretório e de todas as subpastas ("." é a raiz)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryStats {
+    pub path: String,
+    pub files: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+    pub lines: usize,
+}
+
+// Métricas de uma função/método; `name` vem qualificado pelo tipo/classe quando houver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub name: String,
+    pub file: String,
+    pub language: String,
+    pub line: usize,
+    pub end_line: usize,
+    pub lines: usize,
+    pub parameters: usize,
+    pub cyclomatic: usize,
+    pub cognitive: usize,
+    pub max_nesting: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetrics {
+    pub file: String,
+    pub language: String,
+    pub functions: usize,
+    pub total_cyclomatic: usize,
+    pub average_cyclomatic: f64,
+    pub max_cyclomatic: usize,
+    pub max_cognitive: usize,
+    pub max_nesting: usize,
+    pub longest_function: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeMetrics {
+    pub files_analyzed: usize,
+    pub functions_analyzed: usize,
+    pub average_cyclomatic: f64,
+    pub worst_functions: Vec<FunctionMetrics>,
+    pub files: Vec<FileMetrics>,
+}
+
+// Trechos de código repetidos; `duplicated_lines` conta cada linha uma vez por arquivo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub files_analyzed: usize,
+    pub tokens_analyzed: usize,
+    pub total_lines: usize,
+    pub duplicated_lines: usize,
+    pub min_tokens: usize,
+    pub normalize_identifiers: bool,
+    pub groups: Vec<CloneGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneGroup {
+    pub tokens: usize,
+    pub lines: usize,
+    pub fragments: Vec<CloneFragment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneFragment {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerKind {
+    Todo,
+    Fixme,
+    Hack,
+    Xxx,
+    Unimplemented,
+}
+
+// Marcador de dívida técnica; `context` são as linhas a partir de `context_start`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebtMarker {
+    pub kind: MarkerKind,
+    pub file: String,
+    pub line: usize,
+    pub text: String,
+    pub context_start: usize,
+    pub context: Vec<String>,
+    pub blame: Option<BlameInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameInfo {
+    pub author: String,
+    pub email: String,
+    pub date: String,
+    pub commit: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkerCount {
+    pub key: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechDebtReport {
+    pub total: usize,
+    pub by_kind: Vec<MarkerCount>,
+    pub by_file: Vec<MarkerCount>,
+    pub by_author: Vec<MarkerCount>,
+    pub markers: Vec<DebtMarker>,
+}
+
+// Filtros da busca de marcadores; vazios não restringem nada
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkerFilter {
+    #[serde(default)]
+    pub kinds: Vec<MarkerKind>,
+    // Parte do nome ou do e-mail do autor (git blame)
+    pub author: Option<String>,
+    // Parte do caminho relativo do arquivo
+    pub path: Option<String>,
+}
+
+// Nó do grafo de módulos: um arquivo (Rust, TS/JS, Python) ou um pacote Go (diretório)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleNode {
+    pub id: String,
+    pub language: String,
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleGraph {
+    pub nodes: Vec<ModuleNode>,
+    pub edges: Vec<ModuleEdge>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Type,
+    Constant,
+    Variable,
+    Module,
+    Macro,
+}
+
+// `path` é absoluto (para abrir no editor); `file` é relativo à raiz do workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolInfo {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub container: Option<String>,
+    pub language: String,
+    pub path: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolIndexStatus {
+    pub root: String,
+    pub files: usize,
+    pub symbols: usize,
+    pub watching: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub template: String,
+    pub variables: Vec<String>,
+    pub builtin: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateRunRequest {
+    pub template_id: String,
+    pub variables: HashMap<String, String>,
+    pub provider: String,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub project_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonTarget {
+    pub provider: String,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonResult {
+    pub target_index: usize,
+    pub provider: String,
+    pub model: Option<String>,
+    pub content: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+    pub first_token_ms: Option<u64>,
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderComparison {
+    pub id: String,
+    pub prompt: String,
+    pub results: Vec<ComparisonResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonDelta {
+    pub comparison_id: String,
+    pub target_index: usize,
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonUpdate {
+    pub comparison_id: String,
+    pub result: ComparisonResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub status: CheckStatus,
+    pub detail: String,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionDiagnostics {
+    pub success: bool,
+    pub message: String,
+    pub provider: String,
+    pub base_url: String,
+    pub model: Option<String>,
+    pub dns: DiagnosticCheck,
+    pub tls: DiagnosticCheck,
+    pub auth: DiagnosticCheck,
+    pub model_available: DiagnosticCheck,
+    pub latency_ms: Option<u64>,
+    pub rate_limits: HashMap<String, String>,
+    pub available_models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaStatus {
+    pub running: bool,
+    pub version: Option<String>,
+    pub base_url: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    pub size: u64,
+    pub digest: Option<String>,
+    pub modified_at: Option<String>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    pub name: String,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+    pub format: Option<String>,
+    pub license: Option<String>,
+    pub parameters: Option<String>,
+    pub template: Option<String>,
+    pub modelfile: Option<String>,
+    pub model_info: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaPullProgress {
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    pub percent: Option<f64>,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisProgress {
+    pub job_id: Option<String>,
+    pub phase: String,
+    pub files_scanned: usize,
+    pub directories_scanned: usize,
+    pub bytes_read: u64,
+    pub current_directory: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisJobResult {
+    pub job_id: String,
+    pub analysis: Option<ProjectAnalysis>,
+    pub error: Option<String>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseEvidenceKind {
+    File,
+    Manifest,
+    Header,
+}
+
+// Onde a licença do projeto foi declarada; cabeçalhos SPDX iguais são agrupados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseEvidence {
+    pub kind: LicenseEvidenceKind,
+    pub path: String,
+    pub license: String,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectLicense {
+    // Expressão SPDX escolhida entre as evidências
+    pub license: Option<String>,
+    pub evidence: Vec<LicenseEvidence>,
+}
+
+// Política de licenças (.rzonehub/license-policy.toml). Padrões SPDX sem diferenciar
+// maiúsculas, com `*` no fim para prefixo; `exceptions` lista pacotes sempre aceitos
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicensePolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    #[serde(default)]
+    pub exceptions: Vec<String>,
+}
+
+// Da melhor para a pior situação
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseStatus {
+    Allowed,
+    Unknown,
+    Unlisted,
+    Incompatible,
+    Denied,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyLicense {
+    pub name: String,
+    pub version: Option<String>,
+    pub ecosystem: String,
+    pub kind: DependencyKind,
+    pub manifest: String,
+    pub license: Option<String>,
+    // Lockfile, manifesto ou arquivo de licença de onde a licença foi lida
+    pub source: Option<String>,
+    pub status: LicenseStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseReport {
+    pub project: ProjectLicense,
+    pub policy: LicensePolicy,
+    pub dependencies: Vec<DependencyLicense>,
+    // Dependências que não estão como permitidas
+    pub issues: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub size: u64,
+}
+
+// Análise guardada com data e hora para acompanhar o workspace ao longo do tempo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisSnapshot {
+    pub id: String,
+    pub root: String,
+    pub created_at: String,
+    pub files: Vec<SnapshotFile>,
+    pub analysis: ProjectAnalysis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub root: String,
+    pub created_at: String,
+    pub total_files: usize,
+    pub code_lines: usize,
+    pub findings: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageDelta {
+    pub language: String,
+    pub files_before: usize,
+    pub files_after: usize,
+    pub code_before: usize,
+    pub code_after: usize,
+    pub code_delta: i64,
+}
+
+// Mesma dependência (ecossistema, nome e manifesto) com versão ou requisito diferente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyChange {
+    pub name: String,
+    pub ecosystem: String,
+    pub manifest: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingKind {
+    Suggestion,
+    Secret,
+    Vulnerability,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisFinding {
+    pub kind: FindingKind,
+    pub id: String,
+    pub message: String,
+    pub location: Option<String>,
+}
+
+// O que mudou de `from` para `to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisDiff {
+    pub from: SnapshotInfo,
+    pub to: SnapshotInfo,
+    pub files_added: Vec<SnapshotFile>,
+    pub files_removed: Vec<SnapshotFile>,
+    pub new_large_files: Vec<SnapshotFile>,
+    pub languages: Vec<LanguageDelta>,
+    pub code_delta: i64,
+    pub dependencies_added: Vec<Dependency>,
+    pub dependencies_removed: Vec<Dependency>,
+    pub dependencies_changed: Vec<DependencyChange>,
+    pub new_findings: Vec<AnalysisFinding>,
+    pub fixed_findings: Vec<AnalysisFinding>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageTotals {
+    pub files: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+    pub lines: usize,
+    pub dependencies: usize,
+}
+
+// Projeto ou subprojeto do repositório (crate, pacote npm, módulo Go, projeto Python).
+// `own` conta só o que não pertence a um subpacote; `totals` inclui os filhos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageNode {
+    pub name: String,
+    // Diretório relativo à raiz ("." para a própria raiz)
+    pub path: String,
+    pub ecosystems: Vec<String>,
+    pub manifests: Vec<String>,
+    // Declara membros (Cargo workspace, npm/pnpm/yarn workspaces, go.work, uv)
+    pub workspace_root: bool,
+    // Listado nos membros do workspace de um diretório acima
+    pub workspace_member: bool,
+    pub own: PackageTotals,
+    pub totals: PackageTotals,
+    pub languages: Vec<LanguageStats>,
+    pub children: Vec<PackageNode>,
 }